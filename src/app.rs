@@ -0,0 +1,114 @@
+/// the simulator on top of the generic plot: the model's and UI's events, resources and systems
+/// the quantum layers (Ψ, V, expectation values) spawn the plot's series, which start with the
+/// imaginary part and the expectation values hidden
+use bevy::prelude::*;
+
+use crate::{
+    expectation::{
+        setup_expectation_history_curves, update_expectation_history, update_expectation_label,
+        ExpectationHistory, ExpectationValues, MOMENTUM_SERIES, POSITION_SERIES,
+    },
+    model::PotentialModelRegistry,
+    plot::{PlotPlugin, SeriesVisibility},
+    potential_plot::EnergyScale,
+    superposition::{
+        listen_superposition_ui_inputs, reset_superposition, update_superposition_label,
+        Superposition,
+    },
+    text_input::{edit_text_inputs, focus_text_inputs, update_text_input_labels},
+    time_evolution::{
+        listen_time_evolution_ui_inputs, reset_time_evolution_on_model_change,
+        update_time_evolution_labels, TimeEvolution,
+    },
+    ui::{
+        curve_toggle_button_handler, listen_curve_toggle_ui_inputs, listen_energy_level_ui_inputs,
+        listen_potential_model_ui_inputs, listen_ui_inputs, minus_button_handler,
+        parameter_slider_handler, plus_button_handler, potential_model_button_handler,
+        scroll_ui_column, segment_button_handler, setup_ui, superposition_button_handler,
+        time_evolution_button_handler, update_energy_level_label,
+        update_superposition_level_labels, update_superposition_rows,
+        well_placement_button_handler, CurveOptions, CurveToggleEvent, EnergyLevelRange,
+        ParameterInputEvent, PlusMinusInput, PlusMinusInputEvent, PotentialModelInputEvent,
+        SegmentInputEvent, SuperpositionInputEvent, TimeEvolutionInputEvent, UiInputsEvent,
+        WellPlacementInputEvent,
+    },
+    wave_function_plot::{CurveScales, IMAGINARY_SERIES},
+};
+
+/// plugins are bevy's `DefaultPlugins`, configurable to e.g. run without a window
+pub fn add_app(app: &mut App, plugins: impl PluginGroup) {
+    app.add_event::<UiInputsEvent>()
+        .add_event::<PlusMinusInputEvent>()
+        .add_event::<PotentialModelInputEvent>()
+        .add_event::<TimeEvolutionInputEvent>()
+        .add_event::<CurveToggleEvent>()
+        .add_event::<SuperpositionInputEvent>()
+        .add_event::<ParameterInputEvent>()
+        .add_event::<SegmentInputEvent>()
+        .add_event::<WellPlacementInputEvent>()
+        .insert_resource(PlusMinusInput::Plus)
+        .init_resource::<PotentialModelRegistry>()
+        .init_resource::<EnergyLevelRange>()
+        .init_resource::<TimeEvolution>()
+        .init_resource::<CurveOptions>()
+        .insert_resource(SeriesVisibility::hiding(&[
+            IMAGINARY_SERIES,
+            POSITION_SERIES,
+            MOMENTUM_SERIES,
+        ]))
+        .init_resource::<Superposition>()
+        .init_resource::<EnergyScale>()
+        .init_resource::<CurveScales>()
+        .init_resource::<ExpectationValues>()
+        .init_resource::<ExpectationHistory>()
+        .add_plugins(plugins)
+        .add_plugins(PlotPlugin)
+        .add_systems(Startup, setup_light)
+        .add_systems(
+            Update,
+            (
+                listen_ui_inputs,
+                update_energy_level_label,
+                plus_button_handler,
+                minus_button_handler,
+                listen_energy_level_ui_inputs,
+                potential_model_button_handler,
+                listen_potential_model_ui_inputs,
+                time_evolution_button_handler,
+                listen_time_evolution_ui_inputs,
+                reset_time_evolution_on_model_change,
+                update_time_evolution_labels,
+                curve_toggle_button_handler,
+                listen_curve_toggle_ui_inputs,
+                scroll_ui_column,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                superposition_button_handler,
+                listen_superposition_ui_inputs,
+                reset_superposition,
+                update_superposition_rows,
+                update_superposition_level_labels,
+                parameter_slider_handler,
+                focus_text_inputs,
+                edit_text_inputs,
+                update_text_input_labels,
+                update_expectation_label,
+                update_expectation_history,
+                setup_expectation_history_curves.after(update_expectation_history),
+                update_superposition_label,
+                segment_button_handler,
+                well_placement_button_handler,
+            ),
+        )
+        .add_systems(Startup, setup_ui);
+}
+
+fn setup_light(mut commands: Commands) {
+    commands.insert_resource(AmbientLight {
+        color: Color::WHITE,
+        brightness: 1.0,
+    });
+}
@@ -0,0 +1,365 @@
+/// ticks with labels on both axes, and the axes' titles
+/// the ticks follow the camera: the step is re-chosen for the visible part of an axis when zooming,
+/// the labels keep their size on the screen, and stay at its edge when their axis is panned out of view
+/// lengths are labeled in the SI prefixed unit that fits the step, other units in scientific notation
+use crate::camera_controller::CameraController;
+use bevy::{color::palettes::css::GREEN, prelude::*, sprite::Anchor};
+use uom::{
+    fmt::DisplayStyle,
+    si::{
+        f64::Length,
+        length::{self, femtometer, micrometer, millimeter, nanometer, picometer},
+    },
+    Conversion,
+};
+
+/// number of ticks aimed for on the visible part of an axis
+const TARGET_TICKS: f64 = 8.0;
+/// upper bound, for degenerate views
+const MAX_TICKS: i64 = 100;
+const LABEL_FONT_SIZE: f32 = 14.0;
+/// screen px between an axis and its labels
+const LABEL_OFFSET: f32 = 8.0;
+/// screen px left of the y axis kept for its labels, when it's moved to the screen's edge
+const Y_LABEL_WIDTH: f32 = 70.0;
+/// screen px covered by the UI column on the right
+pub const UI_COLUMN_WIDTH: f32 = 130.0;
+const TICK_LENGTH: f32 = 10.0;
+
+/// what the axes show, set by the plotted data's layer
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PlotAxes {
+    pub x: PlotAxis,
+    pub y: PlotAxis,
+}
+
+impl Default for PlotAxes {
+    fn default() -> Self {
+        Self {
+            x: PlotAxis {
+                symbol: "x",
+                unit: AxisUnit::Length,
+                screen_scale: 1.0,
+            },
+            y: PlotAxis {
+                symbol: "y",
+                unit: AxisUnit::Other(""),
+                screen_scale: 1.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotAxis {
+    /// of the quantity, in the title
+    pub symbol: &'static str,
+    pub unit: AxisUnit,
+    /// screen units per unit of the quantity
+    pub screen_scale: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisUnit {
+    /// in m, labeled with the SI prefix that fits the step
+    Length,
+    /// the unit's symbol, labeled in scientific notation
+    Other(&'static str),
+}
+
+impl PlotAxis {
+    /// (screen position, label) of about target ticks in [start, end] (screen units), and the title
+    pub fn ticks(&self, start: f32, end: f32, target: f64) -> (Vec<(f32, String)>, String) {
+        let start = start as f64 / self.screen_scale;
+        let end = end as f64 / self.screen_scale;
+        let step = TickStep::new(start, end, target);
+        let values = step.ticks(start, end);
+        let (labels, unit) = match self.unit {
+            AxisUnit::Length => length_tick_labels(&values, &step),
+            AxisUnit::Other(unit) => (
+                values.iter().map(|v| scientific_tick_label(*v)).collect(),
+                unit,
+            ),
+        };
+        let ticks = values
+            .iter()
+            .map(|v| (v * self.screen_scale) as f32)
+            .zip(labels)
+            .collect();
+        let title = if unit.is_empty() {
+            self.symbol.to_string()
+        } else {
+            format!("{} [{}]", self.symbol, unit)
+        };
+        (ticks, title)
+    }
+}
+
+/// spacing between ticks, mantissa × 10^exponent with a mantissa of 1, 2 or 5
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TickStep {
+    mantissa: i64,
+    exponent: i32,
+}
+
+impl TickStep {
+    /// the step giving about target ticks in [start, end]
+    pub fn new(start: f64, end: f64, target: f64) -> Self {
+        let raw = (end - start).abs() / target;
+        if !raw.is_normal() {
+            return Self {
+                mantissa: 1,
+                exponent: 0,
+            };
+        }
+        // tolerance for log10 of exact powers of 10 being slightly below the integer
+        let exponent = (raw.log10() + 1e-9).floor() as i32;
+        let normalized = raw / 10_f64.powi(exponent);
+        let (mantissa, exponent) = if normalized <= 1.0 + 1e-9 {
+            (1, exponent)
+        } else if normalized <= 2.0 {
+            (2, exponent)
+        } else if normalized <= 5.0 {
+            (5, exponent)
+        } else {
+            (1, exponent + 1)
+        };
+        Self { mantissa, exponent }
+    }
+
+    /// value of the tick at index, i.e. index × step
+    /// parsed from the decimal representation, so it's the f64 closest to it, which is formatted without noise
+    pub fn tick(&self, index: i64) -> f64 {
+        format!("{}e{}", index * self.mantissa, self.exponent)
+            .parse()
+            .unwrap_or(0.0)
+    }
+
+    pub fn value(&self) -> f64 {
+        self.tick(1)
+    }
+
+    /// the tick values in [start, end]
+    pub fn ticks(&self, start: f64, end: f64) -> Vec<f64> {
+        let step = self.value();
+        let first = (start / step).ceil();
+        let last = (end / step).floor();
+        if !(first.is_finite() && last.is_finite()) || last - first > MAX_TICKS as f64 {
+            return vec![];
+        }
+        (first as i64..=last as i64).map(|i| self.tick(i)).collect()
+    }
+}
+
+/// the labels of length ticks, with the unit's abbreviation for the axis title
+/// the unit is the largest one in which the step is at least 1, so the labels are integers
+pub fn length_tick_labels(values: &[f64], step: &TickStep) -> (Vec<String>, &'static str) {
+    let step = step.value();
+    if step >= 1.0 {
+        format_lengths(values, length::meter)
+    } else if step >= 1e-3 {
+        format_lengths(values, millimeter)
+    } else if step >= 1e-6 {
+        format_lengths(values, micrometer)
+    } else if step >= 1e-9 {
+        format_lengths(values, nanometer)
+    } else if step >= 1e-12 {
+        format_lengths(values, picometer)
+    } else {
+        format_lengths(values, femtometer)
+    }
+}
+
+fn format_lengths<N>(values: &[f64], unit: N) -> (Vec<String>, &'static str)
+where
+    N: length::Unit + Conversion<f64, T = f64>,
+{
+    let labels = values
+        .iter()
+        .map(|value| {
+            let length = Length::new::<length::meter>(*value);
+            format!(
+                "{:.0}",
+                length.into_format_args(unit, DisplayStyle::Abbreviation)
+            )
+        })
+        .collect();
+    (labels, N::abbreviation())
+}
+
+/// label of a value without a prefixed unit, e.g. Ψ in m^-1/2
+pub fn scientific_tick_label(value: f64) -> String {
+    if value == 0.0 {
+        "0".to_string()
+    } else {
+        format!("{:e}", value)
+    }
+}
+
+/// a tick label or axis title
+#[derive(Component)]
+pub struct AxisLabel;
+
+/// what an axis label shows and where, to only respawn the labels when they change
+#[derive(Debug, Clone, PartialEq)]
+pub struct AxisLabelSpec {
+    text: String,
+    /// world position
+    position: Vec2,
+    anchor: Anchor,
+}
+
+/// the part of the world shown on the screen, without what the UI covers
+struct View {
+    min: Vec2,
+    max: Vec2,
+    /// world units per screen px
+    px: f32,
+}
+
+impl View {
+    fn new(transform: &Transform, projection: &OrthographicProjection) -> Self {
+        let center = transform.translation.truncate();
+        let px = projection.scale;
+        Self {
+            min: center + projection.area.min,
+            max: center + projection.area.max - Vec2::new(UI_COLUMN_WIDTH * px, 0.0),
+            px,
+        }
+    }
+}
+
+/// draws the ticks of the visible part of the axes, and replaces their labels and the titles when they change
+pub fn setup_axis_ticks(
+    mut commands: Commands,
+    mut gizmos: Gizmos,
+    asset_server: Res<AssetServer>,
+    axes: Res<PlotAxes>,
+    camera_query: Query<(&Transform, &OrthographicProjection), With<CameraController>>,
+    label_query: Query<Entity, With<AxisLabel>>,
+    mut shown_labels: Local<Vec<AxisLabelSpec>>,
+) {
+    let Ok((transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    let view = View::new(transform, projection);
+    let tick_length = TICK_LENGTH * view.px;
+    let offset = LABEL_OFFSET * view.px;
+    // where the labels go, the axes or the screen's edges if they're out of view
+    let x_axis_y = 0_f32.clamp(
+        view.min.y + (LABEL_FONT_SIZE + 2.0 * LABEL_OFFSET) * view.px,
+        view.max.y.max(view.min.y),
+    );
+    let y_axis_x = 0_f32.clamp(
+        view.min.x + Y_LABEL_WIDTH * view.px,
+        view.max.x.max(view.min.x),
+    );
+
+    let mut labels = vec![];
+
+    let (ticks, title) = axes.x.ticks(view.min.x, view.max.x, TARGET_TICKS);
+    for (x, text) in ticks {
+        gizmos.line_2d(
+            Vec2::new(x, -tick_length / 2.0),
+            Vec2::new(x, tick_length / 2.0),
+            GREEN,
+        );
+        labels.push(AxisLabelSpec {
+            text,
+            position: Vec2::new(x, x_axis_y - offset),
+            anchor: Anchor::TopCenter,
+        });
+    }
+    labels.push(AxisLabelSpec {
+        text: title,
+        position: Vec2::new(view.max.x - offset, x_axis_y + offset),
+        anchor: Anchor::BottomRight,
+    });
+
+    let (ticks, title) = axes.y.ticks(view.min.y, view.max.y, TARGET_TICKS);
+    for (y, text) in ticks {
+        gizmos.line_2d(
+            Vec2::new(-tick_length / 2.0, y),
+            Vec2::new(tick_length / 2.0, y),
+            GREEN,
+        );
+        // the x axis' labels are at 0
+        if y != 0.0 {
+            labels.push(AxisLabelSpec {
+                text,
+                position: Vec2::new(y_axis_x - offset, y),
+                anchor: Anchor::CenterRight,
+            });
+        }
+    }
+    labels.push(AxisLabelSpec {
+        text: title,
+        position: Vec2::new(y_axis_x + offset, view.max.y - offset),
+        anchor: Anchor::TopLeft,
+    });
+
+    if *shown_labels == labels {
+        return;
+    }
+    for entity in label_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    for label in &labels {
+        commands.spawn((
+            AxisLabel,
+            Text2dBundle {
+                text: Text::from_section(
+                    label.text.clone(),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: LABEL_FONT_SIZE,
+                        color: GREEN.into(),
+                    },
+                ),
+                text_anchor: label.anchor,
+                // against the camera's scale, to keep the size on the screen
+                transform: Transform::from_translation(label.position.extend(1.0))
+                    .with_scale(Vec3::splat(view.px)),
+                ..default()
+            },
+        ));
+    }
+    *shown_labels = labels;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{length_tick_labels, scientific_tick_label, TickStep, TARGET_TICKS};
+
+    #[test]
+    fn tick_step_is_1_2_or_5_times_a_power_of_10() {
+        assert_eq!(1e-10, TickStep::new(-4e-10, 4e-10, TARGET_TICKS).value());
+        assert_eq!(2e-10, TickStep::new(-6e-10, 6e-10, TARGET_TICKS).value());
+        assert_eq!(5e-9, TickStep::new(0.0, 3e-8, TARGET_TICKS).value());
+        assert_eq!(1e5, TickStep::new(-3e5, 3e5, TARGET_TICKS).value());
+    }
+
+    #[test]
+    fn ticks_are_exact_multiples_of_the_step() {
+        let step = TickStep::new(0.0, 1.6, TARGET_TICKS);
+        assert_eq!(0.2, step.value());
+        let ticks = step.ticks(0.05, 0.7);
+        assert_eq!(vec![0.2, 0.4, 0.6], ticks);
+        assert_eq!("6e-1", scientific_tick_label(ticks[2]));
+        assert_eq!("0", scientific_tick_label(0.0));
+    }
+
+    #[test]
+    fn length_labels_use_the_step_prefix() {
+        let step = TickStep::new(-4e-10, 4e-10, TARGET_TICKS);
+        let (labels, unit) = length_tick_labels(&step.ticks(-2e-10, 1e-10), &step);
+        assert_eq!(vec!["-200 pm", "-100 pm", "0 pm", "100 pm"], labels);
+        assert_eq!("pm", unit);
+
+        let step = TickStep::new(0.0, 1.6e-8, TARGET_TICKS);
+        let (labels, unit) = length_tick_labels(&step.ticks(1e-9, 5e-9), &step);
+        assert_eq!(vec!["2 nm", "4 nm"], labels);
+        assert_eq!("nm", unit);
+    }
+}
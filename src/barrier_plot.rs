@@ -0,0 +1,292 @@
+/// rectangular barrier, a scattering model
+/// it plots Ψ(x) and PDF(x) of a plane wave with energy E incident from the left, selected via the UI
+/// below the barrier's height the wave tunnels through it, above it the transmission resonates with its width
+/// see https://en.wikipedia.org/wiki/Rectangular_potential_barrier
+use crate::{
+    model::{ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    plot::PlotSettings,
+    scattering::{energy_parameter, scattering_plot_settings, wave_number, Scattering},
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::{electronvolt, joule},
+    f64::{Energy, Length, Mass},
+    length::{meter, nanometer},
+    mass::kilogram,
+};
+
+/// barrier between x = 0 and x = width, V(x) = V₀ (height) there and 0 elsewhere
+#[derive(Resource)]
+pub struct Barrier {
+    pub energy: Energy,
+    pub height: Energy,
+    pub width: Length,
+    pub mass: Mass,
+}
+
+impl Default for Barrier {
+    fn default() -> Self {
+        Self {
+            energy: Energy::new::<electronvolt>(0.5),
+            height: Energy::new::<electronvolt>(1.0),
+            width: Length::new::<nanometer>(0.5),
+            mass: Mass::new::<kilogram>(9.11e-31),
+        }
+    }
+}
+
+/// the wave's amplitudes, with the wave numbers k outside and q inside of the barrier
+#[derive(Debug, Clone, Copy)]
+struct Amplitudes {
+    k: Complex64,
+    q: Complex64,
+    /// of the reflected wave r e^(-ikx)
+    r: Complex64,
+    /// of the transmitted wave t e^(ik(x - a))
+    t: Complex64,
+}
+
+impl Barrier {
+    /// from the continuity of Ψ and Ψ' at 0 and a, with c = cos(qa) and s = sin(qa)/q:
+    /// r = (k^2 - q^2)s / D, t = 2ik / D, with D = (k^2 + q^2)s + 2ikc
+    /// a barrier too thick for c and s to be represented reflects like a step, without transmission
+    fn amplitudes(&self, energy: Energy) -> Amplitudes {
+        let zero = Energy::new::<joule>(0.0);
+        let k = wave_number(self.mass, energy, zero);
+        let q = wave_number(self.mass, energy, self.height);
+        let a = self.width.get::<meter>();
+        let i = Complex64::i();
+        let c = (q * a).cos();
+        // sin(qa)/q → a for q → 0
+        let s = if q.norm() * a < 1e-8 {
+            Complex64::new(a, 0.0)
+        } else {
+            (q * a).sin() / q
+        };
+        let d = (k * k + q * q) * s + 2.0 * i * k * c;
+        if d.is_finite() {
+            Amplitudes {
+                k,
+                q,
+                r: (k * k - q * q) * s / d,
+                t: 2.0 * i * k / d,
+            }
+        } else {
+            Amplitudes {
+                k,
+                q,
+                r: (k - q) / (k + q),
+                t: Complex64::new(0.0, 0.0),
+            }
+        }
+    }
+}
+
+impl PotentialModel for Barrier {
+    fn name(&self) -> &'static str {
+        "Barrier"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            energy_parameter(self.energy),
+            ModelParameter {
+                name: "V₀",
+                value: self.height.get::<joule>(),
+                unit: "J",
+                range: ParameterRange::new(1.6e-22, 1.6e-17),
+            },
+            ModelParameter {
+                name: "a",
+                value: self.width.get::<meter>(),
+                unit: "m",
+                range: ParameterRange::new(1e-11, 1e-8),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.energy = Energy::new::<joule>(value),
+            1 => self.height = Energy::new::<joule>(value),
+            2 => self.width = Length::new::<meter>(value),
+            3 => self.mass = Mass::new::<kilogram>(value),
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    /// the same for all levels, it's the state of energy E
+    /// inside the barrier, Ψ = t (cos(q(x - a)) + ik sin(q(x - a))/q), which continues the transmitted wave at a
+    fn psi(&self, x: Length, _level: &EnergyLevel) -> Complex64 {
+        let x = x.get::<meter>();
+        let a = self.width.get::<meter>();
+        let Amplitudes { k, q, r, t } = self.amplitudes(self.energy);
+        let i = Complex64::i();
+        if x < 0.0 {
+            (i * k * x).exp() + r * (-i * k * x).exp()
+        } else if x <= a {
+            if t == Complex64::new(0.0, 0.0) {
+                // the step's transmitted wave
+                return (1.0 + r) * (i * q * x).exp();
+            }
+            let u = q * (x - a);
+            let sin_ratio = if u.norm() < 1e-8 {
+                Complex64::new(x - a, 0.0)
+            } else {
+                u.sin() / q
+            };
+            t * (u.cos() + i * k * sin_ratio)
+        } else {
+            t * (i * k * (x - a)).exp()
+        }
+    }
+
+    fn energy(&self, _level: &EnergyLevel) -> Energy {
+        self.energy
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        let x = x.get::<meter>();
+        if x >= 0.0 && x <= self.width.get::<meter>() {
+            self.height
+        } else {
+            Energy::new::<joule>(0.0)
+        }
+    }
+
+    fn energy_parameter(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    /// T = |t|^2, R = |r|^2, the medium being the same on both sides
+    fn scattering(&self, energy: Energy) -> Option<Scattering> {
+        let amplitudes = self.amplitudes(energy);
+        if amplitudes.k.re <= 0.0 {
+            return None;
+        }
+        Some(Scattering {
+            transmission: amplitudes.t.norm_sqr(),
+            reflection: amplitudes.r.norm_sqr(),
+        })
+    }
+
+    /// a few wave lengths at V₀ on each side of the barrier, or its width if it's wider,
+    /// such that the domain doesn't change with E
+    fn plot_settings(&self) -> PlotSettings {
+        let wave_length = 2.0 * PI * H_BAR
+            / (2.0 * self.mass.get::<kilogram>() * self.height.get::<joule>()).sqrt();
+        scattering_plot_settings(wave_length, 0.0, self.width.get::<meter>())
+    }
+
+    fn max_level(&self) -> u32 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+    use uom::si::{
+        energy::{electronvolt, joule},
+        f64::{Energy, Length, Mass},
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use super::Barrier;
+    use crate::{
+        model::{PotentialModel, H_BAR},
+        ui::EnergyLevel,
+    };
+
+    #[test]
+    fn psi_and_its_derivative_are_continuous_at_the_edges() {
+        for energy in [0.3, 1.0, 2.5] {
+            let barrier = Barrier {
+                energy: Energy::new::<electronvolt>(energy),
+                ..Barrier::default()
+            };
+            let psi = |x: f64| barrier.psi(Length::new::<meter>(x), &EnergyLevel(0));
+            let h = 1e-15;
+            for edge in [0.0, barrier.width.get::<meter>()] {
+                let difference = (psi(edge - h) - psi(edge + h)).norm();
+                assert!(difference < 1e-4, "Ψ jumps by {} at {}", difference, edge);
+                let left = (psi(edge - h) - psi(edge - 2.0 * h)) / h;
+                let right = (psi(edge + 2.0 * h) - psi(edge + h)) / h;
+                assert!((left - right).norm() < 1e-3 * left.norm().max(right.norm()));
+            }
+        }
+    }
+
+    #[test]
+    fn transmission_and_reflection_add_up_to_1() {
+        let barrier = Barrier::default();
+        for energy in [0.1, 0.5, 1.0, 1.5, 5.0] {
+            let scattering = barrier
+                .scattering(Energy::new::<electronvolt>(energy))
+                .unwrap();
+            assert_relative_eq!(
+                1.0,
+                scattering.transmission + scattering.reflection,
+                epsilon = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn transmission_resonates_above_the_barrier() {
+        let barrier = Barrier::default();
+        let m = barrier.mass.get::<kilogram>();
+        let a = barrier.width.get::<meter>();
+        // qa = nπ
+        for n in 1..4 {
+            let q = n as f64 * PI / a;
+            let energy = barrier.height.get::<joule>() + (H_BAR * q).powi(2) / (2.0 * m);
+            let scattering = barrier.scattering(Energy::new::<joule>(energy)).unwrap();
+            assert_relative_eq!(1.0, scattering.transmission, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn thick_barrier_tunneling_decays_exponentially() {
+        // T ≈ 16 E (V₀ - E) / V₀^2 e^(-2κa) for κa >> 1
+        let barrier = Barrier {
+            width: Length::new::<nanometer>(3.0),
+            ..Barrier::default()
+        };
+        let energy = barrier.energy.get::<joule>();
+        let height = barrier.height.get::<joule>();
+        let kappa = (2.0 * barrier.mass.get::<kilogram>() * (height - energy)).sqrt() / H_BAR;
+        let expected = 16.0 * energy * (height - energy) / height.powi(2)
+            * (-2.0 * kappa * barrier.width.get::<meter>()).exp();
+        let scattering = barrier.scattering(barrier.energy).unwrap();
+        assert_relative_eq!(expected, scattering.transmission, max_relative = 1e-3);
+
+        // beyond f64's range, like a step
+        let barrier = Barrier {
+            width: Length::new::<meter>(1e-8),
+            mass: Mass::new::<kilogram>(1e-26),
+            ..barrier
+        };
+        let scattering = barrier.scattering(barrier.energy).unwrap();
+        assert_eq!(0.0, scattering.transmission);
+        assert_relative_eq!(1.0, scattering.reflection, epsilon = 1e-12);
+        let psi = barrier.psi(Length::new::<meter>(5e-9), &EnergyLevel(0));
+        assert_eq!(Complex64::new(0.0, 0.0), psi);
+    }
+}
@@ -102,6 +102,7 @@ fn run_camera_controller(
     mut mouse_events: EventReader<MouseMotion>,
     mouse_button_input: Res<ButtonInput<MouseButton>>,
     key_input: Res<ButtonInput<KeyCode>>,
+    ui_query: Query<&Interaction>,
     mut toggle_cursor_grab: Local<bool>,
     mut mouse_cursor_grab: Local<bool>,
     mut query: Query<
@@ -156,7 +157,9 @@ fn run_camera_controller(
             *toggle_cursor_grab = !*toggle_cursor_grab;
             cursor_grab_change = true;
         }
-        if mouse_button_input.just_pressed(controller.mouse_key_cursor_grab) {
+        // pressing on the UI, e.g. to drag a slider, needs the cursor
+        let pressing_ui = ui_query.iter().any(|i| *i == Interaction::Pressed);
+        if mouse_button_input.just_pressed(controller.mouse_key_cursor_grab) && !pressing_ui {
             *mouse_cursor_grab = true;
             cursor_grab_change = true;
         }
@@ -0,0 +1,185 @@
+/// Crank–Nicolson propagator for the time dependent Schrödinger equation
+/// (1 + iHΔt/2ħ) Ψ(t + Δt) = (1 - iHΔt/2ħ) Ψ(t), with H the finite difference Hamiltonian
+/// it's unitary, so the norm is conserved, and stable for any Δt
+/// grid points where V isn't finite are walls, Ψ is kept 0 there, same as at the domain ends
+use crate::model::H_BAR;
+use num_complex::Complex64;
+
+pub struct CrankNicolson {
+    /// left side tridiagonal matrix (1 + iHΔt/2ħ), by rows
+    lower: Vec<Complex64>,
+    diagonal: Vec<Complex64>,
+    upper: Vec<Complex64>,
+    /// right side matrix (1 - iHΔt/2ħ) is the conjugate of the left side, as H is real
+    walls: Vec<bool>,
+}
+
+impl CrankNicolson {
+    /// potential (J) at the grid points, grid spacing (m), mass (kg), time step (s)
+    pub fn new(potential: &[f64], step: f64, mass: f64, dt: f64) -> Self {
+        // ħ^2/2m dx^2, dividing before multiplying to stay in a comfortable range
+        let kinetic = H_BAR / (2.0 * mass * step) * H_BAR / step;
+        // iΔt/2ħ
+        let alpha = Complex64::new(0.0, dt / (2.0 * H_BAR));
+
+        let walls: Vec<bool> = potential.iter().map(|v| !v.is_finite()).collect();
+        let n = potential.len();
+        let mut lower = vec![Complex64::new(0.0, 0.0); n];
+        let mut diagonal = vec![Complex64::new(1.0, 0.0); n];
+        let mut upper = vec![Complex64::new(0.0, 0.0); n];
+        for i in 0..n {
+            if walls[i] {
+                continue;
+            }
+            diagonal[i] = 1.0 + alpha * (2.0 * kinetic + potential[i]);
+            if i > 0 && !walls[i - 1] {
+                lower[i] = -alpha * kinetic;
+            }
+            if i < n - 1 && !walls[i + 1] {
+                upper[i] = -alpha * kinetic;
+            }
+        }
+
+        Self {
+            lower,
+            diagonal,
+            upper,
+            walls,
+        }
+    }
+
+    /// advances Ψ by one time step
+    pub fn step(&self, psi: &mut [Complex64]) {
+        let n = psi.len();
+        // right side: (1 - iHΔt/2ħ) Ψ
+        let rhs: Vec<Complex64> = (0..n)
+            .map(|i| {
+                if self.walls[i] {
+                    return Complex64::new(0.0, 0.0);
+                }
+                let mut value = self.diagonal[i].conj() * psi[i];
+                if i > 0 {
+                    value += self.lower[i].conj() * psi[i - 1];
+                }
+                if i < n - 1 {
+                    value += self.upper[i].conj() * psi[i + 1];
+                }
+                value
+            })
+            .collect();
+
+        solve_tridiagonal(&self.lower, &self.diagonal, &self.upper, &rhs, psi);
+    }
+}
+
+/// Thomas algorithm, writes the solution to result
+/// doesn't pivot, fine here as the matrix is diagonally dominant
+fn solve_tridiagonal(
+    lower: &[Complex64],
+    diagonal: &[Complex64],
+    upper: &[Complex64],
+    rhs: &[Complex64],
+    result: &mut [Complex64],
+) {
+    let n = diagonal.len();
+    let mut c = vec![Complex64::new(0.0, 0.0); n];
+    let mut d = vec![Complex64::new(0.0, 0.0); n];
+    for i in 0..n {
+        let (previous_c, previous_d) = if i == 0 {
+            (Complex64::new(0.0, 0.0), Complex64::new(0.0, 0.0))
+        } else {
+            (c[i - 1], d[i - 1])
+        };
+        let denominator = diagonal[i] - lower[i] * previous_c;
+        c[i] = upper[i] / denominator;
+        d[i] = (rhs[i] - lower[i] * previous_d) / denominator;
+    }
+    for i in (0..n).rev() {
+        result[i] = if i == n - 1 {
+            d[i]
+        } else {
+            d[i] - c[i] * result[i + 1]
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use uom::si::mass::kilogram;
+
+    use crate::{
+        finite_difference_solver::{interior_grid, solve},
+        harmonic_oscillator_plot::HarmonicOscillator,
+        model::{PotentialModel, H_BAR},
+        propagator_test_setup::{norm, potential, HarmonicOscillatorSetup},
+        time_evolution::gaussian_wave_packet,
+    };
+
+    use super::CrankNicolson;
+
+    fn mean_x(psi: &[Complex64], grid: &[f64], step: f64) -> f64 {
+        psi.iter()
+            .zip(grid)
+            .map(|(p, x)| p.norm_sqr() * x)
+            .sum::<f64>()
+            * step
+    }
+
+    #[test]
+    fn conserves_norm() {
+        let setup = HarmonicOscillatorSetup::default();
+        let mut psi = setup.packet();
+        let propagator = CrankNicolson::new(&setup.potential, setup.step, setup.mass, 1e-18);
+        for _ in 0..500 {
+            propagator.step(&mut psi);
+        }
+        assert_relative_eq!(1.0, norm(&psi, setup.step), max_relative = 1e-6);
+    }
+
+    #[test]
+    fn free_packet_moves_with_group_velocity() {
+        let (grid, step) = interior_grid(&crate::plot::PlotSettings {
+            domain_range_start: -1e-9,
+            domain_range_end: 1e-9,
+            sample_step: 1e-12,
+            ..Default::default()
+        });
+        let potential = vec![0.0; grid.len()];
+        let mass = 9.11e-31;
+        let k0 = 5e9;
+
+        let mut psi = gaussian_wave_packet(&grid, -3e-10, 5e-11, k0);
+        let dt = 1e-18;
+        let steps = 200;
+        let propagator = CrankNicolson::new(&potential, step, mass, dt);
+        for _ in 0..steps {
+            propagator.step(&mut psi);
+        }
+
+        let velocity = H_BAR * k0 / mass;
+        let expected = -3e-10 + velocity * dt * steps as f64;
+        assert_relative_eq!(expected, mean_x(&psi, &grid, step), max_relative = 1e-2);
+    }
+
+    #[test]
+    fn stationary_state_only_gains_phase() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let spectrum = solve(&settings, model.mass(), |x| model.potential(x), 1);
+        let state = &spectrum.states[0];
+        let potential = potential(&model, &spectrum.grid);
+        let mass = model.mass().get::<kilogram>();
+
+        let mut psi: Vec<Complex64> = state.psi.iter().map(|p| Complex64::new(*p, 0.0)).collect();
+        let propagator = CrankNicolson::new(&potential, spectrum.step, mass, 1e-19);
+        for _ in 0..100 {
+            propagator.step(&mut psi);
+        }
+        let max = state.psi.iter().fold(0.0_f64, |acc, p| acc.max(p.abs()));
+        for (evolved, initial) in psi.iter().zip(&state.psi) {
+            assert!((evolved.norm() - initial.abs()).abs() < 1e-4 * max);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+/// repulsive δ barrier, V(x) = α δ(x), a scattering model
+/// it plots Ψ(x) and PDF(x) of a plane wave with energy E incident from the left, selected via the UI
+/// Ψ is continuous at the barrier and Ψ' jumps by 2gΨ(0), with g = mα/ħ^2, so Ψ has a cusp there
+/// the wave tunnels with T = 1/(1 + mα^2/2ħ^2E), which approaches 1 for E much above mα^2/2ħ^2
+/// see https://en.wikipedia.org/wiki/Delta_potential
+use crate::{
+    model::{Delta, ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    plot::PlotSettings,
+    scattering::{energy_parameter, wave_number, Scattering, DOMAIN_WAVE_LENGTHS},
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::{electronvolt, joule},
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+#[derive(Resource)]
+pub struct DeltaBarrier {
+    pub energy: Energy,
+    /// α (J·m), > 0
+    pub strength: f64,
+    pub mass: Mass,
+}
+
+/// transmits half at about 1 eV for an electron
+impl Default for DeltaBarrier {
+    fn default() -> Self {
+        Self {
+            energy: Energy::new::<electronvolt>(0.5),
+            strength: 6.25e-29,
+            mass: Mass::new::<kilogram>(9.11e-31),
+        }
+    }
+}
+
+impl DeltaBarrier {
+    /// g = mα/ħ^2 (1/m)
+    fn coupling(&self) -> f64 {
+        self.mass.get::<kilogram>() * self.strength / H_BAR.powi(2)
+    }
+
+    /// the wave number k and the amplitudes of the reflected and transmitted waves,
+    /// from the continuity of Ψ and the jump of Ψ' at 0: r = -ig/(k + ig), t = k/(k + ig)
+    fn amplitudes(&self, energy: Energy) -> (Complex64, Complex64, Complex64) {
+        let k = wave_number(self.mass, energy, Energy::new::<joule>(0.0));
+        let ig = Complex64::new(0.0, self.coupling());
+        (k, -ig / (k + ig), k / (k + ig))
+    }
+}
+
+impl PotentialModel for DeltaBarrier {
+    fn name(&self) -> &'static str {
+        "δ barrier"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            energy_parameter(self.energy),
+            ModelParameter {
+                name: "α",
+                value: self.strength,
+                unit: "J·m",
+                range: ParameterRange::new(1e-30, 1e-27),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.energy = Energy::new::<joule>(value),
+            1 => self.strength = value,
+            2 => self.mass = Mass::new::<kilogram>(value),
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    /// the same for all levels, it's the state of energy E
+    fn psi(&self, x: Length, _level: &EnergyLevel) -> Complex64 {
+        let x = x.get::<meter>();
+        let (k, r, t) = self.amplitudes(self.energy);
+        let i = Complex64::i();
+        if x < 0.0 {
+            (i * k * x).exp() + r * (-i * k * x).exp()
+        } else {
+            t * (i * k * x).exp()
+        }
+    }
+
+    fn energy(&self, _level: &EnergyLevel) -> Energy {
+        self.energy
+    }
+
+    /// 0, the barrier is the δ
+    fn potential(&self, _x: Length) -> Energy {
+        Energy::new::<joule>(0.0)
+    }
+
+    fn deltas(&self) -> Vec<Delta> {
+        vec![Delta {
+            position: Length::new::<meter>(0.0),
+            strength: self.strength,
+        }]
+    }
+
+    fn energy_parameter(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    /// T = |t|^2 = 1/(1 + mα^2/2ħ^2E), R = |r|^2
+    fn scattering(&self, energy: Energy) -> Option<Scattering> {
+        let (k, r, t) = self.amplitudes(energy);
+        if k.re <= 0.0 {
+            return None;
+        }
+        Some(Scattering {
+            transmission: t.norm_sqr(),
+            reflection: r.norm_sqr(),
+        })
+    }
+
+    /// a few wave lengths at mα^2/2ħ^2 (k = g) on each side of the barrier, such that the domain doesn't change with E
+    fn plot_settings(&self) -> PlotSettings {
+        let wave_length = 2.0 * PI / self.coupling();
+        let half_extent = DOMAIN_WAVE_LENGTHS * wave_length;
+        PlotSettings {
+            domain_range_start: -half_extent,
+            domain_range_end: half_extent,
+            sample_step: wave_length / 200.0,
+            screen_scale_x: 5.0 / half_extent,
+            ..PlotSettings::default()
+        }
+    }
+
+    fn max_level(&self) -> u32 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::{electronvolt, joule},
+        f64::{Energy, Length},
+        length::meter,
+    };
+
+    use super::DeltaBarrier;
+    use crate::{barrier_plot::Barrier, model::PotentialModel, ui::EnergyLevel};
+
+    #[test]
+    fn transmission_is_analytic() {
+        let barrier = DeltaBarrier::default();
+        let half = barrier.deltas()[0].energy(barrier.mass);
+        for factor in [0.1, 1.0, 10.0] {
+            let energy = half * factor;
+            let scattering = barrier.scattering(energy).unwrap();
+            assert_relative_eq!(1.0 / (1.0 + 1.0 / factor), scattering.transmission);
+            assert_relative_eq!(
+                1.0,
+                scattering.transmission + scattering.reflection,
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn thin_barriers_approach_the_delta() {
+        let delta = DeltaBarrier::default();
+        // V₀a = α
+        let width = 1e-12;
+        let barrier = Barrier {
+            energy: delta.energy,
+            height: Energy::new::<joule>(delta.strength / width),
+            width: Length::new::<meter>(width),
+            mass: delta.mass,
+        };
+        for energy in [0.1, 0.5, 2.0] {
+            let energy = Energy::new::<electronvolt>(energy);
+            assert_relative_eq!(
+                barrier.scattering(energy).unwrap().transmission,
+                delta.scattering(energy).unwrap().transmission,
+                max_relative = 1e-2
+            );
+        }
+
+        // Ψ is continuous with a cusp
+        let psi = |x: f64| delta.psi(Length::new::<meter>(x), &EnergyLevel(0));
+        let h = 1e-15;
+        assert!((psi(-h) - psi(h)).norm() < 1e-4);
+        let jump = (psi(2.0 * h) - psi(h)) / h - (psi(-h) - psi(-2.0 * h)) / h;
+        assert_relative_eq!(
+            (2.0 * delta.coupling() * psi(0.0)).norm(),
+            jump.norm(),
+            max_relative = 1e-3
+        );
+    }
+}
@@ -0,0 +1,188 @@
+/// attractive δ well, V(x) = -α δ(x)
+/// it has a single bound state, Ψ = √κ e^(-κ|x|) with κ = mα/ħ^2, whose energy is E = -mα^2/2ħ^2
+/// Ψ' jumps by -2κΨ(0) at the well, so Ψ has a cusp there
+/// see https://en.wikipedia.org/wiki/Delta_potential
+use crate::{
+    model::{Delta, ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    plot::PlotSettings,
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass, Momentum},
+    length::meter,
+    mass::kilogram,
+    momentum::kilogram_meter_per_second,
+};
+
+/// decay lengths 1/κ on each side of the well on the plot's domain
+const TAIL_DECAY_LENGTHS: f64 = 6.0;
+
+#[derive(Resource)]
+pub struct DeltaWell {
+    /// α (J·m), > 0
+    pub strength: f64,
+    pub mass: Mass,
+}
+
+/// E ≈ -1 eV for an electron
+impl Default for DeltaWell {
+    fn default() -> Self {
+        Self {
+            strength: 6.25e-29,
+            mass: Mass::new::<kilogram>(9.11e-31),
+        }
+    }
+}
+
+impl DeltaWell {
+    /// κ = mα/ħ^2 (1/m), the decay constant of the bound state
+    fn kappa(&self) -> f64 {
+        self.mass.get::<kilogram>() * self.strength / H_BAR.powi(2)
+    }
+}
+
+impl PotentialModel for DeltaWell {
+    fn name(&self) -> &'static str {
+        "δ well"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            ModelParameter {
+                name: "α",
+                value: self.strength,
+                unit: "J·m",
+                range: ParameterRange::new(1e-30, 1e-27),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.strength = value,
+            1 => self.mass = Mass::new::<kilogram>(value),
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        if level.0 != 0 {
+            return Complex64::new(0.0, 0.0);
+        }
+        let kappa = self.kappa();
+        Complex64::new(kappa.sqrt() * (-kappa * x.get::<meter>().abs()).exp(), 0.0)
+    }
+
+    /// φ(p) = √κ/√(2πħ) 2κ/(κ^2 + (p/ħ)^2), a lorentzian
+    fn phi(&self, p: Momentum, level: &EnergyLevel) -> Option<Complex64> {
+        if level.0 != 0 {
+            return Some(Complex64::new(0.0, 0.0));
+        }
+        let kappa = self.kappa();
+        let k = p.get::<kilogram_meter_per_second>() / H_BAR;
+        let phi = (kappa / (2.0 * PI * H_BAR)).sqrt() * 2.0 * kappa / (kappa.powi(2) + k.powi(2));
+        Some(Complex64::new(phi, 0.0))
+    }
+
+    /// E = -mα^2/2ħ^2, there's no level above it
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        if level.0 == 0 {
+            -self.deltas()[0].energy(self.mass)
+        } else {
+            Energy::new::<joule>(0.0)
+        }
+    }
+
+    /// 0, the well is the δ
+    fn potential(&self, _x: Length) -> Energy {
+        Energy::new::<joule>(0.0)
+    }
+
+    fn deltas(&self) -> Vec<Delta> {
+        vec![Delta {
+            position: Length::new::<meter>(0.0),
+            strength: -self.strength,
+        }]
+    }
+
+    /// scaled with the decay length, such that the tails always span the screen
+    fn plot_settings(&self) -> PlotSettings {
+        let half_extent = TAIL_DECAY_LENGTHS / self.kappa();
+        PlotSettings {
+            domain_range_start: -half_extent,
+            domain_range_end: half_extent,
+            sample_step: half_extent / 1000.0,
+            screen_scale_x: 5.0 / half_extent,
+            ..PlotSettings::default()
+        }
+    }
+
+    fn max_level(&self) -> u32 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::electronvolt,
+        f64::{Length, Momentum},
+        length::meter,
+        momentum::kilogram_meter_per_second,
+    };
+
+    use super::DeltaWell;
+    use crate::{model::PotentialModel, ui::EnergyLevel, validation::Grid};
+
+    #[test]
+    fn bound_state_is_normalized_with_a_cusp() {
+        let well = DeltaWell::default();
+        let level = EnergyLevel(0);
+        assert_relative_eq!(
+            -1.0,
+            well.energy(&level).get::<electronvolt>(),
+            max_relative = 0.05
+        );
+
+        let settings = well.plot_settings();
+        let grid = Grid::new(&settings);
+        assert_relative_eq!(1.0, grid.norm(&well, &level), max_relative = 1e-3);
+
+        // Ψ'(0+) - Ψ'(0-) = -2κΨ(0)
+        let psi = |x: f64| well.psi(Length::new::<meter>(x), &level).re;
+        let h = settings.sample_step * 1e-3;
+        let jump = (psi(h) - psi(0.0)) / h - (psi(0.0) - psi(-h)) / h;
+        assert_relative_eq!(-2.0 * well.kappa() * psi(0.0), jump, max_relative = 1e-2);
+    }
+
+    #[test]
+    fn momentum_distribution_is_normalized() {
+        let well = DeltaWell::default();
+        let settings = well.momentum_plot_settings();
+        let level = EnergyLevel(0);
+        let mut norm = 0.0;
+        let mut p = settings.domain_range_start;
+        while p <= settings.domain_range_end {
+            let momentum = Momentum::new::<kilogram_meter_per_second>(p);
+            norm += well.phi(momentum, &level).unwrap().norm_sqr() * settings.sample_step;
+            p += settings.sample_step;
+        }
+        // the lorentzian's tails beyond the domain are left out
+        assert_relative_eq!(1.0, norm, max_relative = 2e-2);
+    }
+}
@@ -0,0 +1,303 @@
+/// two attractive δ wells at x = ±d/2, V(x) = -α (δ(x + d/2) + δ(x - d/2))
+/// like the H2+ ion in 1D: the lone wells' states combine into an even (bonding) state, below the lone well's level,
+/// and an odd (antibonding) one above it, which is only bound while the wells are close enough, gd > 1
+/// with g = mα/ħ^2, the decay constants κ of the even and odd states solve κ = g(1 ± e^(-κd))
+/// see https://en.wikipedia.org/wiki/Delta_potential#Double_delta_potential
+use crate::{
+    model::{Delta, ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    numerics::bisect,
+    plot::PlotSettings,
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass},
+    length::{meter, nanometer},
+    mass::kilogram,
+};
+
+/// decay lengths 1/κ of the least bound state beyond the wells on the plot's domain
+const TAIL_DECAY_LENGTHS: f64 = 6.0;
+
+#[derive(Resource)]
+pub struct DoubleDeltaWell {
+    /// α (J·m) of each well, > 0
+    strength: f64,
+    /// d, between the wells
+    separation: Length,
+    mass: Mass,
+    /// solved bound states, the even one and the odd one if it's bound
+    bound_states: Vec<BoundState>,
+}
+
+/// a solved bound state
+#[derive(Debug, Clone, Copy)]
+struct BoundState {
+    /// decay constant (1/m)
+    kappa: f64,
+    even: bool,
+    /// of Ψ scaled by e^(-κd/2), √ m^-1/2
+    normalization: f64,
+}
+
+/// the lone wells' levels at about -1 eV for an electron
+impl Default for DoubleDeltaWell {
+    fn default() -> Self {
+        Self::new(
+            6.25e-29,
+            Length::new::<nanometer>(0.5),
+            Mass::new::<kilogram>(9.11e-31),
+        )
+    }
+}
+
+impl DoubleDeltaWell {
+    pub fn new(strength: f64, separation: Length, mass: Mass) -> Self {
+        let g = mass.get::<kilogram>() * strength / H_BAR.powi(2);
+        Self {
+            strength,
+            separation,
+            mass,
+            bound_states: solve_bound_states(g, separation.get::<meter>()),
+        }
+    }
+
+    fn half_separation(&self) -> f64 {
+        self.separation.get::<meter>() / 2.0
+    }
+}
+
+impl PotentialModel for DoubleDeltaWell {
+    fn name(&self) -> &'static str {
+        "Double δ well"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            ModelParameter {
+                name: "α",
+                value: self.strength,
+                unit: "J·m",
+                range: ParameterRange::new(1e-30, 1e-27),
+            },
+            ModelParameter {
+                name: "d",
+                value: self.separation.get::<meter>(),
+                unit: "m",
+                range: ParameterRange::new(1e-11, 1e-8),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    /// the bound states are solved again
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        let (mut strength, mut separation, mut mass) = (self.strength, self.separation, self.mass);
+        match index {
+            0 => strength = value,
+            1 => separation = Length::new::<meter>(value),
+            2 => mass = Mass::new::<kilogram>(value),
+            _ => return,
+        }
+        *self = Self::new(strength, separation, mass);
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    /// cosh(κx) (even) or sinh(κx) (odd) between the wells, decaying as e^(-κ|x|) beyond them,
+    /// written with e^(-κd/2) factored out, so it doesn't overflow for strong or distant wells
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        let Some(state) = self.bound_states.get(level.0 as usize) else {
+            return Complex64::new(0.0, 0.0);
+        };
+        let x = x.get::<meter>();
+        let a = self.half_separation();
+        let kappa = state.kappa;
+        let sign = if state.even { 1.0 } else { -1.0 };
+        // cosh(κx) or sinh(κx), times e^(-κa)
+        let inner = |x: f64| ((kappa * (x - a)).exp() + sign * (-kappa * (x + a)).exp()) / 2.0;
+        let psi = if x.abs() <= a {
+            inner(x)
+        } else {
+            let side = if x > 0.0 { 1.0 } else { sign };
+            side * inner(a) * (-kappa * (x.abs() - a)).exp()
+        };
+        Complex64::new(state.normalization * psi, 0.0)
+    }
+
+    /// E = -ħ^2κ^2/2m, 0 for levels that aren't bound
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        let energy = match self.bound_states.get(level.0 as usize) {
+            Some(state) => -(H_BAR * state.kappa).powi(2) / (2.0 * self.mass.get::<kilogram>()),
+            None => 0.0,
+        };
+        Energy::new::<joule>(energy)
+    }
+
+    /// 0, the wells are the δs
+    fn potential(&self, _x: Length) -> Energy {
+        Energy::new::<joule>(0.0)
+    }
+
+    fn deltas(&self) -> Vec<Delta> {
+        let a = self.half_separation();
+        [-a, a]
+            .iter()
+            .map(|x| Delta {
+                position: Length::new::<meter>(*x),
+                strength: -self.strength,
+            })
+            .collect()
+    }
+
+    /// the wells are 5 screen units apart at most, less if the tails of the least bound state reach farther
+    /// the sample step divides the half separation and the tails, so the wells are on the sample points,
+    /// unless they're within a step
+    fn plot_settings(&self) -> PlotSettings {
+        let a = self.half_separation();
+        let tail = self
+            .bound_states
+            .last()
+            .map(|state| TAIL_DECAY_LENGTHS / state.kappa)
+            .unwrap_or(a);
+        let mut step = (a + tail) / 1000.0;
+        let steps = (a / step).round();
+        if steps >= 1.0 {
+            step = a / steps;
+        }
+        let half_extent = a + (tail / step).ceil() * step;
+        PlotSettings {
+            domain_range_start: -half_extent,
+            domain_range_end: half_extent,
+            sample_step: step,
+            screen_scale_x: (2.5 / a).min(5.0 / half_extent),
+            ..PlotSettings::default()
+        }
+    }
+
+    fn max_level(&self) -> u32 {
+        self.bound_states.len() as u32 - 1
+    }
+
+    fn info(&self) -> Option<String> {
+        if self.bound_states.len() < 2 {
+            return Some("antibonding state unbound (gd < 1)".to_string());
+        }
+        let splitting = self.energy(&EnergyLevel(1)) - self.energy(&EnergyLevel(0));
+        Some(format!(
+            "bonding-antibonding splitting: {:.2e} J",
+            splitting.get::<joule>()
+        ))
+    }
+}
+
+/// the even state, with κ in [g, 2g], and the odd one, with κ in (0, g), if gd > 1
+/// the conditions come from the jump of Ψ' by -2gΨ at the wells
+fn solve_bound_states(g: f64, d: f64) -> Vec<BoundState> {
+    let mut states = vec![];
+    let even = bisect(|kappa| kappa - g * (1.0 + (-kappa * d).exp()), g, 2.0 * g);
+    states.push(BoundState {
+        kappa: even,
+        even: true,
+        normalization: calculate_normalization_constant(even, d / 2.0, true),
+    });
+    if g * d > 1.0 {
+        // divided by κ, as the condition's root at κ = 0 is spurious, with exp_m1 for its precision at small κ
+        let odd = bisect(
+            |kappa| 1.0 + g * (-kappa * d).exp_m1() / kappa,
+            1e-12 * g,
+            g,
+        );
+        states.push(BoundState {
+            kappa: odd,
+            even: false,
+            normalization: calculate_normalization_constant(odd, d / 2.0, false),
+        });
+    }
+    states
+}
+
+/// such that ∫|Ψ|^2 = 1, with Ψ scaled by e^(-κa), a being the half separation
+/// between the wells: ∫cosh^2 or ∫sinh^2 over [-a, a], beyond them: 2 exponential tails
+fn calculate_normalization_constant(kappa: f64, a: f64, even: bool) -> f64 {
+    let sign = if even { 1.0 } else { -1.0 };
+    let decay = (-2.0 * kappa * a).exp();
+    let inner = (1.0 - decay.powi(2)) / (4.0 * kappa) + sign * a * decay;
+    let edge = (1.0 + sign * decay) / 2.0;
+    let tails = edge.powi(2) / kappa;
+    1.0 / (inner + tails).sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        f64::Length,
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use super::DoubleDeltaWell;
+    use crate::{
+        delta_well_plot::DeltaWell,
+        model::{PotentialModel, H_BAR},
+        ui::EnergyLevel,
+    };
+
+    #[test]
+    fn levels_split_around_the_lone_well() {
+        let lone = DeltaWell::default();
+        let lone_energy = lone.energy(&EnergyLevel(0));
+        let well = DoubleDeltaWell::default();
+        assert_eq!(1, well.max_level());
+        let bonding = well.energy(&EnergyLevel(0));
+        let antibonding = well.energy(&EnergyLevel(1));
+        assert!(bonding < lone_energy && lone_energy < antibonding);
+
+        // far apart, both approach the lone well's level
+        let far = DoubleDeltaWell::new(lone.strength, Length::new::<nanometer>(5.0), lone.mass);
+        for level in [EnergyLevel(0), EnergyLevel(1)] {
+            assert_relative_eq!(
+                lone_energy.value,
+                far.energy(&level).value,
+                max_relative = 1e-6
+            );
+        }
+
+        // close together, the odd state isn't bound, and the even one approaches the well of strength 2α
+        let close = DoubleDeltaWell::new(lone.strength, Length::new::<meter>(1e-11), lone.mass);
+        assert_eq!(0, close.max_level());
+        assert!(close.info().unwrap().contains("unbound"));
+        assert_relative_eq!(
+            4.0 * lone_energy.value,
+            close.energy(&EnergyLevel(0)).value,
+            max_relative = 0.2
+        );
+    }
+
+    #[test]
+    fn psi_has_cusps_at_the_wells() {
+        let well = DoubleDeltaWell::default();
+        let a = well.half_separation();
+        let g = well.mass.get::<kilogram>() * well.strength / H_BAR.powi(2);
+        for level in [EnergyLevel(0), EnergyLevel(1)] {
+            let psi = |x: f64| well.psi(Length::new::<meter>(x), &level).re;
+            let h = a * 1e-6;
+            for x in [-a, a] {
+                assert!((psi(x - h) - psi(x + h)).abs() < 1e-3 * psi(x).abs());
+                // Ψ'(x+) - Ψ'(x-) = -2gΨ(x)
+                let jump = (psi(x + h) - psi(x)) / h - (psi(x) - psi(x - h)) / h;
+                assert_relative_eq!(-2.0 * g * psi(x), jump, max_relative = 1e-3);
+            }
+        }
+    }
+}
@@ -0,0 +1,541 @@
+/// expectation values of the shown state: ⟨x⟩, ⟨x^2⟩, ⟨p⟩, ⟨p^2⟩ and ⟨H⟩, with the uncertainties Δx, Δp
+/// integrated numerically over Ψ sampled on a uniform grid, with p = -iħ d/dx as finite differences
+/// they're listed on a panel in the UI column, and ⟨x⟩ (⟨p⟩ in momentum space) is a line on the plot,
+/// which follows the wave packet during the time evolution
+/// during the time evolution, ⟨x⟩(t) and ⟨p⟩(t) are plotted on the inset, shown with the same toggles
+use crate::{
+    axis::{AxisUnit, PlotAxes, PlotAxis},
+    inset::{InsetAxes, InsetSeries, INSET_HEIGHT, INSET_WIDTH},
+    model::{ModelPlotSettings, PotentialModel, H_BAR},
+    plot::{LineStyle, PlotSettings, Series, SeriesId, SeriesVisibility},
+    superposition::Superposition,
+    time_evolution::TimeEvolution,
+    ui::{despawn_all_entities, CurveOptions, ExpectationLabelMarker, PotentialModelInput},
+};
+use bevy::{
+    color::palettes::tailwind::{FUCHSIA_400, LIME_400},
+    prelude::*,
+};
+use num_complex::Complex64;
+use uom::si::{energy::joule, f64::Length, length::meter, mass::kilogram};
+
+pub const EXPECTATION_COLOR: Srgba = LIME_400;
+/// of ⟨p⟩(t) on the inset, next to ⟨x⟩(t) in the expectation color
+pub const MOMENTUM_HISTORY_COLOR: Srgba = FUCHSIA_400;
+
+pub const POSITION_SERIES: SeriesId = SeriesId("⟨x⟩");
+pub const MOMENTUM_SERIES: SeriesId = SeriesId("⟨p⟩");
+
+/// samples kept of the history, it's thinned out when full, so it spans the whole evolution
+const HISTORY_LENGTH: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Expectations {
+    /// ⟨x⟩ (m)
+    pub position: f64,
+    /// ⟨x^2⟩ (m^2)
+    pub position_squared: f64,
+    /// ⟨p⟩ (kg·m/s)
+    pub momentum: f64,
+    /// ⟨p^2⟩ ((kg·m/s)^2)
+    pub momentum_squared: f64,
+    /// ⟨H⟩ (J)
+    pub energy: f64,
+}
+
+impl Expectations {
+    /// of Ψ at start + j step (m), in the potential V (J) at the same points, for a particle of mass (kg)
+    /// Ψ is normalized here, it's 0 where V isn't finite (walls) and beyond the grid
+    /// None for Ψ = 0
+    pub fn new(
+        psi: &[Complex64],
+        start: f64,
+        step: f64,
+        potential: &[f64],
+        mass: f64,
+    ) -> Option<Self> {
+        let zero = Complex64::new(0.0, 0.0);
+        let psi: Vec<Complex64> = psi
+            .iter()
+            .zip(potential)
+            .map(|(psi, v)| if v.is_finite() { *psi } else { zero })
+            .collect();
+        let norm: f64 = psi.iter().map(|p| p.norm_sqr()).sum::<f64>() * step;
+        if !(norm > 0.0 && norm.is_finite()) {
+            return None;
+        }
+        // ∫ f |Ψ|^2 dx
+        let integrate = |f: &dyn Fn(usize) -> f64| -> f64 {
+            psi.iter()
+                .enumerate()
+                .map(|(j, p)| f(j) * p.norm_sqr())
+                .sum::<f64>()
+                * step
+                / norm
+        };
+        let x = |j: usize| start + j as f64 * step;
+
+        // ⟨p⟩ = ∫ Ψ* (-iħ dΨ/dx) dx, with central differences
+        let momentum = H_BAR
+            * (0..psi.len() as isize)
+                .map(|j| (at(&psi, j).conj() * (at(&psi, j + 1) - at(&psi, j - 1))).im / 2.0)
+                .sum::<f64>()
+            / norm;
+        // ⟨p^2⟩ = ⟨p⟩^2 + ħ^2 ∫ |dΦ/dx|^2 dx, with Φ = Ψ e^(-i⟨p⟩x/ħ) centered at p = 0, so the differences
+        // are taken on the envelope instead of the carrier, with forward differences including the steps to 0 beyond the grid
+        let centered: Vec<Complex64> = psi
+            .iter()
+            .enumerate()
+            .map(|(j, psi)| psi * Complex64::from_polar(1.0, -momentum * x(j) / H_BAR))
+            .collect();
+        let momentum_squared = momentum.powi(2)
+            + H_BAR.powi(2)
+                * (-1..psi.len() as isize)
+                    .map(|j| (at(&centered, j + 1) - at(&centered, j)).norm_sqr())
+                    .sum::<f64>()
+                / step
+                / norm;
+        let potential_energy = integrate(&|j| {
+            let v = potential[j];
+            if v.is_finite() {
+                v
+            } else {
+                0.0
+            }
+        });
+
+        Some(Self {
+            position: integrate(&|j| x(j)),
+            position_squared: integrate(&|j| x(j).powi(2)),
+            momentum,
+            momentum_squared,
+            energy: momentum_squared / (2.0 * mass) + potential_energy,
+        })
+    }
+
+    /// of the superposition, sampled with the settings' step
+    pub fn of_superposition<M: PotentialModel>(
+        model: &M,
+        superposition: &Superposition,
+        settings: &PlotSettings,
+    ) -> Option<Self> {
+        let samples = superposition.sample(model, settings);
+        let start = samples.first()?.0;
+        let psi: Vec<Complex64> = samples.iter().map(|(_, psi)| *psi).collect();
+        let mut potential: Vec<f64> = samples
+            .iter()
+            .map(|(x, _)| model.potential(Length::new::<meter>(*x)).get::<joule>())
+            .collect();
+        for delta in model.deltas() {
+            delta.add_to_grid(&mut potential, start, settings.sample_step);
+        }
+        let mass = model.mass().get::<kilogram>();
+        Self::new(&psi, start, settings.sample_step, &potential, mass)
+    }
+
+    /// Δx (m)
+    pub fn position_uncertainty(&self) -> f64 {
+        (self.position_squared - self.position.powi(2))
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// Δp (kg·m/s)
+    pub fn momentum_uncertainty(&self) -> f64 {
+        (self.momentum_squared - self.momentum.powi(2))
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// ΔxΔp in units of ħ/2, at least 1 by the uncertainty principle
+    pub fn uncertainty_ratio(&self) -> f64 {
+        self.position_uncertainty() * self.momentum_uncertainty() / (H_BAR / 2.0)
+    }
+}
+
+/// Ψ_j, 0 beyond the grid
+fn at(psi: &[Complex64], j: isize) -> Complex64 {
+    usize::try_from(j)
+        .ok()
+        .and_then(|j| psi.get(j).cloned())
+        .unwrap_or(Complex64::new(0.0, 0.0))
+}
+
+/// of the shown state, None without one
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ExpectationValues(pub Option<Expectations>);
+
+/// computes the expectation values of the packet while the time evolution is active, else of the superposition
+pub fn update_expectation_values<M: PotentialModel>(
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    selected: Res<PotentialModelInput>,
+    superposition: Res<Superposition>,
+    time_evolution: Res<TimeEvolution>,
+    mut values: ResMut<ExpectationValues>,
+) {
+    if !(model.is_changed()
+        || settings.is_changed()
+        || selected.is_changed()
+        || superposition.is_changed()
+        || time_evolution.is_changed())
+    {
+        return;
+    }
+    let expectations = match &time_evolution.packet {
+        Some(packet) if time_evolution.active => packet.expectations(),
+        _ => Expectations::of_superposition(&*model, &superposition, &settings.settings),
+    };
+    values.set_if_neq(ExpectationValues(expectations));
+}
+
+/// marker for the line at ⟨x⟩ or ⟨p⟩
+#[derive(Component)]
+pub struct ExpectationCurve;
+
+/// replaces the line at ⟨x⟩, or at ⟨p⟩ in momentum space, when it moves
+pub fn setup_expectation_curves<M: PotentialModel>(
+    mut commands: Commands,
+    curve_query: Query<Entity, With<ExpectationCurve>>,
+    settings: Res<ModelPlotSettings<M>>,
+    selected: Res<PotentialModelInput>,
+    options: Res<CurveOptions>,
+    values: Res<ExpectationValues>,
+) {
+    if !(values.is_changed()
+        || settings.is_changed()
+        || selected.is_changed()
+        || options.is_changed())
+    {
+        return;
+    }
+    despawn_all_entities(&mut commands, &curve_query);
+    let Some(expectations) = values.0 else {
+        return;
+    };
+    let (id, label, value, settings) = if options.momentum {
+        (
+            MOMENTUM_SERIES,
+            "⟨p⟩",
+            expectations.momentum,
+            &settings.momentum,
+        )
+    } else {
+        (
+            POSITION_SERIES,
+            "⟨x⟩",
+            expectations.position,
+            &settings.settings,
+        )
+    };
+    let x = (value * settings.screen_scale_x) as f32;
+    let height = settings.screen_height as f32;
+    let series = Series::new(
+        id,
+        label,
+        EXPECTATION_COLOR,
+        LineStyle::Dashed,
+        vec![Vec2::new(x, -height), Vec2::new(x, height)],
+    );
+    commands.spawn((ExpectationCurve, series));
+}
+
+/// ⟨x⟩ and ⟨p⟩ of the wave packet at a time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistorySample {
+    /// t (s)
+    pub time: f64,
+    /// ⟨x⟩ (m)
+    pub position: f64,
+    /// ⟨p⟩ (kg·m/s)
+    pub momentum: f64,
+}
+
+/// ⟨x⟩ and ⟨p⟩ of the wave packet since its creation, empty while the time evolution isn't active
+#[derive(Resource, Debug, Default)]
+pub struct ExpectationHistory(pub Vec<HistorySample>);
+
+impl ExpectationHistory {
+    /// adds a sample, restarting the history if the packet was reset
+    /// keeps every other sample when full
+    fn push(&mut self, sample: HistorySample) {
+        let samples = &mut self.0;
+        match samples.last() {
+            Some(last) if last.time == sample.time => return,
+            Some(last) if last.time > sample.time => samples.clear(),
+            _ => {}
+        }
+        if samples.len() >= HISTORY_LENGTH {
+            let mut index = 0;
+            samples.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+        samples.push(sample);
+    }
+}
+
+/// records ⟨x⟩ and ⟨p⟩ of the packet at each of its times
+/// computed from the packet instead of taken from `ExpectationValues`, which may still be of the previous frame
+pub fn update_expectation_history(
+    time_evolution: Res<TimeEvolution>,
+    mut history: ResMut<ExpectationHistory>,
+) {
+    if !time_evolution.is_changed() {
+        return;
+    }
+    let packet = match &time_evolution.packet {
+        Some(packet) if time_evolution.active => packet,
+        _ => {
+            if !history.0.is_empty() {
+                history.0.clear();
+            }
+            return;
+        }
+    };
+    if history.0.last().map(|last| last.time) == Some(packet.time) {
+        return;
+    }
+    let Some(expectations) = packet.expectations() else {
+        return;
+    };
+    history.push(HistorySample {
+        time: packet.time,
+        position: expectations.position,
+        momentum: expectations.momentum,
+    });
+}
+
+/// marker for the ⟨x⟩(t) and ⟨p⟩(t) curves
+#[derive(Component)]
+pub struct ExpectationHistoryCurve;
+
+/// replaces the ⟨x⟩(t) and ⟨p⟩(t) curves on the inset, and sets its axes while the time evolution is active
+/// the inset is hidden while both curves are toggled off
+/// as ⟨x⟩ and ⟨p⟩ have different units, both are plotted relative to their largest magnitude
+pub fn setup_expectation_history_curves(
+    mut commands: Commands,
+    curve_query: Query<Entity, With<ExpectationHistoryCurve>>,
+    history: Res<ExpectationHistory>,
+    time_evolution: Res<TimeEvolution>,
+    visibility: Res<SeriesVisibility>,
+    mut inset_axes: ResMut<InsetAxes>,
+) {
+    if !(history.is_changed() || visibility.is_changed()) {
+        return;
+    }
+    despawn_all_entities(&mut commands, &curve_query);
+    // otherwise the inset belongs to the scattering curves
+    if !time_evolution.active {
+        return;
+    }
+    if !(visibility.is_visible(POSITION_SERIES) || visibility.is_visible(MOMENTUM_SERIES)) {
+        inset_axes.set_if_neq(InsetAxes::default());
+        return;
+    }
+    let end = history.0.last().map(|last| last.time).unwrap_or(0.0);
+    let Some(mut span) = history.0.iter().map(|s| s.time).find(|time| *time > 0.0) else {
+        // nothing to plot before the first step
+        inset_axes.set_if_neq(InsetAxes::default());
+        return;
+    };
+    // doubled as the time grows, so the axes and their labels aren't replaced every step
+    while span < end {
+        span *= 2.0;
+    }
+    let scale_x = INSET_WIDTH as f64 / span;
+    let scale_y = INSET_HEIGHT as f64 / 2.0;
+    let axes = InsetAxes {
+        axes: Some(PlotAxes {
+            x: PlotAxis {
+                symbol: "t",
+                unit: AxisUnit::Other("s"),
+                screen_scale: scale_x,
+            },
+            y: PlotAxis {
+                symbol: "⟨x⟩, ⟨p⟩ / max",
+                unit: AxisUnit::Other(""),
+                screen_scale: scale_y,
+            },
+        }),
+        centered_y: true,
+    };
+    let origin_y = axes.origin_y();
+    let mut spawn_curve =
+        |id: SeriesId, label: &str, color: Srgba, value: fn(&HistorySample) -> f64| {
+            let max = history
+                .0
+                .iter()
+                .fold(0.0, |max: f64, sample| max.max(value(sample).abs()));
+            let points = history
+                .0
+                .iter()
+                .map(|sample| {
+                    let y = if max > 0.0 { value(sample) / max } else { 0.0 };
+                    Vec2::new(
+                        (sample.time * scale_x) as f32,
+                        (y * scale_y) as f32 + origin_y,
+                    )
+                })
+                .collect();
+            commands.spawn((
+                ExpectationHistoryCurve,
+                InsetSeries,
+                Series::new(id, label, color, LineStyle::Straight, points),
+            ));
+        };
+    spawn_curve(POSITION_SERIES, "⟨x⟩(t)", EXPECTATION_COLOR, |sample| {
+        sample.position
+    });
+    spawn_curve(
+        MOMENTUM_SERIES,
+        "⟨p⟩(t)",
+        MOMENTUM_HISTORY_COLOR,
+        |sample| sample.momentum,
+    );
+    inset_axes.set_if_neq(axes);
+}
+
+/// lists the expectation values on the panel
+pub fn update_expectation_label(
+    values: Res<ExpectationValues>,
+    mut label_query: Query<&mut Text, With<ExpectationLabelMarker>>,
+) {
+    if !values.is_changed() {
+        return;
+    }
+    let text = match values.0 {
+        Some(e) => format!(
+            "⟨x⟩: {:.2e} m\n⟨x^2⟩: {:.2e} m^2\n⟨p⟩: {:.2e} kg·m/s\n⟨p^2⟩: {:.2e} (kg·m/s)^2\nΔx: {:.2e} m\nΔp: {:.2e} kg·m/s\nΔxΔp: {:.3} ħ/2\n⟨H⟩: {:.2e} J",
+            e.position,
+            e.position_squared,
+            e.momentum,
+            e.momentum_squared,
+            e.position_uncertainty(),
+            e.momentum_uncertainty(),
+            e.uncertainty_ratio(),
+            e.energy
+        ),
+        None => "-".to_string(),
+    };
+    for mut label in label_query.iter_mut() {
+        label.sections[0].value.clone_from(&text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+
+    use super::{ExpectationHistory, Expectations, HistorySample, HISTORY_LENGTH};
+    use crate::{
+        harmonic_oscillator_plot::HarmonicOscillator,
+        infinite_well_plot::InfiniteWell,
+        model::{PotentialModel, H_BAR},
+        superposition::Superposition,
+        ui::EnergyLevel,
+    };
+
+    fn expectations<M: PotentialModel>(model: &M, level: u32) -> Expectations {
+        let superposition = Superposition::single(EnergyLevel(level));
+        Expectations::of_superposition(model, &superposition, &model.plot_settings()).unwrap()
+    }
+
+    #[test]
+    fn harmonic_oscillator_expectations() {
+        let model = HarmonicOscillator::default();
+        let mass = model.mass.value;
+        let omega = model.ang_freq.value;
+        for n in 0..4 {
+            let e = expectations(&model, n);
+            let quantum = n as f64 + 0.5;
+            // 0 relative to the spreads
+            assert!(e.position.abs() < 1e-6 * e.position_uncertainty());
+            assert!(e.momentum.abs() < 1e-6 * e.momentum_uncertainty());
+            assert_relative_eq!(
+                (H_BAR / (mass * omega) * quantum).sqrt(),
+                e.position_uncertainty(),
+                max_relative = 1e-3
+            );
+            assert_relative_eq!(
+                (H_BAR * mass * omega * quantum).sqrt(),
+                e.momentum_uncertainty(),
+                max_relative = 1e-3
+            );
+            assert_relative_eq!(2.0 * quantum, e.uncertainty_ratio(), max_relative = 1e-3);
+            assert_relative_eq!(H_BAR * omega * quantum, e.energy, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn infinite_well_expectations() {
+        // Ψ_n is only inside of the well, the walls are where V isn't finite
+        let model = InfiniteWell::default();
+        let width = model.width.value;
+        for n in 1..4 {
+            let e = expectations(&model, n);
+            let k = n as f64 * PI / width;
+            assert_relative_eq!(width / 2.0, e.position, max_relative = 1e-3);
+            assert_relative_eq!(
+                width.powi(2) * (1.0 / 3.0 - 1.0 / (2.0 * (n as f64 * PI).powi(2))),
+                e.position_squared,
+                max_relative = 1e-3
+            );
+            assert!(e.momentum.abs() < 1e-6 * e.momentum_uncertainty());
+            assert_relative_eq!((H_BAR * k).powi(2), e.momentum_squared, max_relative = 1e-3);
+            assert_relative_eq!(
+                model.energy(&EnergyLevel(n)).value,
+                e.energy,
+                max_relative = 1e-3
+            );
+        }
+    }
+
+    #[test]
+    fn gaussian_packet_has_minimal_uncertainty() {
+        let sigma: f64 = 1e-10;
+        let momentum = 5.0 * H_BAR / sigma;
+        let step = 1e-12;
+        let start = -2e-9;
+        let psi: Vec<Complex64> = (0..=4000)
+            .map(|i| {
+                let x = start + i as f64 * step;
+                let magnitude = (-(x / (2.0 * sigma)).powi(2)).exp();
+                Complex64::from_polar(magnitude, momentum * x / H_BAR)
+            })
+            .collect();
+        let potential = vec![0.0; psi.len()];
+        let e = Expectations::new(&psi, start, step, &potential, 9e-31).unwrap();
+        assert!(e.position.abs() < 1e-6 * sigma);
+        assert_relative_eq!(sigma, e.position_uncertainty(), max_relative = 1e-3);
+        assert_relative_eq!(momentum, e.momentum, max_relative = 1e-3);
+        assert_relative_eq!(1.0, e.uncertainty_ratio(), max_relative = 1e-3);
+
+        assert_eq!(None, Expectations::new(&[], start, step, &[], 9e-31));
+    }
+
+    #[test]
+    fn history_is_thinned_out_and_restarted() {
+        let sample = |time: f64| HistorySample {
+            time,
+            position: time,
+            momentum: 0.0,
+        };
+        let mut history = ExpectationHistory::default();
+        for i in 0..HISTORY_LENGTH + 1 {
+            history.push(sample(i as f64));
+        }
+        // still from the start
+        assert_eq!(HISTORY_LENGTH / 2 + 1, history.0.len());
+        assert_eq!(0.0, history.0[0].time);
+        assert_eq!(2.0, history.0[1].time);
+        assert_eq!(HISTORY_LENGTH as f64, history.0.last().unwrap().time);
+
+        // the packet was reset
+        history.push(sample(0.0));
+        assert_eq!(vec![sample(0.0)], history.0);
+    }
+}
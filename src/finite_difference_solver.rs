@@ -0,0 +1,357 @@
+/// numerical eigensolver for the 1D time independent Schrödinger equation
+/// -ħ^2/2m Ψ'' + V(x)Ψ = EΨ is discretized on the plot's domain grid with the 3 point second derivative,
+/// which gives a symmetric tridiagonal Hamiltonian matrix
+/// Ψ = 0 at the domain ends, i.e. the domain is implicitly enclosed by infinite walls
+use crate::{model::H_BAR, plot::PlotSettings};
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+/// the lowest eigenstates of a discretized Hamiltonian
+#[derive(Debug, Clone)]
+pub struct Spectrum {
+    /// x (m) of the grid points, without the domain ends
+    pub grid: Vec<f64>,
+    /// grid spacing (m)
+    pub step: f64,
+    /// eigenstates, ordered by energy
+    pub states: Vec<Eigenstate>,
+}
+
+/// an eigenvalue with its eigenvector
+#[derive(Debug, Clone)]
+pub struct Eigenstate {
+    /// E_n (J)
+    pub energy: f64,
+    /// Ψ_n at the grid points, normalized such that Σ|Ψ|^2 dx = 1
+    pub psi: Vec<f64>,
+}
+
+impl Spectrum {
+    /// Ψ_n(x), linearly interpolated between grid points, 0 outside of the grid
+    pub fn psi(&self, level: usize, x: f64) -> f64 {
+        match self.states.get(level) {
+            Some(state) => interpolate(&self.grid, self.step, &state.psi, x),
+            None => 0.0,
+        }
+    }
+}
+
+/// Ψ(x) from its values at the grid points (without the domain ends), linearly interpolated, 0 outside of the grid
+pub fn interpolate(grid: &[f64], step: f64, psi: &[f64], x: f64) -> f64 {
+    let Some(first) = grid.first() else {
+        return 0.0;
+    };
+    // the domain ends (Ψ = 0) are 1 step outside of the grid
+    let position = (x - first) / step;
+    if position <= -1.0 || position >= grid.len() as f64 {
+        return 0.0;
+    }
+    let index = position.floor();
+    let fraction = position - index;
+    let value_at = |i: f64| {
+        if i < 0.0 || i >= grid.len() as f64 {
+            0.0
+        } else {
+            psi[i as usize]
+        }
+    };
+    value_at(index) * (1.0 - fraction) + value_at(index + 1.0) * fraction
+}
+
+/// solves the lowest `count` eigenstates for a potential on the settings' domain grid
+/// the grid uses the settings' sample step
+pub fn solve<F>(settings: &PlotSettings, mass: Mass, potential: F, count: usize) -> Spectrum
+where
+    F: Fn(Length) -> Energy,
+{
+    let (grid, step) = interior_grid(settings);
+
+    let mass = mass.get::<kilogram>();
+    // ħ^2/2m dx^2, dividing before multiplying to stay in a comfortable range
+    let kinetic = H_BAR / (2.0 * mass * step) * H_BAR / step;
+
+    let diagonal: Vec<f64> = grid
+        .iter()
+        .map(|x| {
+            let v = potential(Length::new::<meter>(*x)).get::<joule>();
+            2.0 * kinetic + v
+        })
+        .collect();
+    let off_diagonal = -kinetic;
+
+    let count = count.min(grid.len());
+    let mut states: Vec<Eigenstate> = vec![];
+    for k in 0..count {
+        let energy = eigenvalue(&diagonal, off_diagonal, k);
+        let psi = eigenvector(&diagonal, off_diagonal, energy, &states, step);
+        states.push(Eigenstate { energy, psi });
+    }
+
+    Spectrum { grid, step, states }
+}
+
+/// x (m) of the points in the settings' domain, spaced by about the sample step, without the domain ends
+/// returns the points and the exact spacing
+pub fn interior_grid(settings: &PlotSettings) -> (Vec<f64>, f64) {
+    let start = settings.domain_range_start;
+    let end = settings.domain_range_end;
+    let intervals = ((end - start) / settings.sample_step).round() as usize;
+    let step = (end - start) / intervals as f64;
+    let grid = (1..intervals).map(|i| start + i as f64 * step).collect();
+    (grid, step)
+}
+
+/// number of eigenvalues smaller than lambda, via the Sturm sequence
+fn count_eigenvalues_below(diagonal: &[f64], off_diagonal: f64, lambda: f64) -> usize {
+    let mut count = 0;
+    let mut q = 1.0;
+    for (i, d) in diagonal.iter().enumerate() {
+        q = if i == 0 {
+            d - lambda
+        } else {
+            d - lambda - off_diagonal.powi(2) / q
+        };
+        if q == 0.0 {
+            q = f64::EPSILON * off_diagonal.abs();
+        }
+        if q < 0.0 {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// k-th smallest eigenvalue (0 based), found by bisection within the Gershgorin bounds
+fn eigenvalue(diagonal: &[f64], off_diagonal: f64, k: usize) -> f64 {
+    let radius = 2.0 * off_diagonal.abs();
+    let mut low = diagonal.iter().cloned().fold(f64::INFINITY, f64::min) - radius;
+    let mut high = diagonal.iter().cloned().fold(f64::NEG_INFINITY, f64::max) + radius;
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+        if count_eigenvalues_below(diagonal, off_diagonal, mid) > k {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// eigenvector for an eigenvalue, via inverse iteration
+/// previous eigenvectors are projected out, to not converge to them for nearly degenerate eigenvalues
+fn eigenvector(
+    diagonal: &[f64],
+    off_diagonal: f64,
+    eigenvalue: f64,
+    previous: &[Eigenstate],
+    step: f64,
+) -> Vec<f64> {
+    let mut vector = vec![1.0; diagonal.len()];
+    for _ in 0..4 {
+        vector = solve_shifted_tridiagonal(diagonal, off_diagonal, eigenvalue, &vector);
+        for state in previous {
+            let overlap: f64 = state.psi.iter().zip(&vector).map(|(a, b)| a * b).sum();
+            let overlap = overlap * step;
+            for (v, p) in vector.iter_mut().zip(&state.psi) {
+                *v -= overlap * p;
+            }
+        }
+        normalize(&mut vector, step);
+    }
+    fix_sign(&mut vector);
+    vector
+}
+
+/// solves (T - shift I) y = rhs for tridiagonal T, with the Thomas algorithm
+fn solve_shifted_tridiagonal(
+    diagonal: &[f64],
+    off_diagonal: f64,
+    shift: f64,
+    rhs: &[f64],
+) -> Vec<f64> {
+    let n = diagonal.len();
+    let mut c = vec![0.0; n];
+    let mut d = vec![0.0; n];
+    // exact zeros (the shift is an eigenvalue) are nudged, inverse iteration only needs the direction
+    let non_zero = |value: f64| {
+        if value == 0.0 {
+            f64::EPSILON * off_diagonal.abs()
+        } else {
+            value
+        }
+    };
+    for i in 0..n {
+        let (previous_c, previous_d) = if i == 0 {
+            (0.0, 0.0)
+        } else {
+            (c[i - 1], d[i - 1])
+        };
+        let denominator = non_zero(diagonal[i] - shift - off_diagonal * previous_c);
+        c[i] = off_diagonal / denominator;
+        d[i] = (rhs[i] - off_diagonal * previous_d) / denominator;
+    }
+    let mut y = vec![0.0; n];
+    for i in (0..n).rev() {
+        y[i] = if i == n - 1 {
+            d[i]
+        } else {
+            d[i] - c[i] * y[i + 1]
+        };
+    }
+    y
+}
+
+/// scales the vector such that Σ|v|^2 dx = 1
+pub fn normalize(vector: &mut [f64], step: f64) {
+    let norm = (vector.iter().map(|v| v.powi(2)).sum::<f64>() * step).sqrt();
+    if norm > 0.0 && norm.is_finite() {
+        for v in vector.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+/// eigenvectors are determined up to sign, we make the first non negligible value positive
+pub fn fix_sign(vector: &mut [f64]) {
+    let max = vector.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    if let Some(first) = vector.iter().find(|v| v.abs() > max * 1e-3) {
+        if *first < 0.0 {
+            for v in vector.iter_mut() {
+                *v = -*v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::joule,
+        f64::{Energy, Length},
+        length::meter,
+    };
+
+    use crate::{
+        harmonic_oscillator_plot::HarmonicOscillator, infinite_well_plot::InfiniteWell,
+        model::PotentialModel, plot::PlotSettings, ui::EnergyLevel,
+    };
+
+    use super::{solve, Spectrum};
+
+    /// compares Ψ_n with a closed form, allowing for the sign difference
+    fn assert_psi_matches<F>(spectrum: &Spectrum, level: usize, closed_form: F)
+    where
+        F: Fn(f64) -> f64,
+    {
+        let state = &spectrum.states[level];
+        let expected: Vec<f64> = spectrum.grid.iter().map(|x| closed_form(*x)).collect();
+        let overlap: f64 = expected.iter().zip(&state.psi).map(|(a, b)| a * b).sum();
+        let sign = overlap.signum();
+        let max = expected.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+        for (expected, actual) in expected.iter().zip(&state.psi) {
+            assert!(
+                (expected - sign * actual).abs() < max * 1e-2,
+                "level {}: expected {}, got {}",
+                level,
+                expected,
+                sign * actual
+            );
+        }
+    }
+
+    #[test]
+    fn infinite_well_matches_closed_form() {
+        let model = InfiniteWell::default();
+        // the domain ends are the walls
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: 2.0,
+            sample_step: 0.002,
+            ..PlotSettings::default()
+        };
+        let spectrum = solve(&settings, model.mass, |_| Energy::new::<joule>(0.0), 4);
+
+        for level in 0..4 {
+            // the infinite well's levels start at 1
+            let energy_level = EnergyLevel(level as u32 + 1);
+            let expected_energy = model.energy(&energy_level).get::<joule>();
+            assert_relative_eq!(
+                expected_energy,
+                spectrum.states[level].energy,
+                max_relative = 1e-3
+            );
+            assert_psi_matches(&spectrum, level, |x| {
+                model.psi(Length::new::<meter>(x), &energy_level).re
+            });
+        }
+    }
+
+    #[test]
+    fn harmonic_oscillator_matches_closed_form() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let spectrum = solve(&settings, model.mass, |x| model.potential(x), 4);
+
+        for level in 0..4 {
+            let energy_level = EnergyLevel(level as u32);
+            let expected_energy = model.energy(&energy_level).get::<joule>();
+            assert_relative_eq!(
+                expected_energy,
+                spectrum.states[level].energy,
+                max_relative = 1e-3
+            );
+            assert_psi_matches(&spectrum, level, |x| {
+                model.psi(Length::new::<meter>(x), &energy_level).re
+            });
+        }
+    }
+
+    #[test]
+    fn eigenstates_are_normalized_and_orthogonal() {
+        let model = HarmonicOscillator::default();
+        let spectrum = solve(
+            &model.plot_settings(),
+            model.mass,
+            |x| model.potential(x),
+            3,
+        );
+
+        for a in &spectrum.states {
+            for b in &spectrum.states {
+                let product: f64 = a.psi.iter().zip(&b.psi).map(|(a, b)| a * b).sum();
+                let product = product * spectrum.step;
+                let expected = if std::ptr::eq(a, b) { 1.0 } else { 0.0 };
+                assert_relative_eq!(expected, product, epsilon = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn interpolates_between_grid_points() {
+        let model = InfiniteWell::default();
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: 2.0,
+            sample_step: 0.5,
+            ..PlotSettings::default()
+        };
+        let spectrum = solve(&settings, model.mass, |_| Energy::new::<joule>(0.0), 1);
+
+        let state = &spectrum.states[0];
+        assert_eq!(vec![0.5, 1.0, 1.5], spectrum.grid);
+        assert_relative_eq!(state.psi[0], spectrum.psi(0, 0.5));
+        assert_relative_eq!((state.psi[0] + state.psi[1]) / 2.0, spectrum.psi(0, 0.75));
+        // the domain ends are walls
+        assert_relative_eq!(state.psi[0] / 2.0, spectrum.psi(0, 0.25));
+        assert_relative_eq!(0.0, spectrum.psi(0, 0.0));
+        assert_relative_eq!(0.0, spectrum.psi(0, 3.0));
+    }
+}
@@ -0,0 +1,330 @@
+/// finite square well plot
+/// it plots Ψ(x) and PDF(x) for a given bound state, selected via the UI
+/// the bound state energies are found numerically, solving the transcendental equations
+/// see https://en.wikipedia.org/wiki/Finite_potential_well
+use crate::{
+    model::{ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    numerics::bisect,
+    plot::PlotSettings,
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::FRAC_PI_2;
+use uom::si::{
+    energy::{electronvolt, joule},
+    f64::{Energy, Length, Mass},
+    length::{meter, nanometer},
+    mass::kilogram,
+};
+
+/// decay lengths 1/κ of the tails outside of the well on the plot's domain
+const TAIL_DECAY_LENGTHS: f64 = 5.0;
+/// bound on the tails' extent (widths), for states at the edge of the well, which barely decay
+const MAX_TAIL_WIDTHS: f64 = 10.0;
+
+/// finite well between x = -width/2 and x = width/2
+/// V(x) = 0 inside the well and V₀ (depth) outside
+#[derive(Resource)]
+pub struct FiniteWell {
+    width: Length,
+    depth: Energy,
+    mass: Mass,
+    /// solved bound states, ordered by energy
+    bound_states: Vec<BoundState>,
+}
+
+/// a solved bound state
+/// in dimensionless units: z = ka, ζ = κa, with a the half width
+#[derive(Debug, Clone, Copy)]
+struct BoundState {
+    /// wave number inside the well, times a
+    z: f64,
+    /// decay constant outside the well, times a
+    zeta: f64,
+    parity: Parity,
+    /// normalization constant, times √a
+    normalization: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Parity {
+    Even,
+    Odd,
+}
+
+impl Default for FiniteWell {
+    fn default() -> Self {
+        Self::new(
+            Length::new::<nanometer>(1.0),
+            Energy::new::<electronvolt>(10.0),
+            Mass::new::<kilogram>(9.11e-31),
+        )
+    }
+}
+
+impl FiniteWell {
+    pub fn new(width: Length, depth: Energy, mass: Mass) -> Self {
+        let z0 = calculate_z0(width, depth, mass);
+        Self {
+            width,
+            depth,
+            mass,
+            bound_states: solve_bound_states(z0),
+        }
+    }
+
+    /// number of bound states, there's always at least 1
+    pub fn bound_state_count(&self) -> u32 {
+        self.bound_states.len() as u32
+    }
+
+    fn half_width(&self) -> f64 {
+        self.width.get::<meter>() / 2.0
+    }
+}
+
+impl PotentialModel for FiniteWell {
+    fn name(&self) -> &'static str {
+        "Finite well"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            ModelParameter {
+                name: "L",
+                value: self.width.get::<meter>(),
+                unit: "m",
+                range: ParameterRange::new(1e-10, 1e-8),
+            },
+            ModelParameter {
+                name: "V₀",
+                value: self.depth.get::<joule>(),
+                unit: "J",
+                // 0.01 eV to 1 keV
+                range: ParameterRange::new(1.6e-21, 1.6e-16),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    /// the bound states are solved again
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        let (mut width, mut depth, mut mass) = (self.width, self.depth, self.mass);
+        match index {
+            0 => width = Length::new::<meter>(value),
+            1 => depth = Energy::new::<joule>(value),
+            2 => mass = Mass::new::<kilogram>(value),
+            _ => return,
+        }
+        *self = Self::new(width, depth, mass);
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        let psi = match self.bound_states.get(level.0 as usize) {
+            Some(state) => psi(x.get::<meter>(), self.half_width(), state),
+            None => 0.0,
+        };
+        Complex64::new(psi, 0.0)
+    }
+
+    /// E_n = V₀ (z/z₀)^2, measured from the bottom of the well
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        match self.bound_states.get(level.0 as usize) {
+            Some(state) => {
+                let z0_squared = state.z.powi(2) + state.zeta.powi(2);
+                self.depth * (state.z.powi(2) / z0_squared)
+            }
+            None => self.depth,
+        }
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        if x.get::<meter>().abs() <= self.half_width() {
+            Energy::new::<joule>(0.0)
+        } else {
+            self.depth
+        }
+    }
+
+    /// scaled with the width, such that the well is always 5 screen units wide
+    /// the domain covers the tails of the least bound state, which reach the farthest
+    fn plot_settings(&self) -> PlotSettings {
+        let width = self.width.get::<meter>();
+        let tail = self
+            .bound_states
+            .last()
+            .map(|state| TAIL_DECAY_LENGTHS * self.half_width() / state.zeta)
+            .unwrap_or(0.0)
+            .clamp(width, MAX_TAIL_WIDTHS * width);
+        let half_extent = self.half_width() + tail;
+        PlotSettings {
+            domain_range_start: -half_extent,
+            domain_range_end: half_extent,
+            sample_step: width / 200.0,
+            screen_scale_x: 5.0 / width,
+            ..PlotSettings::default()
+        }
+    }
+
+    fn max_level(&self) -> u32 {
+        self.bound_state_count() - 1
+    }
+}
+
+/// z₀ = a √(2mV₀) / ħ, with a the half width
+/// it determines how many bound states there are
+fn calculate_z0(width: Length, depth: Energy, mass: Mass) -> f64 {
+    let a = width.get::<meter>() / 2.0;
+    a * (2.0 * mass.get::<kilogram>() * depth.get::<joule>()).sqrt() / H_BAR
+}
+
+/// finds the bound states for a well of strength z₀
+/// the n-th state is in [nπ/2, (n+1)π/2] (and below z₀), even n are even states, odd n odd states
+fn solve_bound_states(z0: f64) -> Vec<BoundState> {
+    let mut states = vec![];
+    let mut n = 0;
+    while (n as f64) * FRAC_PI_2 < z0 {
+        let start = n as f64 * FRAC_PI_2;
+        let end = ((n + 1) as f64 * FRAC_PI_2).min(z0);
+
+        let parity = if n % 2 == 0 {
+            Parity::Even
+        } else {
+            Parity::Odd
+        };
+
+        let zeta = |z: f64| (z0.powi(2) - z.powi(2)).max(0.0).sqrt();
+        // the transcendental equations, multiplied out to avoid the poles of tan / cot
+        let z = match parity {
+            // z tan(z) = ζ
+            Parity::Even => bisect(|z| z * z.sin() - zeta(z) * z.cos(), start, end),
+            // -z cot(z) = ζ
+            Parity::Odd => bisect(|z| z * z.cos() + zeta(z) * z.sin(), start, end),
+        };
+        let zeta = zeta(z);
+
+        states.push(BoundState {
+            z,
+            zeta,
+            parity,
+            normalization: calculate_normalization_constant(z, zeta, parity),
+        });
+        n += 1;
+    }
+    states
+}
+
+/// A√a, such that ∫|Ψ|^2 = 1
+/// inside: ∫cos^2 or ∫sin^2 over [-a, a], outside: 2 exponential tails
+fn calculate_normalization_constant(z: f64, zeta: f64, parity: Parity) -> f64 {
+    let (inside, boundary) = match parity {
+        Parity::Even => (1.0 + (2.0 * z).sin() / (2.0 * z), z.cos()),
+        Parity::Odd => (1.0 - (2.0 * z).sin() / (2.0 * z), z.sin()),
+    };
+    let outside = boundary.powi(2) / zeta;
+    1.0 / (inside + outside).sqrt()
+}
+
+/// Ψ_n(x), oscillating inside the well and decaying exponentially outside
+fn psi(x: f64, half_width: f64, state: &BoundState) -> f64 {
+    let a = half_width;
+    let amplitude = state.normalization / a.sqrt();
+    let u = x / a;
+    if u.abs() <= 1.0 {
+        match state.parity {
+            Parity::Even => amplitude * (state.z * u).cos(),
+            Parity::Odd => amplitude * (state.z * u).sin(),
+        }
+    } else {
+        let tail = (-state.zeta * (u.abs() - 1.0)).exp();
+        match state.parity {
+            Parity::Even => amplitude * state.z.cos() * tail,
+            Parity::Odd => amplitude * state.z.sin() * u.signum() * tail,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use std::f64::consts::PI;
+    use uom::si::{
+        energy::electronvolt,
+        f64::{Energy, Length, Mass},
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use crate::{model::PotentialModel, plot::generate_points, ui::EnergyLevel};
+
+    use super::{solve_bound_states, FiniteWell};
+
+    #[test]
+    fn bound_state_count_follows_z0() {
+        assert_eq!(1, solve_bound_states(1.0).len());
+        assert_eq!(2, solve_bound_states(2.0).len());
+        assert_eq!(4, solve_bound_states(5.0).len());
+        assert_eq!(6, solve_bound_states(8.0).len());
+    }
+
+    #[test]
+    fn bound_states_solve_transcendental_equations() {
+        let z0: f64 = 8.0;
+        for (n, state) in solve_bound_states(z0).iter().enumerate() {
+            assert_relative_eq!(
+                z0.powi(2),
+                state.z.powi(2) + state.zeta.powi(2),
+                epsilon = 1e-9
+            );
+            let expected_zeta = if n % 2 == 0 {
+                state.z * state.z.tan()
+            } else {
+                -state.z / state.z.tan()
+            };
+            assert_relative_eq!(expected_zeta, state.zeta, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn bound_states_are_normalized() {
+        let well = FiniteWell::default();
+        for n in 0..well.bound_state_count() {
+            let level = EnergyLevel(n);
+            let step = 1e-12;
+            let points = generate_points(-3e-9, 3e-9, step, |x| {
+                well.psi(Length::new::<meter>(x), &level).norm_sqr()
+            });
+            let integral: f64 = points.iter().map(|p| p.y * step).sum();
+            assert_relative_eq!(1.0, integral, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn deep_well_approaches_infinite_well_energies() {
+        let width = Length::new::<nanometer>(1.0);
+        let mass = Mass::new::<kilogram>(9.11e-31);
+        let well = FiniteWell::new(width, Energy::new::<electronvolt>(10000.0), mass);
+
+        // E_n of the infinite well, with n starting at 1
+        let infinite_well_energy = |n: f64| {
+            let momentum = n * PI * super::H_BAR / width.get::<meter>();
+            momentum.powi(2) / (2.0 * mass.get::<kilogram>())
+        };
+
+        for n in 0..3 {
+            let energy = well.energy(&EnergyLevel(n)).value;
+            let expected = infinite_well_energy((n + 1) as f64);
+            assert_relative_eq!(expected, energy, max_relative = 0.05);
+        }
+    }
+}
@@ -2,232 +2,195 @@
 /// it plots Ψ(x) and PDF(x) for a given energy level, selected via the UI
 /// we use the solved equations for Ψ and PDF
 use crate::{
-    plot::{
-        generate_points, setup_curve, setup_plot_ticks, Curve, CurvePDF, CurveWave, PlotSettings,
-        TickSettings,
-    },
-    ui::{EnergyLevel, PotentialModelInput},
+    model::{ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    momentum::momentum_plot_settings,
+    plot::PlotSettings,
+    ui::EnergyLevel,
 };
-use bevy::{
-    color::palettes::{css::WHITE, tailwind::GRAY_500},
-    prelude::*,
-};
-use std::f32::consts::{E, PI};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
 use uom::si::{
-    f32::{Frequency, Length, Mass},
+    energy::joule,
+    f64::{Energy, Frequency, Length, Mass, Momentum},
     frequency::hertz,
     length::meter,
     mass::kilogram,
+    momentum::kilogram_meter_per_second,
 };
 
-const H_BAR: f32 = 1.054571817e-34;
-
-/// make settings specific to this plot type
-/// needed for bevy's resources specifics
+/// harmonic oscillator with potential V(x) = 1/2 m ω^2 x^2
 #[derive(Resource)]
-pub struct HarmonicOscillatorPlotSettings(pub PlotSettings);
-
-/// adds this plot to the app
-pub fn add_plot(app: &mut App) {
-    app.add_systems(
-        Update,
-        (setup_pdf, setup_psi, setup_ticks).run_if(is_model_selected),
-    )
-    .insert_resource(HarmonicOscillatorPlotSettings(PlotSettings {
-        domain_range_start: -2e-10,
-        domain_range_end: 2e-10,
-        screen_scale_x: 1e10,
-        screen_scale_y_psi: 1.0 / 72414.0,
-        screen_scale_y_pdf: 1.0 / 8000000000.0,
-        ticks: TickSettings { step: 1e-10 },
-    }));
+pub struct HarmonicOscillator {
+    pub mass: Mass,
+    pub ang_freq: Frequency,
 }
 
-/// condition to add this plot
-fn is_model_selected(mode: Res<PotentialModelInput>) -> bool {
-    match *mode {
-        PotentialModelInput::HarmonicOscillator => true,
-        _ => false,
+impl Default for HarmonicOscillator {
+    fn default() -> Self {
+        Self {
+            mass: Mass::new::<kilogram>(9e-31),
+            ang_freq: Frequency::new::<hertz>(10e16),
+        }
     }
 }
 
-/// adds Ψ screen curve to bevy
-fn setup_psi(
-    mut commands: Commands,
-    energy_level_query: Query<&EnergyLevel>,
-    curve_query: Query<Entity, (With<Curve>, With<CurveWave>)>,
-    settings: Res<HarmonicOscillatorPlotSettings>,
-) {
-    let mass = Mass::new::<kilogram>(9e-31);
-    let ang_freq = Frequency::new::<hertz>(10e16_f32);
-    for e in energy_level_query.iter() {
-        let points = generate_psi_points(|x| psi(x, e, mass, ang_freq), &settings.0.clone());
-        setup_curve(&mut commands, WHITE, e.0, &curve_query, points);
+impl PotentialModel for HarmonicOscillator {
+    fn name(&self) -> &'static str {
+        "Harmonic oscillator"
     }
-}
 
-/// adds PDF screen curve to bevy
-fn setup_pdf(
-    mut commands: Commands,
-    energy_level_query: Query<&EnergyLevel>,
-    curve_query: Query<Entity, (With<Curve>, With<CurvePDF>)>,
-    settings: Res<HarmonicOscillatorPlotSettings>,
-) {
-    let mass = Mass::new::<kilogram>(9e-31);
-    let ang_freq = Frequency::new::<hertz>(10e16_f32);
-    for e in energy_level_query.iter() {
-        let points = generate_pdf_points(|x| pdf(x, e, mass, ang_freq), &settings.0.clone());
-        setup_curve(&mut commands, GRAY_500, e.0, &curve_query, points);
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+            ModelParameter {
+                name: "ω",
+                value: self.ang_freq.get::<hertz>(),
+                unit: "Hz",
+                range: ParameterRange::new(1e14, 1e18),
+            },
+        ]
     }
-}
-
-/// Ψ_n(x), see https://en.wikipedia.org/wiki/Quantum_harmonic_oscillator#Hamiltonian_and_energy_eigenstates
-fn psi(x: Length, level: &EnergyLevel, mass: Mass, ang_freq: Frequency) -> f32 {
-    let normalization_constant = calculate_normalization_constant(level, mass, ang_freq);
 
-    let sub_term = (mass * ang_freq) / H_BAR;
-    let sub_term_value = sub_term.value;
-    let x_value = x.value;
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.mass = Mass::new::<kilogram>(value),
+            1 => self.ang_freq = Frequency::new::<hertz>(value),
+            _ => {}
+        }
+    }
 
-    let e_exp = -sub_term_value * x_value.powi(2) / 2.0;
-    let e_term = E.powf(e_exp);
+    fn mass(&self) -> Mass {
+        self.mass
+    }
 
-    let pol = hermite_polynomial(level);
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        Complex64::new(psi(x, level, self.mass, self.ang_freq), 0.0)
+    }
 
-    let pol_param = sub_term_value.sqrt() * x_value;
+    /// φ_n(p) = (-i)^n Ψ_n(p/mω) / sqrt(mω), Ψ_n with p/mω as x has the same shape
+    fn phi(&self, p: Momentum, level: &EnergyLevel) -> Option<Complex64> {
+        let mass_ang_freq = (self.mass * self.ang_freq).value;
+        let x = Length::new::<meter>(p.get::<kilogram_meter_per_second>() / mass_ang_freq);
+        let psi = psi(x, level, self.mass, self.ang_freq) / mass_ang_freq.sqrt();
+        Some(Complex64::new(0.0, -1.0).powu(level.0) * psi)
+    }
 
-    let res = normalization_constant * e_term * pol(pol_param);
+    /// E_n = ħω(n + 1/2)
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        let ang_freq = self.ang_freq.get::<hertz>();
+        Energy::new::<joule>(H_BAR * ang_freq * (level.0 as f64 + 0.5))
+    }
 
-    res
-}
+    fn potential(&self, x: Length) -> Energy {
+        let mass = self.mass.get::<kilogram>();
+        let ang_freq = self.ang_freq.get::<hertz>();
+        let x = x.get::<meter>();
+        Energy::new::<joule>(0.5 * mass * ang_freq.powi(2) * x.powi(2))
+    }
 
-/// PDF for Ψ_n(x)
-fn pdf(x: Length, level: &EnergyLevel, mass: Mass, ang_freq: Frequency) -> f32 {
-    let psi = psi(x, level, mass, ang_freq);
-    psi.powi(2)
-}
+    /// scaled with the oscillator's length sqrt(ħ/mω)
+    fn plot_settings(&self) -> PlotSettings {
+        let length = (H_BAR / (self.mass * self.ang_freq).value).sqrt();
+        // wide enough for the classical turning point of the max level, sqrt(2n + 1) lengths
+        let half_width = 30.0 * length;
+        PlotSettings {
+            domain_range_start: -half_width,
+            domain_range_end: half_width,
+            // fine enough for the max level's wave length
+            sample_step: length / 64.0,
+            screen_scale_x: 10.0 / half_width,
+            ..PlotSettings::default()
+        }
+    }
 
-/// step in Ψ calculation, for better readability
-fn calculate_normalization_constant(level: &EnergyLevel, mass: Mass, ang_freq: Frequency) -> f32 {
-    let two_float = 2.0_f32;
-    let level_int = level.0 as i32;
-    let level_uint = level.0 as u32;
+    /// φ_n has the shape of Ψ_n, so p is scaled with sqrt(ħmω) the same way as x with the length
+    fn momentum_plot_settings(&self) -> PlotSettings {
+        momentum_plot_settings(30.0 * (H_BAR * (self.mass * self.ang_freq).value).sqrt())
+    }
 
-    let level_fact: u32 = (1..=level_uint).product();
+    fn max_level(&self) -> u32 {
+        // high enough to show the correspondence principle, its turning point is still in the domain
+        300
+    }
+}
 
-    let term1 = 1.0 / (two_float.powi(level_int) * level_fact as f32).sqrt();
+/// Ψ_n(x), see https://en.wikipedia.org/wiki/Quantum_harmonic_oscillator#Hamiltonian_and_energy_eigenstates
+fn psi(x: Length, level: &EnergyLevel, mass: Mass, ang_freq: Frequency) -> f64 {
+    let normalization_constant = calculate_normalization_constant(mass, ang_freq);
 
     let sub_term = (mass * ang_freq) / H_BAR;
     let sub_term_value = sub_term.value;
+    let x_value = x.value;
 
-    let term2 = (sub_term_value / PI).powf(1.0 / 4.0);
-
-    term1 * term2
-}
+    let hermite_param = sub_term_value.sqrt() * x_value;
 
-/// generates Ψ screen points
-fn generate_psi_points<F>(function: F, settings: &PlotSettings) -> Vec<Vec2>
-where
-    // for now assuming the dimension to be spatial
-    F: Fn(Length) -> f32,
-{
-    // scaled down y by ~max value so it fits in graph
-    // TODO generic mapping to screen coords
-    generate_psi_or_pdf_points(function, settings.screen_scale_y_psi, settings)
+    normalization_constant * hermite_function(level, hermite_param)
 }
 
-/// generates Ψ pdf points
-fn generate_pdf_points<F>(function: F, settings: &PlotSettings) -> Vec<Vec2>
-where
-    // for now assuming the dimension to be spatial
-    F: Fn(Length) -> f32,
-{
-    // scaled dowwn y by eye to plot together with psi
-    // exact height unimportant
-    generate_psi_or_pdf_points(function, settings.screen_scale_y_pdf, settings)
-}
+/// step in Ψ calculation, for better readability
+/// the level dependent factor 1/sqrt(2^n n!) is part of `hermite_function`
+fn calculate_normalization_constant(mass: Mass, ang_freq: Frequency) -> f64 {
+    let sub_term = (mass * ang_freq) / H_BAR;
+    let sub_term_value = sub_term.value;
 
-/// generates screen points
-fn generate_psi_or_pdf_points<F>(function: F, scale_y: f32, settings: &PlotSettings) -> Vec<Vec2>
-where
-    // for now assuming the dimension to be spatial
-    F: Fn(Length) -> f32,
-{
-    let domain_points = generate_points(
-        settings.domain_range_start,
-        settings.domain_range_end,
-        1e-12,
-        |x| function(Length::new::<meter>(x)),
-    );
-    let scaled_points: Vec<Vec2> = domain_points
-        .into_iter()
-        .map(|p| Vec2::new(p.x * settings.screen_scale_x, p.y * scale_y)) // wave
-        .collect();
-
-    scaled_points
+    (sub_term_value / PI).powf(1.0 / 4.0)
 }
 
-/// generates the hermite polynomial for a given energy level
-/// ideally it should be done dynamically (allowing for principally infinite levels),
-/// but not entirely trivial in rust (TODO)
-/// for now hardcoded the polynomials for the 10 first energy levels.
-fn hermite_polynomial(level: &EnergyLevel) -> impl Fn(f32) -> f32 {
-    match level.0 {
-        0 => |_| 1.0,
-        1 => |y| 2.0 * y,
-        2 => |y: f32| 4.0 * y.powi(2) - 2.0,
-        3 => |y: f32| 8.0 * y.powi(3) - 12.0 * y,
-        4 => |y: f32| 16.0 * y.powi(4) - 48.0 * y.powi(2) + 12.0,
-        5 => |y: f32| 32.0 * y.powi(5) - 160.0 * y.powi(3) + 120.0 * y,
-        6 => |y: f32| 64.0 * y.powi(6) - 480.0 * y.powi(4) + 720.0 * y.powi(2) - 120.0,
-        7 => |y: f32| 128.0 * y.powi(7) - 1344.0 * y.powi(5) + 3360.0 * y.powi(3) - 1680.0 * y,
-        8 => |y: f32| {
-            256.0 * y.powi(8) - 3584.0 * y.powi(6) + 13440.0 * y.powi(4) - 13440.0 * y.powi(2)
-                + 1680.0
-        },
-        9 => |y: f32| {
-            512.0 * y.powi(9) - 9216.0 * y.powi(7) + 48384.0 * y.powi(5) - 80640.0 * y.powi(3)
-                + 30240.0 * y
-        },
-        10 => |y: f32| {
-            1024.0 * y.powi(10) - 23040.0 * y.powi(8) + 161280.0 * y.powi(6) - 403200.0 * y.powi(4)
-                + 302400.0 * y.powi(2)
-                + 30240.0
-        },
-        // leniently using panic!, implementation detail, don't want to add noise downstream
-        _ => panic!("TODO polynomials not supported for n > 10"),
+/// H_n(y) e^(-y^2/2) / sqrt(2^n n!), with H_n the (physicists') hermite polynomial
+/// computed with the recurrence of the normalized functions, which stay of order 1 for any n,
+/// while H_n and 2^n n! on their own overflow already for moderate n
+/// h_{n+1} = sqrt(2/(n+1)) y h_n - sqrt(n/(n+1)) h_{n-1}, h_0 = e^(-y^2/2)
+fn hermite_function(level: &EnergyLevel, y: f64) -> f64 {
+    let mut previous = 0.0;
+    let mut current = (-y * y / 2.0).exp();
+    for n in 0..level.0 {
+        let n = n as f64;
+        let next = (2.0 / (n + 1.0)).sqrt() * y * current - (n / (n + 1.0)).sqrt() * previous;
+        previous = current;
+        current = next;
     }
-}
-
-fn setup_ticks(mut gizmos: Gizmos, settings: Res<HarmonicOscillatorPlotSettings>) {
-    setup_plot_ticks(&mut gizmos, settings.0.clone())
+    current
 }
 
 #[cfg(test)]
+// expected values are written with the precision they were calculated with
+#[allow(clippy::excessive_precision)]
 mod test {
     use approx::assert_relative_eq;
-    use bevy::math::Vec2;
+    use bevy::math::DVec2;
     use uom::si::{
-        f32::{Frequency, Length, Mass},
+        f64::{Frequency, Length, Mass},
         frequency::hertz,
         length::meter,
         mass::{gram, kilogram},
     };
 
-    use crate::{harmonic_oscillator_plot::pdf, plot::generate_points, ui::EnergyLevel};
+    use crate::{plot::generate_points, ui::EnergyLevel};
 
-    use super::{calculate_normalization_constant, psi};
+    use super::{calculate_normalization_constant, hermite_function, psi};
+
+    /// PDF for Ψ_n(x)
+    fn pdf(x: Length, level: &EnergyLevel, mass: Mass, ang_freq: Frequency) -> f64 {
+        let psi = psi(x, level, mass, ang_freq);
+        psi.powi(2)
+    }
 
     #[test]
     fn generates_correct_domain_points() {
         let domain_points = generate_points(-2e-10, 2e-10, 1e-10, |x| x * 2.0);
 
         assert_eq!(5, domain_points.len());
-        assert_eq!(Vec2::new(-2e-10, -4e-10), domain_points[0]);
-        assert_eq!(Vec2::new(-1e-10, -2e-10), domain_points[1]);
-        assert_eq!(Vec2::new(0.0, 0.0), domain_points[2]);
-        assert_eq!(Vec2::new(1e-10, 2e-10), domain_points[3]);
-        assert_eq!(Vec2::new(2e-10, 4e-10), domain_points[4]);
+        assert_eq!(DVec2::new(-2e-10, -4e-10), domain_points[0]);
+        assert_eq!(DVec2::new(-1e-10, -2e-10), domain_points[1]);
+        assert_eq!(DVec2::new(0.0, 0.0), domain_points[2]);
+        assert_eq!(DVec2::new(1e-10, 2e-10), domain_points[3]);
+        assert_eq!(DVec2::new(2e-10, 4e-10), domain_points[4]);
     }
 
     #[test]
@@ -238,15 +201,15 @@ mod test {
         let level = EnergyLevel(0);
         let x = Length::new::<meter>(0.0);
 
-        let n = calculate_normalization_constant(&level, mass, ang_freq);
+        let n = calculate_normalization_constant(mass, ang_freq);
 
         let psi = psi(x, &level, mass, ang_freq);
         let pd = pdf(x, &level, mass, ang_freq);
 
-        assert_eq!(234392381.5, n);
+        assert_relative_eq!(234392381.502583, n, max_relative = 1e-12);
         // psi = normalization constant: x = 0 makes the rest of the equation 1
         assert_relative_eq!(n, psi);
-        assert_relative_eq!(5.49397885e16, pd, epsilon = 0.00000000000001);
+        assert_relative_eq!(5.49397885064524e16, pd, max_relative = 1e-12);
     }
 
     #[test]
@@ -257,12 +220,12 @@ mod test {
         let level = EnergyLevel(0);
         let x = Length::new::<meter>(2.0);
 
-        let n = calculate_normalization_constant(&level, mass, ang_freq);
+        let n = calculate_normalization_constant(mass, ang_freq);
 
         let psi = psi(x, &level, mass, ang_freq);
         let pd = pdf(x, &level, mass, ang_freq);
 
-        assert_eq!(234392381.5, n);
+        assert_relative_eq!(234392381.502583, n, max_relative = 1e-12);
         assert_relative_eq!(0.0, psi);
         assert_relative_eq!(0.0, pd);
     }
@@ -270,37 +233,91 @@ mod test {
     #[test]
     fn waves_for_e_0_x_0_realistic_pars_are_correct() {
         let mass = Mass::new::<kilogram>(9.11e-31);
-        let ang_freq = Frequency::new::<hertz>(1e16_f32);
+        let ang_freq = Frequency::new::<hertz>(1e16);
 
         let level = EnergyLevel(0);
         let x = Length::new::<meter>(0.0);
 
-        let n = calculate_normalization_constant(&level, mass, ang_freq);
+        let n = calculate_normalization_constant(mass, ang_freq);
 
         let psi = psi(x, &level, mass, ang_freq);
         let pd = pdf(x, &level, mass, ang_freq);
 
-        assert_eq!(72414.09141, n);
+        assert_relative_eq!(72414.0914054390, n, max_relative = 1e-12);
         assert_relative_eq!(n, psi);
-        assert_relative_eq!(5.24380063e9, pd, epsilon = 0.00000000000001);
+        assert_relative_eq!(5.24380063407527e9, pd, max_relative = 1e-12);
     }
 
     #[test]
     fn waves_for_e_0_x_nonzero_realistic_pars_are_correct() {
         let mass = Mass::new::<kilogram>(9.11e-31);
-        let ang_freq = Frequency::new::<hertz>(1e16_f32);
+        let ang_freq = Frequency::new::<hertz>(1e16);
 
         let level = EnergyLevel(0);
         let x = Length::new::<meter>(-1e-10);
 
-        let n = calculate_normalization_constant(&level, mass, ang_freq);
+        let n = calculate_normalization_constant(mass, ang_freq);
 
         let psi = psi(x, &level, mass, ang_freq);
         let pd = pdf(x, &level, mass, ang_freq);
 
-        assert_eq!(72414.09141, n);
-        assert_relative_eq!(47015.25181, psi);
-        assert_relative_eq!(2.2104339e9, pd, epsilon = 0.00000000000001);
+        assert_relative_eq!(72414.0914054390, n, max_relative = 1e-12);
+        assert_relative_eq!(47015.2518111771, psi, max_relative = 1e-12);
+        assert_relative_eq!(2.21043390286839e9, pd, max_relative = 1e-12);
+    }
+
+    /// the hermite polynomials as they were hardcoded before using the recurrence, up to n = 9
+    fn explicit_hermite_polynomial(n: u32, y: f64) -> Option<f64> {
+        let value = match n {
+            0 => 1.0,
+            1 => 2.0 * y,
+            2 => 4.0 * y.powi(2) - 2.0,
+            3 => 8.0 * y.powi(3) - 12.0 * y,
+            4 => 16.0 * y.powi(4) - 48.0 * y.powi(2) + 12.0,
+            5 => 32.0 * y.powi(5) - 160.0 * y.powi(3) + 120.0 * y,
+            6 => 64.0 * y.powi(6) - 480.0 * y.powi(4) + 720.0 * y.powi(2) - 120.0,
+            7 => 128.0 * y.powi(7) - 1344.0 * y.powi(5) + 3360.0 * y.powi(3) - 1680.0 * y,
+            8 => {
+                256.0 * y.powi(8) - 3584.0 * y.powi(6) + 13440.0 * y.powi(4) - 13440.0 * y.powi(2)
+                    + 1680.0
+            }
+            9 => {
+                512.0 * y.powi(9) - 9216.0 * y.powi(7) + 48384.0 * y.powi(5) - 80640.0 * y.powi(3)
+                    + 30240.0 * y
+            }
+            _ => return None,
+        };
+        Some(value)
+    }
+
+    #[test]
+    fn hermite_functions_match_explicit_polynomials() {
+        for n in 0..=9 {
+            let factorial: f64 = (1..=n).map(|i| i as f64).product();
+            let normalization = 1.0 / (2_f64.powi(n as i32) * factorial).sqrt();
+            for y in [-3.0, -1.7, -0.5, 0.0, 0.3, 1.0, 2.2, 4.0] {
+                let expected = normalization
+                    * explicit_hermite_polynomial(n, y).unwrap()
+                    * (-y * y / 2.0).exp();
+                let actual = hermite_function(&EnergyLevel(n), y);
+                assert_relative_eq!(expected, actual, epsilon = 1e-12, max_relative = 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn high_levels_are_normalized() {
+        let mass = Mass::new::<kilogram>(9e-31);
+        let ang_freq = Frequency::new::<hertz>(10e16);
+        for n in [20, 150, 300] {
+            let level = EnergyLevel(n);
+            let step = 2e-13;
+            let points = generate_points(-1e-9, 1e-9, step, |x| {
+                pdf(Length::new::<meter>(x), &level, mass, ang_freq)
+            });
+            let integral: f64 = points.iter().map(|p| p.y * step).sum();
+            assert_relative_eq!(1.0, integral, max_relative = 1e-6);
+        }
     }
 
     /// just double checking `value` property
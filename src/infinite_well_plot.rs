@@ -1,118 +1,223 @@
 /// basic infinite well plot
 /// it plots Ψ(x) and PDF(x) for a given energy level, selected via the UI
 /// we use the solved equations for Ψ and PDF
+/// the walls are placed at 0 and L, or centered, with buttons below the parameters
 use crate::{
-    plot::{
-        generate_points, setup_curve, setup_plot_ticks, Curve, CurvePDF, CurveWave, PlotSettings,
+    model::{
+        add_model, is_model_selected, setup_parameter_sliders, ModelParameter, ParameterRange,
+        PotentialModel, H_BAR,
     },
-    ui::{EnergyLevel, PotentialModelInput},
-};
-use bevy::{
-    color::palettes::{
-        css::{GRAY, WHITE},
-        tailwind::GRAY_500,
+    plot::PlotSettings,
+    ui::{
+        add_placement_buttons, EnergyLevel, ParameterSlidersMarker, PotentialModelInput,
+        WellPlacementInputEvent,
     },
-    prelude::*,
 };
-use std::f32::consts::PI;
-use uom::si::{f32::Length, length::meter};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass, Momentum},
+    length::meter,
+    mass::kilogram,
+    momentum::kilogram_meter_per_second,
+};
 
-/// make settings specific to this plot type
-/// needed for bevy's resources specifics
+/// infinite well of a width, with walls at a and b = a + width, where it's placed
+/// V(x) = 0 in [a, b] and infinite outside, Ψ = 0 outside
 #[derive(Resource)]
-pub struct InfiniteWellPlotSettings(PlotSettings);
+pub struct InfiniteWell {
+    pub width: Length,
+    pub mass: Mass,
+    pub placement: WellPlacement,
+}
 
-/// adds this plot to the app
-pub fn add_plot(app: &mut App) {
-    app.add_systems(
-        Update,
-        (
-            setup_pdf,
-            setup_psi,
-            setup_ticks,
-            setup_vertical_dashed_line,
-        )
-            .run_if(is_model_selected),
-    )
-    .insert_resource(InfiniteWellPlotSettings(PlotSettings::default()));
+/// where the well's walls are
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WellPlacement {
+    /// walls at 0 and width
+    #[default]
+    ZeroBased,
+    /// walls at -width/2 and width/2
+    Centered,
 }
 
-/// condition to add this plot
-fn is_model_selected(mode: Res<PotentialModelInput>) -> bool {
-    match *mode {
-        PotentialModelInput::InfiniteWell => true,
-        _ => false,
+impl Default for InfiniteWell {
+    fn default() -> Self {
+        Self {
+            width: Length::new::<meter>(2.0),
+            mass: Mass::new::<kilogram>(9.11e-31),
+            placement: WellPlacement::default(),
+        }
     }
 }
 
-/// adds Ψ screen curve to bevy
-fn setup_psi(
-    mut commands: Commands,
-    energy_level_query: Query<&EnergyLevel>,
-    curve_query: Query<Entity, (With<Curve>, With<CurveWave>)>,
-) {
-    for e in energy_level_query.iter() {
-        let points = generate_scaled_points(|x| psi(x, e));
-        setup_curve(&mut commands, WHITE, e.0, &curve_query, points);
+impl InfiniteWell {
+    /// x (m) of the left and right walls
+    pub fn walls(&self) -> (f64, f64) {
+        let width = self.width.get::<meter>();
+        match self.placement {
+            WellPlacement::ZeroBased => (0.0, width),
+            WellPlacement::Centered => (-width / 2.0, width / 2.0),
+        }
     }
 }
 
-/// adds PDF screen curve to bevy
-fn setup_pdf(
-    mut commands: Commands,
-    energy_level_query: Query<&EnergyLevel>,
-    curve_query: Query<Entity, (With<Curve>, With<CurvePDF>)>,
-) {
-    for e in energy_level_query.iter() {
-        let points = generate_scaled_points(|x| pdf(x, e));
-        setup_curve(&mut commands, GRAY_500, e.0, &curve_query, points);
+impl PotentialModel for InfiniteWell {
+    fn name(&self) -> &'static str {
+        "Infinite well"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            ModelParameter {
+                name: "L",
+                value: self.width.get::<meter>(),
+                unit: "m",
+                range: ParameterRange::new(1e-10, 10.0),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.width = Length::new::<meter>(value),
+            1 => self.mass = Mass::new::<kilogram>(value),
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        let (left, right) = self.walls();
+        let x = x.get::<meter>();
+        if x < left || x > right {
+            return Complex64::new(0.0, 0.0);
+        }
+        Complex64::new(psi(Length::new::<meter>(x - left), level, self.width), 0.0)
     }
-}
 
-/// generates Ψ screen points
-fn generate_scaled_points<F>(function: F) -> Vec<Vec2>
-where
-    F: Fn(Length) -> f32,
-{
-    let domain_points = generate_points(-10.0, 10.0, 0.02, |x| function(Length::new::<meter>(x)));
-    // for now no scaling needed, domain parameters happen to match screen dimensions
-    domain_points
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        energy(level, self.width, self.mass)
+    }
+
+    /// the zero based well's φ_n, shifted to the left wall a: e^(-ipa/ħ) φ_n(p)
+    fn phi(&self, p: Momentum, level: &EnergyLevel) -> Option<Complex64> {
+        let (left, _) = self.walls();
+        let shift =
+            Complex64::from_polar(1.0, -p.get::<kilogram_meter_per_second>() * left / H_BAR);
+        Some(shift * phi(p, level, self.width))
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        let (left, right) = self.walls();
+        let x = x.get::<meter>();
+        if x < left || x > right {
+            Energy::new::<joule>(f64::INFINITY)
+        } else {
+            Energy::new::<joule>(0.0)
+        }
+    }
+
+    /// scaled with the width, such that the well is always 2 screen units wide
+    /// the domain is the same for both placements, with room for the walls on both sides
+    fn plot_settings(&self) -> PlotSettings {
+        let width = self.width.get::<meter>();
+        PlotSettings {
+            domain_range_start: -5.0 * width,
+            domain_range_end: 5.0 * width,
+            sample_step: width / 100.0,
+            screen_scale_x: 2.0 / width,
+            ..PlotSettings::default()
+        }
+    }
+
+    /// n = 0 would be Ψ = 0 everywhere
+    fn min_level(&self) -> u32 {
+        1
+    }
 }
 
-/// Ψ_n(x)
-fn psi(x: Length, level: &EnergyLevel) -> f32 {
-    let l = Length::new::<meter>(2.0);
-    let l_value = l.value;
-    (2.0 / l_value).sqrt() * ((level.0 as f32 * PI * x.value) / l_value).sin()
+/// adds the model, with the systems of its placement buttons
+pub fn add_infinite_well(app: &mut App, model: InfiniteWell) {
+    let id = add_model(app, model);
+    app.add_systems(
+        Update,
+        (
+            setup_placement_buttons.after(setup_parameter_sliders::<InfiniteWell>),
+            listen_placement_ui_inputs,
+        )
+            .run_if(is_model_selected(id)),
+    );
 }
 
-/// PDF for Ψ_n(x)
-fn pdf(x: Length, level: &EnergyLevel) -> f32 {
-    let psi = psi(x, level);
-    psi.powi(2)
+/// adds the placement buttons below the parameter sliders when the model is selected
+fn setup_placement_buttons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    selected: Res<PotentialModelInput>,
+    sliders_query: Query<Entity, With<ParameterSlidersMarker>>,
+) {
+    if !selected.is_changed() {
+        return;
+    }
+    // the sliders' container was just emptied, with the previous buttons
+    let Ok(sliders_id) = sliders_query.get_single() else {
+        return;
+    };
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    add_placement_buttons(&mut commands, sliders_id, &font);
 }
 
-/// draws a vertical dashed line through the right boundary of the well
-fn setup_vertical_dashed_line(mut gizmos: Gizmos, model: Res<PotentialModelInput>) {
-    if *model == PotentialModelInput::InfiniteWell {
-        // for now hardcoded (TODO pass as setting)
-        let x = 2.0;
-        let mut y_start = -10_f32;
-        while y_start < 10_f32 {
-            gizmos.line_2d(
-                Vec2 { x, y: y_start },
-                Vec2 {
-                    x,
-                    y: y_start + 0.06,
-                },
-                GRAY,
-            );
-
-            y_start += 0.1;
+/// places the walls as selected on the UI, the domain is the same for both placements
+fn listen_placement_ui_inputs(
+    mut events: EventReader<WellPlacementInputEvent>,
+    mut model: ResMut<InfiniteWell>,
+) {
+    for event in events.read() {
+        if model.placement != event.placement {
+            model.placement = event.placement;
         }
     }
 }
 
-fn setup_ticks(mut gizmos: Gizmos, settings: Res<InfiniteWellPlotSettings>) {
-    setup_plot_ticks(&mut gizmos, settings.0.clone())
+/// Ψ_n(x) of the zero based well, inside of it
+fn psi(x: Length, level: &EnergyLevel, width: Length) -> f64 {
+    let l_value = width.value;
+    (2.0 / l_value).sqrt() * ((level.0 as f64 * PI * x.value) / l_value).sin()
+}
+
+/// φ_n(p) = (2πħ)^(-1/2) sqrt(2/L) ∫_0^L sin(kx) e^(-iqx) dx, with k = nπ/L and q = p/ħ
+/// the integral is k (1 - (-1)^n e^(-iqL)) / (k^2 - q^2), ∓iL/2 at q = ±k
+fn phi(p: Momentum, level: &EnergyLevel, width: Length) -> Complex64 {
+    let width = width.value;
+    let k = level.0 as f64 * PI / width;
+    let q = p.get::<kilogram_meter_per_second>() / H_BAR;
+    // (-1)^n
+    let sign = (-1_f64).powi(level.0 as i32);
+    let denominator = k * k - q * q;
+    let integral = if k == 0.0 {
+        Complex64::new(0.0, 0.0)
+    } else if denominator.abs() < 1e-9 * k * k {
+        Complex64::new(0.0, -q.signum() * width / 2.0)
+    } else {
+        k * (1.0 - sign * Complex64::from_polar(1.0, -q * width)) / denominator
+    };
+    (2.0 / width).sqrt() / (2.0 * PI * H_BAR).sqrt() * integral
+}
+
+/// E_n = (nπħ/L)^2 / 2m
+fn energy(level: &EnergyLevel, width: Length, mass: Mass) -> Energy {
+    let momentum = level.0 as f64 * PI * H_BAR / width.value;
+    Energy::new::<joule>(momentum.powi(2) / (2.0 * mass.value))
 }
@@ -0,0 +1,261 @@
+/// a second plot at the bottom of the window, left of the UI column, e.g. T(E) or ⟨x⟩(t) next to Ψ(x)
+/// it has its own camera, which renders the inset's render layer into a viewport, so it's not panned or zoomed
+/// with the main plot: the series with `InsetSeries` are drawn there, in inset units, the frame being
+/// [0, INSET_WIDTH] × [0, INSET_HEIGHT]
+/// it's shown while its axes are set, by the plotted data's layer
+use crate::axis::{PlotAxes, UI_COLUMN_WIDTH};
+use crate::plot::{draw_series, Series, SeriesVisibility};
+use bevy::{
+    color::palettes::css::GREEN,
+    prelude::*,
+    render::{
+        camera::{ScalingMode, Viewport},
+        view::RenderLayers,
+    },
+    sprite::Anchor,
+    window::PrimaryWindow,
+};
+
+/// width of the frame, in inset units
+pub const INSET_WIDTH: f32 = 4.0;
+/// height of the frame, in inset units
+pub const INSET_HEIGHT: f32 = 2.0;
+/// room around the frame for the labels (left, bottom, right, top), in inset units
+const INSET_PADDING: (f32, f32, f32, f32) = (0.9, 0.5, 0.2, 0.3);
+/// logical px per inset unit
+const INSET_PX_PER_UNIT: f32 = 70.0;
+/// px between the inset and the window's bottom and the UI column
+const INSET_MARGIN: f32 = 10.0;
+const INSET_LAYER: usize = 1;
+const INSET_BACKGROUND: Color = Color::srgb(0.08, 0.08, 0.08);
+const LABEL_FONT_SIZE: f32 = 12.0;
+/// inset units between the frame and the labels
+const LABEL_OFFSET: f32 = 0.1;
+const TICK_LENGTH: f32 = 0.08;
+/// number of ticks aimed for on an axis, fewer than on the main plot as the labels have less room
+const INSET_TICKS: f64 = 4.0;
+
+/// the inset's camera
+#[derive(Component)]
+pub struct InsetCamera;
+
+/// a series drawn on the inset instead of the main plot
+#[derive(Component)]
+pub struct InsetSeries;
+
+/// a tick label or axis title of the inset
+#[derive(Component)]
+struct InsetLabel;
+
+/// what the inset's axes show, None to hide the inset
+/// the axes' screen scales are inset units per unit of their quantity
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct InsetAxes {
+    pub axes: Option<PlotAxes>,
+    /// y = 0 is in the frame's middle instead of at its bottom, for values of both signs
+    pub centered_y: bool,
+}
+
+impl InsetAxes {
+    /// inset units between the frame's bottom and y = 0
+    pub fn origin_y(&self) -> f32 {
+        if self.centered_y {
+            INSET_HEIGHT / 2.0
+        } else {
+            0.0
+        }
+    }
+
+    /// (inset y, label) of the y ticks, and the y title
+    fn y_ticks(&self, axes: &PlotAxes) -> (Vec<(f32, String)>, String) {
+        let origin = self.origin_y();
+        let (ticks, title) = axes.y.ticks(-origin, INSET_HEIGHT - origin, INSET_TICKS);
+        let ticks = ticks
+            .into_iter()
+            .map(|(y, text)| (y + origin, text))
+            .collect();
+        (ticks, title)
+    }
+}
+
+/// gizmos drawn on the inset's render layer
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct InsetGizmos;
+
+pub struct InsetPlugin;
+
+impl Plugin for InsetPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InsetAxes>()
+            .init_gizmo_group::<InsetGizmos>()
+            .add_systems(Startup, setup_inset)
+            .add_systems(
+                Update,
+                (update_inset_camera, draw_inset, setup_inset_labels),
+            );
+    }
+}
+
+/// size of the inset with its padding, in inset units
+fn inset_size() -> Vec2 {
+    let (left, bottom, right, top) = INSET_PADDING;
+    Vec2::new(INSET_WIDTH + left + right, INSET_HEIGHT + bottom + top)
+}
+
+fn setup_inset(mut commands: Commands, mut config_store: ResMut<GizmoConfigStore>) {
+    let (left, bottom, _, _) = INSET_PADDING;
+    let size = inset_size();
+    let center = Vec2::new(size.x / 2.0 - left, size.y / 2.0 - bottom);
+    commands.spawn((
+        InsetCamera,
+        Camera2dBundle {
+            camera: Camera {
+                // after the main plot
+                order: 1,
+                is_active: false,
+                clear_color: ClearColorConfig::Custom(INSET_BACKGROUND),
+                ..default()
+            },
+            projection: OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: size.x,
+                    height: size.y,
+                },
+                ..default()
+            },
+            transform: Transform::from_translation(center.extend(0.0)),
+            ..default()
+        },
+        RenderLayers::layer(INSET_LAYER),
+    ));
+    let (config, _) = config_store.config_mut::<InsetGizmos>();
+    config.render_layers = RenderLayers::layer(INSET_LAYER);
+}
+
+/// the inset's viewport in the window, None if the window is too small for it
+fn inset_viewport(window: &Window) -> Option<Viewport> {
+    let scale_factor = window.scale_factor();
+    let size = inset_size() * INSET_PX_PER_UNIT;
+    let left = window.width() - UI_COLUMN_WIDTH - INSET_MARGIN - size.x;
+    let top = window.height() - INSET_MARGIN - size.y;
+    if left < 0.0 || top < 0.0 {
+        return None;
+    }
+    Some(Viewport {
+        physical_position: (Vec2::new(left, top) * scale_factor).as_uvec2(),
+        physical_size: (size * scale_factor).as_uvec2(),
+        ..default()
+    })
+}
+
+/// shows the inset while it has axes, in the window's corner
+fn update_inset_camera(
+    axes: Res<InsetAxes>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut camera_query: Query<&mut Camera, With<InsetCamera>>,
+) {
+    let Ok(mut camera) = camera_query.get_single_mut() else {
+        return;
+    };
+    let viewport = window_query.get_single().ok().and_then(inset_viewport);
+    let active = axes.axes.is_some() && viewport.is_some();
+    if camera.is_active != active {
+        camera.is_active = active;
+    }
+    let rect = |viewport: &Option<Viewport>| {
+        viewport
+            .as_ref()
+            .map(|v| (v.physical_position, v.physical_size))
+    };
+    if rect(&camera.viewport) != rect(&viewport) {
+        camera.viewport = viewport;
+    }
+}
+
+/// draws the frame with the ticks, and the visible inset series
+fn draw_inset(
+    axes: Res<InsetAxes>,
+    series_query: Query<&Series, With<InsetSeries>>,
+    visibility: Res<SeriesVisibility>,
+    mut gizmos: Gizmos<InsetGizmos>,
+) {
+    let Some(plot_axes) = &axes.axes else {
+        return;
+    };
+    gizmos.rect_2d(
+        Vec2::new(INSET_WIDTH / 2.0, INSET_HEIGHT / 2.0),
+        0.0,
+        Vec2::new(INSET_WIDTH, INSET_HEIGHT),
+        GREEN,
+    );
+    let (x_ticks, _) = plot_axes.x.ticks(0.0, INSET_WIDTH, INSET_TICKS);
+    for (x, _) in x_ticks {
+        gizmos.line_2d(Vec2::new(x, 0.0), Vec2::new(x, TICK_LENGTH), GREEN);
+    }
+    let (y_ticks, _) = axes.y_ticks(plot_axes);
+    for (y, _) in y_ticks {
+        gizmos.line_2d(Vec2::new(0.0, y), Vec2::new(TICK_LENGTH, y), GREEN);
+    }
+    for series in series_query.iter().filter(|s| visibility.is_visible(s.id)) {
+        draw_series(&mut gizmos, series);
+    }
+}
+
+/// replaces the tick labels and the titles when the axes change
+fn setup_inset_labels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    axes: Res<InsetAxes>,
+    label_query: Query<Entity, With<InsetLabel>>,
+) {
+    if !axes.is_changed() {
+        return;
+    }
+    for entity in label_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(plot_axes) = &axes.axes else {
+        return;
+    };
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    let mut spawn_label = |text: String, position: Vec2, anchor: Anchor| {
+        commands.spawn((
+            InsetLabel,
+            Text2dBundle {
+                text: Text::from_section(
+                    text,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: LABEL_FONT_SIZE,
+                        color: GREEN.into(),
+                    },
+                ),
+                text_anchor: anchor,
+                // px to inset units
+                transform: Transform::from_translation(position.extend(1.0))
+                    .with_scale(Vec3::splat(1.0 / INSET_PX_PER_UNIT)),
+                ..default()
+            },
+            RenderLayers::layer(INSET_LAYER),
+        ));
+    };
+
+    let (x_ticks, x_title) = plot_axes.x.ticks(0.0, INSET_WIDTH, INSET_TICKS);
+    for (x, text) in x_ticks {
+        spawn_label(text, Vec2::new(x, -LABEL_OFFSET), Anchor::TopCenter);
+    }
+    spawn_label(
+        x_title,
+        Vec2::new(INSET_WIDTH, INSET_HEIGHT + LABEL_OFFSET),
+        Anchor::BottomRight,
+    );
+    let (y_ticks, y_title) = axes.y_ticks(plot_axes);
+    for (y, text) in y_ticks {
+        spawn_label(text, Vec2::new(-LABEL_OFFSET, y), Anchor::CenterRight);
+    }
+    spawn_label(
+        y_title,
+        Vec2::new(0.0, INSET_HEIGHT + LABEL_OFFSET),
+        Anchor::BottomLeft,
+    );
+}
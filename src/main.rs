@@ -1,18 +1,414 @@
+mod app;
+mod axis;
+mod barrier_plot;
 mod camera_controller;
+mod crank_nicolson;
+mod delta_barrier_plot;
+mod delta_well_plot;
+mod double_delta_well_plot;
+mod expectation;
+mod finite_difference_solver;
+mod finite_well_plot;
 mod harmonic_oscillator_plot;
 mod infinite_well_plot;
+mod inset;
+mod model;
+mod momentum;
+// no model on the UI is solved numerically, the numerical model is exercised by the tests
+#[allow(dead_code)]
+mod numerical_model;
+mod numerics;
+mod numerov_solver;
+mod piecewise_potential_plot;
 mod plot;
+mod potential_plot;
+#[cfg(test)]
+mod propagator_test_setup;
+mod scattering;
+mod split_operator;
+mod step_potential_plot;
+mod superposition;
+mod text_input;
+mod time_evolution;
+mod transfer_matrix;
 mod ui;
+#[cfg(test)]
+mod validation;
+mod wave_function_plot;
 
-use bevy::app::App;
+use app::add_app;
+use barrier_plot::Barrier;
+use bevy::{app::App, DefaultPlugins};
+use delta_barrier_plot::DeltaBarrier;
+use delta_well_plot::DeltaWell;
+use double_delta_well_plot::DoubleDeltaWell;
+use finite_well_plot::FiniteWell;
+use harmonic_oscillator_plot::HarmonicOscillator;
+use infinite_well_plot::{add_infinite_well, InfiniteWell, WellPlacement};
+use model::add_model;
+use piecewise_potential_plot::add_piecewise_potential;
+use step_potential_plot::PotentialStep;
 
-/// all the plots are added here
-/// the plots decide dynamically to actually attach themsevelves,
-/// via a condition defined in the respective files.
 fn main() {
     let app = &mut App::new();
-    plot::add_plot(app);
-    infinite_well_plot::add_plot(app);
-    harmonic_oscillator_plot::add_plot(app);
+    add_app(app, DefaultPlugins);
+    add_models(app);
     app.run();
 }
+
+/// all the models are registered here
+/// the UI shows a button for each, in the order they're added,
+/// and a model's plot systems run only while it's selected.
+fn add_models(app: &mut App) {
+    // centered like the finite well, to compare them, it can be placed at 0 on the UI
+    add_infinite_well(
+        app,
+        InfiniteWell {
+            placement: WellPlacement::Centered,
+            ..InfiniteWell::default()
+        },
+    );
+    add_model(app, FiniteWell::default());
+    add_model(app, HarmonicOscillator::default());
+    add_model(app, PotentialStep::default());
+    add_model(app, Barrier::default());
+    add_piecewise_potential(app);
+    add_model(app, DeltaWell::default());
+    add_model(app, DeltaBarrier::default());
+    add_model(app, DoubleDeltaWell::default());
+}
+
+#[cfg(test)]
+mod test {
+    use bevy::{
+        input::mouse::{MouseScrollUnit, MouseWheel},
+        log::LogPlugin,
+        prelude::*,
+        render::{
+            settings::{RenderCreation, WgpuSettings},
+            RenderPlugin,
+        },
+        window::{ExitCondition, PrimaryWindow},
+        winit::WinitPlugin,
+    };
+
+    use super::add_models;
+    use crate::{
+        app::add_app,
+        expectation::{ExpectationHistory, ExpectationHistoryCurve, MOMENTUM_SERIES},
+        infinite_well_plot::{InfiniteWell, WellPlacement},
+        inset::InsetAxes,
+        model::PotentialModelRegistry,
+        piecewise_potential_plot::PiecewisePotential,
+        plot::{Series, SeriesVisibility},
+        text_input::{TextInput, TextInputTarget},
+        ui::{
+            EnergyLevel, EnergyLevelControlsMarker, EnergyLevelRange, ExpectationLabelMarker,
+            PotentialModelInput, SegmentInput, SegmentInputEvent, TimeEvolutionInput,
+            TimeEvolutionInputEvent, UiColumnMarker, WellPlacementInputEvent, WellPlacementMarker,
+        },
+    };
+
+    /// the app without a window and a GPU
+    fn headless_app() -> App {
+        app_with_window(None)
+    }
+
+    /// the app without a GPU, with a window that isn't opened, for the UI's layout
+    fn app_with_window(window: Option<Window>) -> App {
+        let mut app = App::new();
+        let plugins = DefaultPlugins
+            .build()
+            .disable::<WinitPlugin>()
+            .disable::<LogPlugin>()
+            .set(WindowPlugin {
+                primary_window: window,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                render_creation: RenderCreation::Automatic(WgpuSettings {
+                    backends: None,
+                    ..default()
+                }),
+                ..default()
+            });
+        add_app(&mut app, plugins);
+        add_models(&mut app);
+        app.finish();
+        app.cleanup();
+        app
+    }
+
+    /// selects the model registered with the name, independently of the order the models are added in
+    fn select_model(app: &mut App, name: &str) {
+        let id = app
+            .world()
+            .resource::<PotentialModelRegistry>()
+            .models
+            .iter()
+            .find(|model| model.name == name)
+            .map(|model| model.id)
+            .unwrap_or_else(|| panic!("no model named {}", name));
+        *app.world_mut().resource_mut::<PotentialModelInput>() = PotentialModelInput(id);
+    }
+
+    fn series_entities(app: &mut App) -> Vec<Entity> {
+        let mut entities: Vec<Entity> = app
+            .world_mut()
+            .query_filtered::<Entity, With<Series>>()
+            .iter(app.world())
+            .collect();
+        entities.sort();
+        entities
+    }
+
+    #[test]
+    fn curves_are_not_respawned_every_frame() {
+        let mut app = headless_app();
+        // setup, and the curves of the initial state
+        for _ in 0..5 {
+            app.update();
+        }
+        let entity_count = app.world().entities().len();
+        let series = series_entities(&mut app);
+        assert!(!series.is_empty());
+
+        for _ in 0..50 {
+            app.update();
+            assert_eq!(entity_count, app.world().entities().len());
+        }
+        assert_eq!(series, series_entities(&mut app));
+    }
+
+    #[test]
+    fn energy_levels_follow_the_selected_model() {
+        let mut app = headless_app();
+        let level = |app: &mut App| *app.world_mut().query::<&EnergyLevel>().single(app.world());
+        app.update();
+        app.update();
+        // the infinite well's levels start at 1
+        assert_eq!(
+            EnergyLevelRange { min: 1, max: 10 },
+            *app.world().resource::<EnergyLevelRange>()
+        );
+
+        // the harmonic oscillator's ground state
+        select_model(&mut app, "Harmonic oscillator");
+        app.update();
+        assert_eq!(
+            EnergyLevelRange { min: 0, max: 300 },
+            *app.world().resource::<EnergyLevelRange>()
+        );
+        *app.world_mut()
+            .query::<&mut EnergyLevel>()
+            .single_mut(app.world_mut()) = EnergyLevel(0);
+        app.update();
+        assert_eq!(EnergyLevel(0), level(&mut app));
+
+        // clamped to the infinite well's lowest level
+        select_model(&mut app, "Infinite well");
+        app.update();
+        assert_eq!(EnergyLevel(1), level(&mut app));
+    }
+
+    #[test]
+    fn scattering_models_replace_the_level_with_the_energy() {
+        let mut app = headless_app();
+        let level_controls_display = |app: &mut App| {
+            app.world_mut()
+                .query_filtered::<&Style, With<EnergyLevelControlsMarker>>()
+                .single(app.world())
+                .display
+        };
+        app.update();
+        app.update();
+        assert!(app.world().resource::<InsetAxes>().axes.is_none());
+        assert_eq!(Display::Flex, level_controls_display(&mut app));
+
+        select_model(&mut app, "Potential step");
+        app.update();
+        app.update();
+        assert!(app.world().resource::<InsetAxes>().axes.is_some());
+        assert_eq!(Display::None, level_controls_display(&mut app));
+        assert_eq!(
+            EnergyLevelRange { min: 0, max: 0 },
+            *app.world().resource::<EnergyLevelRange>()
+        );
+
+        select_model(&mut app, "Harmonic oscillator");
+        app.update();
+        assert!(app.world().resource::<InsetAxes>().axes.is_none());
+        assert_eq!(Display::Flex, level_controls_display(&mut app));
+    }
+
+    #[test]
+    fn segment_editor_adds_and_removes_segments() {
+        let mut app = headless_app();
+        let segment_inputs = |app: &mut App| {
+            app.world_mut()
+                .query::<&TextInput>()
+                .iter(app.world())
+                .filter(|input| matches!(input.target, TextInputTarget::SegmentWidth(_)))
+                .count()
+        };
+        app.update();
+        assert_eq!(0, segment_inputs(&mut app));
+
+        // a double barrier by default
+        select_model(&mut app, "Custom piecewise");
+        app.update();
+        app.update();
+        assert_eq!(3, segment_inputs(&mut app));
+
+        app.world_mut().send_event(SegmentInputEvent {
+            input: SegmentInput::Add,
+        });
+        app.update();
+        app.update();
+        assert_eq!(
+            4,
+            app.world()
+                .resource::<PiecewisePotential>()
+                .segments()
+                .len()
+        );
+        assert_eq!(4, segment_inputs(&mut app));
+
+        app.world_mut().send_event(SegmentInputEvent {
+            input: SegmentInput::Remove(0),
+        });
+        app.update();
+        app.update();
+        assert_eq!(3, segment_inputs(&mut app));
+
+        // the editor is removed with the model's sliders
+        select_model(&mut app, "Infinite well");
+        app.update();
+        app.update();
+        assert_eq!(0, segment_inputs(&mut app));
+    }
+
+    #[test]
+    fn infinite_well_walls_are_placed_on_the_ui() {
+        let mut app = headless_app();
+        let placement_buttons = |app: &mut App| {
+            app.world_mut()
+                .query::<&WellPlacementMarker>()
+                .iter(app.world())
+                .count()
+        };
+        app.update();
+        app.update();
+        assert_eq!(2, placement_buttons(&mut app));
+        assert_eq!(
+            WellPlacement::Centered,
+            app.world().resource::<InfiniteWell>().placement
+        );
+
+        app.world_mut().send_event(WellPlacementInputEvent {
+            placement: WellPlacement::ZeroBased,
+        });
+        app.update();
+        let well = app.world().resource::<InfiniteWell>();
+        assert_eq!(0.0, well.walls().0);
+
+        // removed with the model's sliders
+        select_model(&mut app, "Harmonic oscillator");
+        app.update();
+        app.update();
+        assert_eq!(0, placement_buttons(&mut app));
+    }
+
+    #[test]
+    fn expectation_history_is_plotted_on_the_inset_during_the_time_evolution() {
+        let mut app = headless_app();
+        let history_curves = |app: &mut App| {
+            app.world_mut()
+                .query::<&ExpectationHistoryCurve>()
+                .iter(app.world())
+                .count()
+        };
+        app.update();
+        app.update();
+        app.world_mut().send_event(TimeEvolutionInputEvent {
+            input: TimeEvolutionInput::Play,
+        });
+        for _ in 0..10 {
+            app.update();
+        }
+        let history = &app.world().resource::<ExpectationHistory>().0;
+        assert!(history.len() > 5);
+        assert!(history.windows(2).all(|pair| pair[0].time < pair[1].time));
+        // hidden with the ⟨x⟩ and ⟨p⟩ toggles, shown with either
+        assert!(app.world().resource::<InsetAxes>().axes.is_none());
+        app.world_mut()
+            .resource_mut::<SeriesVisibility>()
+            .toggle(MOMENTUM_SERIES);
+        app.update();
+        let inset = app.world().resource::<InsetAxes>();
+        assert!(inset.axes.is_some() && inset.centered_y);
+        assert_eq!(2, history_curves(&mut app));
+
+        // the scattering curves get the inset back
+        select_model(&mut app, "Potential step");
+        app.update();
+        app.update();
+        assert!(app.world().resource::<ExpectationHistory>().0.is_empty());
+        let inset = app.world().resource::<InsetAxes>();
+        assert!(inset.axes.is_some() && !inset.centered_y);
+        assert_eq!(0, history_curves(&mut app));
+    }
+
+    #[test]
+    fn ui_column_fits_the_window_and_scrolls_to_its_end() {
+        let height = 720.0;
+        let mut app = app_with_window(Some(Window {
+            resolution: (1280.0, height).into(),
+            ..default()
+        }));
+        for _ in 0..3 {
+            app.update();
+        }
+        let node_size =
+            |app: &mut App, entity: Entity| app.world().get::<Node>(entity).unwrap().size();
+        let column = app
+            .world_mut()
+            .query_filtered::<Entity, With<UiColumnMarker>>()
+            .single(app.world());
+        let column_size = node_size(&mut app, column);
+        assert!(column_size.y > 0.0 && column_size.y <= height);
+        // the expectation values at the column's end
+        let label = app
+            .world_mut()
+            .query_filtered::<Entity, With<ExpectationLabelMarker>>()
+            .single(app.world());
+        let label_bottom = |app: &mut App| {
+            let center = app
+                .world()
+                .get::<GlobalTransform>(label)
+                .unwrap()
+                .translation();
+            center.y + node_size(app, label).y / 2.0
+        };
+        assert!(label_bottom(&mut app) > height);
+
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world());
+        app.world_mut()
+            .get_mut::<Window>(window)
+            .unwrap()
+            .set_cursor_position(Some(Vec2::new(1280.0 - 50.0, 300.0)));
+        app.world_mut().send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: -1000.0,
+            window,
+        });
+        app.update();
+        app.update();
+        assert!(label_bottom(&mut app) <= height);
+    }
+}
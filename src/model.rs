@@ -0,0 +1,594 @@
+/// potential models and the registry they're added to
+/// a model provides the solved equations (Ψ, E, V) and its default plot settings,
+/// the plot systems and the UI button for a model are generated when registering it
+use crate::{
+    axis::{AxisUnit, PlotAxes, PlotAxis},
+    expectation::{setup_expectation_curves, update_expectation_values},
+    momentum::{momentum_plot_settings, MomentumEigenstates},
+    plot::{AdaptiveSampling, PixelSize, PlotSettings},
+    potential_plot::{setup_potential_curves, update_energy_scale},
+    scattering::{setup_scattering_curves, Scattering},
+    superposition::{animate_superposition, Superposition, WeightedLevel},
+    text_input::{TextInput, TextInputTarget},
+    time_evolution::{is_stationary, update_wave_packet},
+    ui::{
+        add_parameter_slider, parameter_value_text, parse_f64, slider_fill_width, CurveOptions,
+        EnergyControlMarker, EnergyLevel, EnergyLevelControlsMarker, EnergyLevelRange,
+        ModelInfoLabelMarker, ParameterInputEvent, ParameterSliderFillMarker,
+        ParameterSlidersMarker, PotentialModelInput, UiInputsEvent,
+    },
+    wave_function_plot::{
+        setup_wave_function_curves, CurveExtents, CurveScales, CurveStyle, WaveFunctionCurve,
+    },
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::{f64::consts::PI, marker::PhantomData};
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass, Momentum},
+    length::meter,
+    mass::kilogram,
+};
+
+/// reduced Planck constant (J·s)
+pub const H_BAR: f64 = 1.054_571_817e-34;
+
+/// a quantum model, defined by its potential
+/// to add a model to the app, implement this and register it with `add_model`
+pub trait PotentialModel: Resource {
+    /// name shown on the model's button
+    fn name(&self) -> &'static str;
+
+    /// physical parameters of the model (e.g. mass, well width)
+    fn parameters(&self) -> Vec<ModelParameter>;
+
+    /// sets the parameter at index in `parameters`, the value is within the parameter's range
+    /// anything derived from the parameters (e.g. solved states) is updated here
+    fn set_parameter(&mut self, index: usize, value: f64);
+
+    /// mass of the particle
+    fn mass(&self) -> Mass;
+
+    /// Ψ_n(x)
+    /// complex, to be combined with other (complex) states, though the models' eigenstates are real
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64;
+
+    /// E_n
+    fn energy(&self, level: &EnergyLevel) -> Energy;
+
+    /// V(x), infinite at walls
+    fn potential(&self, x: Length) -> Energy;
+
+    /// φ_n(p), the momentum space Ψ_n, where its closed form is known
+    /// None to compute it with an FFT of Ψ_n
+    fn phi(&self, _p: Momentum, _level: &EnergyLevel) -> Option<Complex64> {
+        None
+    }
+
+    /// plot settings for the current parameters
+    fn plot_settings(&self) -> PlotSettings;
+
+    /// plot settings of φ(p) for the current parameters, the domain in kg·m/s
+    /// by default the momenta of waves with up to 8 half wave lengths per screen unit of x
+    fn momentum_plot_settings(&self) -> PlotSettings {
+        momentum_plot_settings(8.0 * PI * H_BAR * self.plot_settings().screen_scale_x)
+    }
+
+    /// lowest energy level that can be selected, e.g. 1 for models whose n counts the antinodes
+    fn min_level(&self) -> u32 {
+        0
+    }
+
+    /// highest energy level that can be selected
+    fn max_level(&self) -> u32 {
+        10
+    }
+
+    /// index in `parameters` of E, for models whose energy is continuous (scattering) instead of quantized
+    /// E is set on the UI instead of a level, and Ψ and E are the same for all levels
+    fn energy_parameter(&self) -> Option<usize> {
+        None
+    }
+
+    /// transmission and reflection of a plane wave with energy E incident from the left, for scattering models
+    fn scattering(&self, _energy: Energy) -> Option<Scattering> {
+        None
+    }
+
+    /// shown below E on the UI, e.g. solved quantities that aren't levels
+    fn info(&self) -> Option<String> {
+        None
+    }
+
+    /// δ potentials added to V(x), which can't be sampled
+    /// Ψ has a cusp at each of them, where its curves aren't smoothed
+    fn deltas(&self) -> Vec<Delta> {
+        vec![]
+    }
+}
+
+/// x (m) of the model's δ potentials, where Ψ has cusps
+pub fn cusps<M: PotentialModel>(model: &M) -> Vec<f64> {
+    model
+        .deltas()
+        .iter()
+        .map(|delta| delta.position.get::<meter>())
+        .collect()
+}
+
+/// a δ potential α δ(x - x₀)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Delta {
+    pub position: Length,
+    /// α (J·m), positive for a barrier, negative for a well
+    pub strength: f64,
+}
+
+impl Delta {
+    /// mα^2/2ħ^2, the binding energy of a lone δ well, and the energy at which a lone δ barrier transmits half
+    pub fn energy(&self, mass: Mass) -> Energy {
+        Energy::new::<joule>(mass.get::<kilogram>() * self.strength.powi(2) / (2.0 * H_BAR.powi(2)))
+    }
+
+    /// adds the δ to V (J) sampled on a grid, as α/step at the point nearest to x₀, if it's on the grid
+    pub fn add_to_grid(&self, potential: &mut [f64], start: f64, step: f64) {
+        let index = ((self.position.get::<meter>() - start) / step).round();
+        if index >= 0.0 && (index as usize) < potential.len() {
+            potential[index as usize] += self.strength / step;
+        }
+    }
+}
+
+/// a physical parameter of a model, as displayed on the UI
+#[derive(Debug, Clone)]
+pub struct ModelParameter {
+    /// symbol or short name
+    pub name: &'static str,
+    /// value in SI base units
+    pub value: f64,
+    /// SI unit symbol
+    pub unit: &'static str,
+    /// accepted values
+    pub range: ParameterRange,
+}
+
+/// accepted values of a parameter, min > 0
+/// spread logarithmically on a slider, as the parameters span orders of magnitude
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ParameterRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ParameterRange {
+    pub fn new(min: f64, max: f64) -> Self {
+        Self { min, max }
+    }
+
+    pub fn clamp(&self, value: f64) -> f64 {
+        value.clamp(self.min, self.max)
+    }
+
+    /// position of a value on a slider, in [0, 1]
+    pub fn fraction(&self, value: f64) -> f64 {
+        (self.clamp(value) / self.min).ln() / (self.max / self.min).ln()
+    }
+
+    /// value at a position on a slider, in [0, 1]
+    pub fn value(&self, fraction: f64) -> f64 {
+        let value = self.min * (self.max / self.min).powf(fraction.clamp(0.0, 1.0));
+        // powf can be off by rounding at the ends
+        self.clamp(value)
+    }
+}
+
+/// identifies a registered model, it's the index in the registry
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ModelId(pub usize);
+
+/// a model as known by the registry
+#[derive(Debug, Clone)]
+pub struct RegisteredModel {
+    pub id: ModelId,
+    pub name: &'static str,
+}
+
+/// all the models added to the app, in the order they were added
+/// the UI generates a button for each of them
+#[derive(Resource, Debug, Default)]
+pub struct PotentialModelRegistry {
+    pub models: Vec<RegisteredModel>,
+}
+
+impl PotentialModelRegistry {
+    fn register(&mut self, name: &'static str) -> ModelId {
+        let id = ModelId(self.models.len());
+        self.models.push(RegisteredModel { id, name });
+        id
+    }
+}
+
+/// make settings specific to a model
+/// needed for bevy's resources specifics
+#[derive(Resource)]
+pub struct ModelPlotSettings<M: PotentialModel> {
+    pub settings: PlotSettings,
+    /// of the momentum space plot
+    pub momentum: PlotSettings,
+    model: PhantomData<M>,
+}
+
+impl<M: PotentialModel> ModelPlotSettings<M> {
+    pub fn new(model: &M) -> Self {
+        Self {
+            settings: model.plot_settings(),
+            momentum: model.momentum_plot_settings(),
+            model: PhantomData,
+        }
+    }
+}
+
+/// adds a model to the registry and its plot systems to the app
+/// the systems run only while the model is selected
+pub fn add_model<M: PotentialModel>(app: &mut App, model: M) -> ModelId {
+    let id = app
+        .world_mut()
+        .resource_mut::<PotentialModelRegistry>()
+        .register(model.name());
+
+    app.insert_resource(ModelPlotSettings::new(&model))
+        .insert_resource(model)
+        .add_systems(
+            Update,
+            (
+                update_energy_scale::<M>,
+                setup_wave_function::<M>
+                    .run_if(is_stationary)
+                    .after(update_energy_scale::<M>),
+                update_wave_packet::<M>.after(update_energy_scale::<M>),
+                setup_potential_curves::<M>.after(update_energy_scale::<M>),
+                update_expectation_values::<M>
+                    .after(update_wave_packet::<M>)
+                    .after(animate_superposition::<M>),
+                setup_expectation_curves::<M>.after(update_expectation_values::<M>),
+                setup_scattering_curves::<M>,
+                animate_superposition::<M>,
+                update_plot_axes::<M>,
+                update_model_info_label::<M>,
+                update_energy_level_range::<M>,
+                listen_parameter_ui_inputs::<M>,
+                setup_parameter_sliders::<M>,
+                update_parameter_sliders::<M>,
+            )
+                .run_if(is_model_selected(id)),
+        );
+    id
+}
+
+/// condition to run a model's systems
+pub fn is_model_selected(id: ModelId) -> impl Fn(Res<PotentialModelInput>) -> bool {
+    move |selected: Res<PotentialModelInput>| selected.0 == id
+}
+
+/// Ψ of the superposition sampled for the screen, with the extents its curves' scale is fitted to
+struct SampledWaveFunction {
+    levels: Vec<WeightedLevel>,
+    extents: CurveExtents,
+    samples: Vec<(f64, Complex64)>,
+    /// φ_n(p) of the levels with the extents of φ, computed when φ is first shown
+    momentum: Option<(MomentumEigenstates, CurveExtents)>,
+}
+
+/// adds the screen curves of the superposition of eigenstates (a single one by default) to bevy,
+/// in position or momentum space
+/// Ψ is resampled when the superposition, the model, the settings or the zoom change, and the curves
+/// are only replaced then, or when how they're drawn changes
+/// the curves' scale is fitted when the superposition's levels or the model change, not with the time
+#[allow(clippy::too_many_arguments)]
+fn setup_wave_function<M: PotentialModel>(
+    mut commands: Commands,
+    superposition: Res<Superposition>,
+    curve_query: Query<Entity, With<WaveFunctionCurve>>,
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    style: CurveStyle,
+    pixel_size: Res<PixelSize>,
+    mut curve_scales: ResMut<CurveScales>,
+    mut sampled: Local<Option<SampledWaveFunction>>,
+) {
+    let refit = match &*sampled {
+        Some(sampled) => {
+            model.is_changed() || settings.is_changed() || sampled.levels != superposition.levels
+        }
+        None => true,
+    };
+    let resample = refit || superposition.is_changed() || pixel_size.is_changed();
+    if !(resample || style.is_changed()) {
+        return;
+    }
+    let momentum_settings = &settings.momentum;
+    let settings = &settings.settings;
+    if refit {
+        *sampled = Some(SampledWaveFunction {
+            levels: superposition.levels.clone(),
+            extents: superposition.extents(&*model, settings),
+            samples: vec![],
+            momentum: None,
+        });
+    }
+    let Some(sampled) = sampled.as_mut() else {
+        return;
+    };
+
+    if style.options.momentum {
+        let coefficients = superposition.coefficients(&*model);
+        let (eigenstates, extents) = sampled.momentum.get_or_insert_with(|| {
+            let levels: Vec<EnergyLevel> = superposition.levels.iter().map(|l| l.level).collect();
+            let eigenstates =
+                MomentumEigenstates::new(&*model, settings, momentum_settings, &levels);
+            let extents = eigenstates.extents(&coefficients);
+            (eigenstates, extents)
+        });
+        let scales = CurveScales::fit(extents, momentum_settings, style.options.shared_scale);
+        curve_scales.set_if_neq(scales);
+        setup_wave_function_curves(
+            &mut commands,
+            &curve_query,
+            &eigenstates.superpose(&coefficients),
+            &[],
+            momentum_settings,
+            &scales,
+            &style.options,
+            &style.visibility,
+            None,
+        );
+        return;
+    }
+
+    // not kept up to date while φ is shown
+    if resample || style.options.is_changed() {
+        let sampling = AdaptiveSampling::new(settings, *pixel_size);
+        sampled.samples =
+            superposition.sample_curves(&*model, settings, &sampling, &sampled.extents);
+    }
+    let scales = CurveScales::fit(&sampled.extents, settings, style.options.shared_scale);
+    curve_scales.set_if_neq(scales);
+
+    let energy_offset = style.options.energy_offset.then(|| {
+        style
+            .energy_scale
+            .screen_y(superposition.mean_energy(&*model))
+    });
+    setup_wave_function_curves(
+        &mut commands,
+        &curve_query,
+        &sampled.samples,
+        &cusps(&*model),
+        settings,
+        &scales,
+        &style.options,
+        &style.visibility,
+        energy_offset,
+    );
+}
+
+/// x is the model's domain, y Ψ with its fitted scale, or p and φ in momentum space
+fn update_plot_axes<M: PotentialModel>(
+    settings: Res<ModelPlotSettings<M>>,
+    scales: Res<CurveScales>,
+    options: Res<CurveOptions>,
+    mut axes: ResMut<PlotAxes>,
+) {
+    let plot_axes = if options.momentum {
+        PlotAxes {
+            x: PlotAxis {
+                symbol: "p",
+                unit: AxisUnit::Other("kg·m/s"),
+                screen_scale: settings.momentum.screen_scale_x,
+            },
+            y: PlotAxis {
+                symbol: "φ",
+                unit: AxisUnit::Other("(kg·m/s)^-1/2"),
+                screen_scale: scales.psi,
+            },
+        }
+    } else {
+        PlotAxes {
+            x: PlotAxis {
+                symbol: "x",
+                unit: AxisUnit::Length,
+                screen_scale: settings.settings.screen_scale_x,
+            },
+            y: PlotAxis {
+                symbol: "Ψ",
+                unit: AxisUnit::Other("m^-1/2"),
+                screen_scale: scales.psi,
+            },
+        }
+    };
+    axes.set_if_neq(plot_axes);
+}
+
+/// updates the UI label with the current energy
+fn update_model_info_label<M: PotentialModel>(
+    model: Res<M>,
+    energy_level_query: Query<&EnergyLevel>,
+    mut label_query: Query<&mut Text, With<ModelInfoLabelMarker>>,
+) {
+    for energy_level in energy_level_query.iter() {
+        let mut info = format!("E: {:.2e} J", model.energy(energy_level).get::<joule>());
+        if let Some(model_info) = model.info() {
+            info.push('\n');
+            info.push_str(&model_info);
+        }
+
+        for mut text in label_query.iter_mut() {
+            if text.sections[0].value != info {
+                text.sections[0].value = info.clone();
+            }
+        }
+    }
+}
+
+/// sets the parameters changed on the UI, with the sliders or text inputs
+/// the plot settings follow, so e.g. a wider well still fits on the screen
+fn listen_parameter_ui_inputs<M: PotentialModel>(
+    mut slider_events: EventReader<ParameterInputEvent>,
+    mut text_events: EventReader<UiInputsEvent>,
+    selected: Res<PotentialModelInput>,
+    mut model: ResMut<M>,
+    mut settings: ResMut<ModelPlotSettings<M>>,
+    mut input_query: Query<&mut TextInput>,
+) {
+    // (index, value) of the parameters to set
+    let mut changes: Vec<(usize, f64)> = slider_events
+        .read()
+        .filter(|event| event.model == selected.0)
+        .map(|event| (event.index, event.value))
+        .collect();
+
+    let parameters = model.parameters();
+    for event in text_events.read() {
+        let TextInputTarget::Parameter(index) = event.target else {
+            continue;
+        };
+        // the inputs of a previously selected model are gone
+        let Ok(mut input) = input_query.get_mut(event.input) else {
+            continue;
+        };
+        let Some(parameter) = parameters.get(index) else {
+            continue;
+        };
+        let range = parameter.range;
+        let result = parse_f64(event.text.trim()).and_then(|value| {
+            if value >= range.min && value <= range.max {
+                Ok(value)
+            } else {
+                Err(format!("not in {:.0e}..{:.0e}", range.min, range.max))
+            }
+        });
+        if let Ok(value) = result {
+            changes.push((index, value));
+        }
+        input.set_error(result.err());
+    }
+
+    for (index, value) in changes {
+        let Some(parameter) = model.parameters().into_iter().nth(index) else {
+            continue;
+        };
+        let value = parameter.range.clamp(value);
+        // the sliders send while pressed, also without moving
+        if value != parameter.value {
+            model.set_parameter(index, value);
+            *settings = ModelPlotSettings::new(&*model);
+        }
+    }
+}
+
+/// replaces the parameter sliders with the selected model's
+/// a continuous energy's slider goes in place of the energy level's controls, which are hidden then
+pub fn setup_parameter_sliders<M: PotentialModel>(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    selected: Res<PotentialModelInput>,
+    model: Res<M>,
+    sliders_query: Query<Entity, With<ParameterSlidersMarker>>,
+    energy_query: Query<Entity, With<EnergyControlMarker>>,
+    mut level_controls_query: Query<&mut Style, With<EnergyLevelControlsMarker>>,
+) {
+    if !selected.is_changed() {
+        return;
+    }
+    let (Ok(sliders_id), Ok(energy_id)) = (sliders_query.get_single(), energy_query.get_single())
+    else {
+        return;
+    };
+    commands.entity(sliders_id).despawn_descendants();
+    commands.entity(energy_id).despawn_descendants();
+
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    let energy_parameter = model.energy_parameter();
+    for (index, parameter) in model.parameters().iter().enumerate() {
+        let container_id = if energy_parameter == Some(index) {
+            energy_id
+        } else {
+            sliders_id
+        };
+        add_parameter_slider(&mut commands, container_id, &font, index, parameter);
+    }
+    for mut style in level_controls_query.iter_mut() {
+        style.display = if energy_parameter.is_some() {
+            Display::None
+        } else {
+            Display::Flex
+        };
+    }
+}
+
+/// updates the parameters' text inputs and slider positions with the model's values
+fn update_parameter_sliders<M: PotentialModel>(
+    model: Res<M>,
+    mut input_query: Query<&mut TextInput>,
+    mut fill_query: Query<(&ParameterSliderFillMarker, &mut Style)>,
+) {
+    if !model.is_changed() {
+        return;
+    }
+    let parameters = model.parameters();
+    for mut input in input_query.iter_mut() {
+        let TextInputTarget::Parameter(index) = input.target else {
+            continue;
+        };
+        if let Some(parameter) = parameters.get(index) {
+            let value = parameter_value_text(parameter);
+            if input.is_outdated(&value) {
+                input.set_value(value);
+            }
+        }
+    }
+    for (marker, mut style) in fill_query.iter_mut() {
+        if let Some(parameter) = parameters.get(marker.0) {
+            style.width = slider_fill_width(parameter);
+        }
+    }
+}
+
+/// keeps the selectable energy levels within the model's bounds
+/// the current level is clamped, e.g. when switching to a model with fewer levels, or whose levels start at 1
+fn update_energy_level_range<M: PotentialModel>(
+    model: Res<M>,
+    mut range: ResMut<EnergyLevelRange>,
+    mut energy_level_query: Query<&mut EnergyLevel>,
+) {
+    range.set_if_neq(EnergyLevelRange {
+        min: model.min_level(),
+        max: model.max_level(),
+    });
+    for mut energy_level in energy_level_query.iter_mut() {
+        let clamped = energy_level.0.clamp(range.min, range.max);
+        if energy_level.0 != clamped {
+            energy_level.0 = clamped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::ParameterRange;
+
+    #[test]
+    fn parameter_range_maps_slider_logarithmically() {
+        let range = ParameterRange::new(1e-31, 1e-27);
+        assert_relative_eq!(1e-31, range.value(0.0));
+        assert_relative_eq!(1e-29, range.value(0.5), max_relative = 1e-12);
+        assert_relative_eq!(1e-27, range.value(1.0));
+        assert_relative_eq!(0.25, range.fraction(1e-30), max_relative = 1e-12);
+        // dragged outside of the slider
+        assert_relative_eq!(1e-27, range.value(1.3));
+        assert_relative_eq!(0.0, range.fraction(1e-40));
+    }
+}
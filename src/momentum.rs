@@ -0,0 +1,337 @@
+/// the wave function in momentum space, φ(p) = (2πħ)^(-1/2) ∫ Ψ(x) e^(-ipx/ħ) dx
+/// shown instead of Ψ(x) with a toggle on the UI, to compare the spreads of Ψ and φ (uncertainty principle)
+/// models with a closed form of φ_n provide it, for the others φ_n is computed with an FFT of the sampled Ψ_n
+/// the momentum plot has its own settings: the domain is in kg·m/s
+use crate::{
+    model::{PotentialModel, H_BAR},
+    plot::PlotSettings,
+    ui::EnergyLevel,
+    wave_function_plot::CurveExtents,
+};
+use num_complex::Complex64;
+use rustfft::FftPlanner;
+use std::f64::consts::PI;
+use uom::si::{
+    f64::{Length, Momentum},
+    length::meter,
+    momentum::kilogram_meter_per_second,
+};
+
+/// number of momenta sampled on the momentum plot's domain
+const MOMENTUM_SAMPLES: f64 = 1000.0;
+/// screen width of the momentum plot's domain
+const MOMENTUM_SCREEN_WIDTH: f64 = 10.0;
+/// bound on the FFT's size, for grids much finer than the momenta need
+const MAX_FFT_SIZE: usize = 1 << 22;
+
+/// settings of a momentum plot on [-max_momentum, max_momentum] (kg·m/s)
+pub fn momentum_plot_settings(max_momentum: f64) -> PlotSettings {
+    PlotSettings {
+        domain_range_start: -max_momentum,
+        domain_range_end: max_momentum,
+        sample_step: 2.0 * max_momentum / MOMENTUM_SAMPLES,
+        screen_scale_x: MOMENTUM_SCREEN_WIDTH / (2.0 * max_momentum),
+        ..PlotSettings::default()
+    }
+}
+
+/// the momenta at the momentum settings' sample points
+pub fn momenta(settings: &PlotSettings) -> Vec<f64> {
+    let count = ((settings.domain_range_end - settings.domain_range_start) / settings.sample_step)
+        .round()
+        .max(1.0) as usize;
+    (0..=count)
+        .map(|i| settings.domain_range_start + i as f64 * settings.sample_step)
+        .collect()
+}
+
+/// φ(p) at momenta (kg·m/s), for Ψ sampled at start + j step (m)
+/// computed with an FFT of Ψ padded with 0s to resolve the momenta's step, interpolated linearly between its momenta
+/// momenta beyond the grid's Nyquist momentum πħ/step aren't resolved, and are 0
+pub fn fourier_transform(
+    psi: &[Complex64],
+    start: f64,
+    step: f64,
+    momenta: &[f64],
+) -> Vec<Complex64> {
+    let zero = Complex64::new(0.0, 0.0);
+    if psi.is_empty() || step <= 0.0 {
+        return vec![zero; momenta.len()];
+    }
+    let momentum_step = match momenta {
+        [first, second, ..] => (second - first).abs(),
+        _ => f64::INFINITY,
+    };
+    // the FFT's momentum step 2πħ/(n step) is at most half the momenta's
+    let resolving_size = (4.0 * PI * H_BAR / (step * momentum_step)).ceil();
+    let size = if resolving_size.is_finite() {
+        (resolving_size as usize).min(MAX_FFT_SIZE)
+    } else {
+        0
+    };
+    let n = psi.len().max(size).next_power_of_two();
+
+    let mut buffer = psi.to_vec();
+    buffer.resize(n, zero);
+    FftPlanner::new().plan_fft_forward(n).process(&mut buffer);
+
+    let fft_step = 2.0 * PI * H_BAR / (n as f64 * step);
+    let factor = step / (2.0 * PI * H_BAR).sqrt();
+    // φ at the k-th momentum of the FFT, k in (-n/2, n/2), the phase shifting x to start
+    let phi = |k: i64| {
+        let p = k as f64 * fft_step;
+        buffer[k.rem_euclid(n as i64) as usize] * Complex64::from_polar(factor, -p * start / H_BAR)
+    };
+    let nyquist = (n / 2) as i64 - 1;
+    momenta
+        .iter()
+        .map(|p| {
+            let index = p / fft_step;
+            let k = index.floor() as i64;
+            if k < -nyquist || k + 1 > nyquist {
+                return zero;
+            }
+            let fraction = index - k as f64;
+            phi(k) * (1.0 - fraction) + phi(k + 1) * fraction
+        })
+        .collect()
+}
+
+/// φ_n(p) of a model's levels on the momentum plot's sample points,
+/// from the closed form where the model has one, else with an FFT of Ψ_n sampled on the plot's domain
+pub struct MomentumEigenstates {
+    momenta: Vec<f64>,
+    states: Vec<(EnergyLevel, Vec<Complex64>)>,
+}
+
+impl MomentumEigenstates {
+    pub fn new<M: PotentialModel>(
+        model: &M,
+        settings: &PlotSettings,
+        momentum_settings: &PlotSettings,
+        levels: &[EnergyLevel],
+    ) -> Self {
+        let momenta = momenta(momentum_settings);
+        let states = levels
+            .iter()
+            .map(|level| (*level, phi_n(model, level, settings, &momenta)))
+            .collect();
+        Self { momenta, states }
+    }
+
+    /// (p, φ(p)) of the superposition Σ c_n φ_n(p)
+    pub fn superpose(&self, coefficients: &[(EnergyLevel, Complex64)]) -> Vec<(f64, Complex64)> {
+        let mut phi = vec![Complex64::new(0.0, 0.0); self.momenta.len()];
+        for (level, c) in coefficients {
+            for (sum, value) in phi.iter_mut().zip(self.state(level)) {
+                *sum += c * value;
+            }
+        }
+        self.momenta.iter().cloned().zip(phi).collect()
+    }
+
+    /// extents of φ(p, t) at any t, from its bound |φ(p, t)| <= Σ|c_n||φ_n(p)|
+    pub fn extents(&self, coefficients: &[(EnergyLevel, Complex64)]) -> CurveExtents {
+        let mut bound = vec![0.0; self.momenta.len()];
+        for (level, c) in coefficients {
+            for (sum, value) in bound.iter_mut().zip(self.state(level)) {
+                *sum += c.norm() * value.norm();
+            }
+        }
+        let phi = bound.iter().cloned().fold(0.0, f64::max);
+        CurveExtents {
+            psi: phi,
+            pdf: phi * phi,
+        }
+    }
+
+    /// φ_n, empty for a level that wasn't computed
+    fn state(&self, level: &EnergyLevel) -> &[Complex64] {
+        self.states
+            .iter()
+            .find(|(l, _)| l == level)
+            .map(|(_, state)| state.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+/// φ_n at momenta (kg·m/s)
+fn phi_n<M: PotentialModel>(
+    model: &M,
+    level: &EnergyLevel,
+    settings: &PlotSettings,
+    momenta: &[f64],
+) -> Vec<Complex64> {
+    let closed_form: Option<Vec<Complex64>> = momenta
+        .iter()
+        .map(|p| model.phi(Momentum::new::<kilogram_meter_per_second>(*p), level))
+        .collect();
+    closed_form.unwrap_or_else(|| {
+        let psi = sample_psi(model, level, settings);
+        fourier_transform(
+            &psi,
+            settings.domain_range_start,
+            settings.sample_step,
+            momenta,
+        )
+    })
+}
+
+/// Ψ_n at the settings' sample points
+fn sample_psi<M: PotentialModel>(
+    model: &M,
+    level: &EnergyLevel,
+    settings: &PlotSettings,
+) -> Vec<Complex64> {
+    let count = ((settings.domain_range_end - settings.domain_range_start) / settings.sample_step)
+        .floor() as usize;
+    (0..=count)
+        .map(|i| {
+            let x = settings.domain_range_start + i as f64 * settings.sample_step;
+            model.psi(Length::new::<meter>(x), level)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+    use uom::si::{
+        f64::{Length, Momentum},
+        length::nanometer,
+        momentum::kilogram_meter_per_second,
+    };
+
+    use super::{fourier_transform, momenta, momentum_plot_settings, sample_psi};
+    use crate::{
+        harmonic_oscillator_plot::HarmonicOscillator,
+        infinite_well_plot::{InfiniteWell, WellPlacement},
+        model::{PotentialModel, H_BAR},
+        plot::PlotSettings,
+        ui::EnergyLevel,
+    };
+
+    /// max |a - b| relative to max |b|
+    fn max_difference(a: &[Complex64], b: &[Complex64]) -> f64 {
+        let max = b.iter().map(|v| v.norm()).fold(0.0, f64::max);
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| (a - b).norm())
+            .fold(0.0, f64::max)
+            / max
+    }
+
+    #[test]
+    fn fourier_transform_of_gaussian() {
+        let sigma: f64 = 1e-10;
+        let center = 3e-10;
+        let settings = PlotSettings {
+            domain_range_start: -2e-9,
+            domain_range_end: 2e-9,
+            sample_step: 1e-12,
+            ..PlotSettings::default()
+        };
+        let psi: Vec<Complex64> = (0..=4000)
+            .map(|i| {
+                let x = settings.domain_range_start + i as f64 * settings.sample_step;
+                let value = (2.0 * PI * sigma.powi(2)).powf(-0.25)
+                    * (-(x - center).powi(2) / (4.0 * sigma.powi(2))).exp();
+                value.into()
+            })
+            .collect();
+        // ħ/2σ is the momentum's spread
+        let momenta = momenta(&momentum_plot_settings(5.0 * H_BAR / (2.0 * sigma)));
+        let phi = fourier_transform(
+            &psi,
+            settings.domain_range_start,
+            settings.sample_step,
+            &momenta,
+        );
+
+        let expected: Vec<Complex64> = momenta
+            .iter()
+            .map(|p| {
+                let magnitude = (2.0 * sigma.powi(2) / (PI * H_BAR.powi(2))).powf(0.25)
+                    * (-(sigma * p / H_BAR).powi(2)).exp();
+                Complex64::from_polar(magnitude, -p * center / H_BAR)
+            })
+            .collect();
+        assert!(max_difference(&phi, &expected) < 1e-3);
+
+        let step = momenta[1] - momenta[0];
+        let norm: f64 = phi.iter().map(|v| v.norm_sqr() * step).sum();
+        assert!((norm - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn closed_forms_match_fourier_transform() {
+        let oscillator = HarmonicOscillator::default();
+        let settings = oscillator.plot_settings();
+        let oscillator_momenta = momenta(&oscillator.momentum_plot_settings());
+        for level in (0..4).map(EnergyLevel) {
+            let psi = sample_psi(&oscillator, &level, &settings);
+            let transform = fourier_transform(
+                &psi,
+                settings.domain_range_start,
+                settings.sample_step,
+                &oscillator_momenta,
+            );
+            let closed_form = closed_form(&oscillator, &level, &oscillator_momenta);
+            assert!(max_difference(&closed_form, &transform) < 1e-3);
+        }
+
+        let well = InfiniteWell {
+            width: Length::new::<nanometer>(1.0),
+            ..InfiniteWell::default()
+        };
+        // Ψ is 0 outside of the well
+        let width = well.width.value;
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: width,
+            sample_step: width / 4000.0,
+            ..PlotSettings::default()
+        };
+        let well_momenta = momenta(&well.momentum_plot_settings());
+        for level in (1..4).map(EnergyLevel) {
+            let psi = sample_psi(&well, &level, &settings);
+            let transform = fourier_transform(&psi, 0.0, settings.sample_step, &well_momenta);
+            let closed_form = closed_form(&well, &level, &well_momenta);
+            assert!(max_difference(&closed_form, &transform) < 1e-2);
+        }
+
+        // the same Ψ, shifted
+        let centered = InfiniteWell {
+            placement: WellPlacement::Centered,
+            ..well
+        };
+        let start = -width / 2.0;
+        let settings = PlotSettings {
+            domain_range_start: start,
+            domain_range_end: -start,
+            ..settings
+        };
+        for level in (1..4).map(EnergyLevel) {
+            let psi = sample_psi(&centered, &level, &settings);
+            let transform = fourier_transform(&psi, start, settings.sample_step, &well_momenta);
+            let closed_form = closed_form(&centered, &level, &well_momenta);
+            assert!(max_difference(&closed_form, &transform) < 1e-2);
+        }
+    }
+
+    fn closed_form<M: PotentialModel>(
+        model: &M,
+        level: &EnergyLevel,
+        momenta: &[f64],
+    ) -> Vec<Complex64> {
+        momenta
+            .iter()
+            .map(|p| {
+                model
+                    .phi(Momentum::new::<kilogram_meter_per_second>(*p), level)
+                    .unwrap()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,280 @@
+/// model for an arbitrary potential, its eigenstates solved numerically
+/// this allows to plot any V(x), without knowing the closed forms of Ψ and E
+use crate::{
+    finite_difference_solver::{self, Spectrum},
+    model::{ModelParameter, ParameterRange, PotentialModel},
+    numerov_solver::LazySpectrum,
+    plot::PlotSettings,
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+/// V(x) of a numerical model, with the parameters it's built from
+pub trait NumericalPotential: Send + Sync + 'static {
+    fn potential(&self, x: Length) -> Energy;
+
+    /// listed on the UI after the mass
+    fn parameters(&self) -> Vec<ModelParameter>;
+
+    /// index into `parameters`
+    fn set_parameter(&mut self, index: usize, value: f64);
+}
+
+/// how the eigenstates are solved
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SolverBackend {
+    /// diagonalization of the finite difference Hamiltonian
+    FiniteDifference,
+    /// Numerov shooting, level by level, no matrix needed for fine grids or high levels
+    /// the levels are solved when they're first requested
+    Numerov,
+}
+
+/// the solved eigenstates
+enum Eigenstates {
+    /// levels 0..=max_level, solved at once
+    Spectrum(Spectrum),
+    Lazy(LazySpectrum),
+}
+
+/// a model defined only by its potential
+/// the domain in the plot settings doubles as the solver's grid, Ψ is 0 at its ends
+/// the potential has its own type per model: a model is a resource, of which bevy has one per type
+#[derive(Resource)]
+pub struct NumericalModel<P: NumericalPotential> {
+    name: &'static str,
+    mass: Mass,
+    potential: P,
+    settings: PlotSettings,
+    max_level: u32,
+    backend: SolverBackend,
+    eigenstates: Eigenstates,
+}
+
+impl<P: NumericalPotential> NumericalModel<P> {
+    /// solves the eigenstates for levels 0..=max_level with backend
+    pub fn new(
+        name: &'static str,
+        mass: Mass,
+        potential: P,
+        settings: PlotSettings,
+        max_level: u32,
+        backend: SolverBackend,
+    ) -> Self {
+        let eigenstates = solve(&settings, mass, &potential, max_level, backend);
+        Self {
+            name,
+            mass,
+            potential,
+            settings,
+            max_level,
+            backend,
+            eigenstates,
+        }
+    }
+}
+
+fn solve<P: NumericalPotential>(
+    settings: &PlotSettings,
+    mass: Mass,
+    potential: &P,
+    max_level: u32,
+    backend: SolverBackend,
+) -> Eigenstates {
+    let potential = |x| potential.potential(x);
+    match backend {
+        SolverBackend::FiniteDifference => {
+            let count = max_level as usize + 1;
+            Eigenstates::Spectrum(finite_difference_solver::solve(
+                settings, mass, potential, count,
+            ))
+        }
+        SolverBackend::Numerov => Eigenstates::Lazy(LazySpectrum::new(settings, mass, potential)),
+    }
+}
+
+impl<P: NumericalPotential> PotentialModel for NumericalModel<P> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// the mass, followed by the potential's parameters
+    fn parameters(&self) -> Vec<ModelParameter> {
+        let mut parameters = vec![ModelParameter {
+            name: "m",
+            value: self.mass.get::<kilogram>(),
+            unit: "kg",
+            range: ParameterRange::new(1e-31, 1e-26),
+        }];
+        parameters.extend(self.potential.parameters());
+        parameters
+    }
+
+    /// the eigenstates are solved again
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        if index == 0 {
+            self.mass = Mass::new::<kilogram>(value);
+        } else if index <= self.potential.parameters().len() {
+            self.potential.set_parameter(index - 1, value);
+        } else {
+            return;
+        }
+        self.eigenstates = solve(
+            &self.settings,
+            self.mass,
+            &self.potential,
+            self.max_level,
+            self.backend,
+        );
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    fn psi(&self, x: Length, level: &EnergyLevel) -> Complex64 {
+        let x = x.get::<meter>();
+        let psi = match &self.eigenstates {
+            Eigenstates::Spectrum(spectrum) => spectrum.psi(level.0 as usize, x),
+            Eigenstates::Lazy(spectrum) => spectrum.psi(level.0, x),
+        };
+        Complex64::new(psi, 0.0)
+    }
+
+    fn energy(&self, level: &EnergyLevel) -> Energy {
+        let energy = match &self.eigenstates {
+            // above the solved levels, clamped to the highest solved one (Ψ is 0 there)
+            Eigenstates::Spectrum(spectrum) => spectrum
+                .states
+                .get(level.0 as usize)
+                .or(spectrum.states.last())
+                .map_or(0.0, |state| state.energy),
+            Eigenstates::Lazy(spectrum) => spectrum.state(level.0).energy,
+        };
+        Energy::new::<joule>(energy)
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        self.potential.potential(x)
+    }
+
+    fn plot_settings(&self) -> PlotSettings {
+        self.settings.clone()
+    }
+
+    fn max_level(&self) -> u32 {
+        match &self.eigenstates {
+            Eigenstates::Spectrum(spectrum) => spectrum.states.len().saturating_sub(1) as u32,
+            Eigenstates::Lazy(_) => self.max_level,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use uom::si::{
+        energy::{electronvolt, joule},
+        f64::{Energy, Length, Mass},
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use super::{NumericalModel, NumericalPotential, SolverBackend};
+    use crate::{
+        model::{ModelParameter, ParameterRange, PotentialModel},
+        plot::PlotSettings,
+        ui::EnergyLevel,
+    };
+
+    /// V(x) = V₀ ((x/b)^2 - 1)^2, with minima at ±b and a barrier of height V₀ between them
+    /// the lowest states come in pairs (symmetric / antisymmetric), split by tunneling through the barrier
+    pub struct DoubleWell {
+        /// V₀
+        pub barrier: Energy,
+        /// b, the distance of the minima from 0
+        pub minimum: Length,
+    }
+
+    impl NumericalPotential for DoubleWell {
+        fn potential(&self, x: Length) -> Energy {
+            let u = (x / self.minimum).value;
+            self.barrier * (u.powi(2) - 1.0).powi(2)
+        }
+
+        fn parameters(&self) -> Vec<ModelParameter> {
+            vec![
+                ModelParameter {
+                    name: "V₀",
+                    value: self.barrier.get::<joule>(),
+                    unit: "J",
+                    range: ParameterRange::new(1.6e-21, 1.6e-17),
+                },
+                ModelParameter {
+                    name: "b",
+                    value: self.minimum.get::<meter>(),
+                    unit: "m",
+                    range: ParameterRange::new(1e-10, 8e-10),
+                },
+            ]
+        }
+
+        fn set_parameter(&mut self, index: usize, value: f64) {
+            match index {
+                0 => self.barrier = Energy::new::<joule>(value),
+                1 => self.minimum = Length::new::<meter>(value),
+                _ => {}
+            }
+        }
+    }
+
+    /// an electron in a double well, a potential without closed forms for Ψ and E
+    pub fn double_well(backend: SolverBackend) -> NumericalModel<DoubleWell> {
+        NumericalModel::new(
+            "Double well",
+            Mass::new::<kilogram>(9.11e-31),
+            DoubleWell {
+                barrier: Energy::new::<electronvolt>(5.0),
+                minimum: Length::new::<nanometer>(0.5),
+            },
+            PlotSettings {
+                domain_range_start: -1e-9,
+                domain_range_end: 1e-9,
+                sample_step: 2e-12,
+                screen_scale_x: 5e9,
+                ..PlotSettings::default()
+            },
+            10,
+            backend,
+        )
+    }
+
+    fn splitting(model: &impl PotentialModel) -> Energy {
+        model.energy(&EnergyLevel(1)) - model.energy(&EnergyLevel(0))
+    }
+
+    #[test]
+    fn higher_barriers_split_the_lowest_pair_less() {
+        let mut model = double_well(SolverBackend::FiniteDifference);
+        let names: Vec<&str> = model.parameters().iter().map(|p| p.name).collect();
+        assert_eq!(vec!["m", "V₀", "b"], names);
+
+        let low_barrier = splitting(&model);
+        model.set_parameter(1, Energy::new::<electronvolt>(10.0).value);
+        assert!(splitting(&model) < low_barrier);
+    }
+
+    #[test]
+    fn unsolved_levels_have_the_highest_solved_energy() {
+        let model = double_well(SolverBackend::FiniteDifference);
+        let max_level = EnergyLevel(model.max_level());
+        let energy = model.energy(&EnergyLevel(model.max_level() + 5));
+        assert_eq!(model.energy(&max_level), energy);
+    }
+}
@@ -0,0 +1,22 @@
+/// finds a root of function in [start, end]
+/// expects the function to change sign in the interval
+pub fn bisect<F>(function: F, start: f64, end: f64) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let mut low = start;
+    let mut high = end;
+    let low_sign = function(low).signum();
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+        if function(mid).signum() == low_sign {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
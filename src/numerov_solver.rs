@@ -0,0 +1,390 @@
+/// Numerov shooting solver for the 1D time independent Schrödinger equation
+/// Ψ'' = -g(x)Ψ, with g = 2m(E - V)/ħ^2, is integrated from the left domain end (Ψ = 0) for a trial energy
+/// the number of nodes of the solution is the number of eigenvalues below the trial energy,
+/// so E_n is found by bisection on the energy, without building a matrix
+/// this allows to solve single (also high) levels on fine grids, e.g. for deep potentials
+use crate::{
+    finite_difference_solver::{fix_sign, interpolate, normalize, Eigenstate},
+    model::H_BAR,
+    plot::PlotSettings,
+};
+use bevy::math::DVec2;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+/// a solved eigenstate, sampled on the domain grid
+#[derive(Debug, Clone)]
+pub struct NumerovEigenstate {
+    /// E_n (J)
+    pub energy: f64,
+    /// (x, Ψ_n(x)) domain points, same format as `generate_points`
+    /// normalized such that Σ|Ψ|^2 dx = 1, Ψ = 0 at the domain ends
+    pub points: Vec<DVec2>,
+}
+
+/// the potential, evaluated on the grid
+pub struct Discretization {
+    /// x (m), including the domain ends
+    grid: Vec<f64>,
+    step: f64,
+    /// V (J) at the grid points
+    potential: Vec<f64>,
+    /// 2m/ħ^2
+    factor: f64,
+}
+
+impl Discretization {
+    pub fn new<F>(settings: &PlotSettings, mass: Mass, potential: F) -> Self
+    where
+        F: Fn(Length) -> Energy,
+    {
+        let start = settings.domain_range_start;
+        let end = settings.domain_range_end;
+        let intervals = ((end - start) / settings.sample_step).round() as usize;
+        let step = (end - start) / intervals as f64;
+        let grid: Vec<f64> = (0..=intervals).map(|i| start + i as f64 * step).collect();
+        let potential = grid
+            .iter()
+            .map(|x| potential(Length::new::<meter>(*x)).get::<joule>())
+            .collect();
+        let factor = 2.0 * mass.get::<kilogram>() / H_BAR / H_BAR;
+        Self {
+            grid,
+            step,
+            potential,
+            factor,
+        }
+    }
+
+    /// x (m) of the grid points, without the domain ends
+    fn interior_grid(&self) -> &[f64] {
+        &self.grid[1..self.grid.len() - 1]
+    }
+
+    /// g(x) = 2m(E - V)/ħ^2 at the grid points
+    fn g(&self, energy: f64) -> Vec<f64> {
+        self.potential
+            .iter()
+            .map(|v| self.factor * (energy - v))
+            .collect()
+    }
+}
+
+/// solves the n-th (0 based) eigenstate
+/// the discretization can be reused for all levels
+pub fn solve_level(discretization: &Discretization, level: u32) -> NumerovEigenstate {
+    let energy = find_energy(discretization, level);
+    let mut psi = matched_solution(discretization, energy);
+    normalize(&mut psi, discretization.step);
+    fix_sign(&mut psi);
+
+    let points = discretization
+        .grid
+        .iter()
+        .zip(psi.iter())
+        .map(|(x, psi)| DVec2::new(*x, *psi))
+        .collect();
+
+    NumerovEigenstate { energy, points }
+}
+
+/// the n-th eigenstate, without the domain ends, like the states of a finite difference `Spectrum`
+fn solve_interior_level(discretization: &Discretization, level: u32) -> Eigenstate {
+    let state = solve_level(discretization, level);
+    let interior = 1..state.points.len() - 1;
+    Eigenstate {
+        energy: state.energy,
+        psi: state.points[interior].iter().map(|p| p.y).collect(),
+    }
+}
+
+/// eigenstates solved when they're first requested, on a discretization kept between them
+/// this allows to select high levels without solving all the levels below them
+pub struct LazySpectrum {
+    discretization: Discretization,
+    /// solved levels, locked as they're solved while the model is only read
+    states: Mutex<HashMap<u32, Arc<Eigenstate>>>,
+}
+
+impl LazySpectrum {
+    pub fn new<F>(settings: &PlotSettings, mass: Mass, potential: F) -> Self
+    where
+        F: Fn(Length) -> Energy,
+    {
+        Self {
+            discretization: Discretization::new(settings, mass, potential),
+            states: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// the n-th eigenstate, solved if it isn't yet
+    pub fn state(&self, level: u32) -> Arc<Eigenstate> {
+        let mut states = self.states.lock().unwrap();
+        states
+            .entry(level)
+            .or_insert_with(|| Arc::new(solve_interior_level(&self.discretization, level)))
+            .clone()
+    }
+
+    /// Ψ_n(x), linearly interpolated between grid points, 0 outside of the grid
+    pub fn psi(&self, level: u32, x: f64) -> f64 {
+        let state = self.state(level);
+        interpolate(
+            self.discretization.interior_grid(),
+            self.discretization.step,
+            &state.psi,
+            x,
+        )
+    }
+}
+
+/// E_n by bisection: n nodes below, more than n above
+fn find_energy(discretization: &Discretization, level: u32) -> f64 {
+    let min_v = discretization
+        .potential
+        .iter()
+        .cloned()
+        .fold(f64::INFINITY, f64::min);
+    let max_v = discretization
+        .potential
+        .iter()
+        .cloned()
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    // expand the upper bound until it's above E_n
+    // starting with the infinite well energy for the domain, which is of the order of E_n
+    let width = discretization.grid.len() as f64 * discretization.step;
+    let k = (level + 1) as f64 * std::f64::consts::PI / width;
+    let mut span = (k * k / discretization.factor).max(max_v - min_v);
+    let mut low = min_v;
+    let mut high = min_v + span;
+    for _ in 0..200 {
+        if count_nodes(&integrate(&discretization.g(high), discretization.step)) > level as usize {
+            break;
+        }
+        low = high;
+        span *= 2.0;
+        high += span;
+    }
+
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+        let nodes = count_nodes(&integrate(&discretization.g(mid), discretization.step));
+        if nodes > level as usize {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Numerov integration from index 0 (where Ψ = 0) to the end
+/// Ψ_{i+1} f_{i+1} = (12 - 10 f_i) Ψ_i - f_{i-1} Ψ_{i-1}, with f = 1 + h^2 g / 12
+fn integrate(g: &[f64], step: f64) -> Vec<f64> {
+    let f: Vec<f64> = g.iter().map(|g| 1.0 + step * step * g / 12.0).collect();
+    let mut psi = vec![0.0; g.len()];
+    if psi.len() < 2 {
+        return psi;
+    }
+    // the slope is arbitrary, the solution is normalized later
+    psi[1] = 1e-6;
+    for i in 1..g.len() - 1 {
+        psi[i + 1] = ((12.0 - 10.0 * f[i]) * psi[i] - f[i - 1] * psi[i - 1]) / f[i + 1];
+        // in forbidden regions the solution grows exponentially, rescale to not overflow
+        if psi[i + 1].abs() > 1e100 {
+            for value in psi[..=i + 1].iter_mut() {
+                *value *= 1e-100;
+            }
+        }
+    }
+    psi
+}
+
+/// number of sign changes, exact zeros don't count as a change
+fn count_nodes(psi: &[f64]) -> usize {
+    let mut nodes = 0;
+    let mut last_sign = 0.0;
+    for value in psi.iter().skip(1) {
+        if *value == 0.0 {
+            continue;
+        }
+        let sign = value.signum();
+        if last_sign != 0.0 && sign != last_sign {
+            nodes += 1;
+        }
+        last_sign = sign;
+    }
+    nodes
+}
+
+/// Ψ for an eigenvalue, integrating from both ends and joining at the rightmost classical turning point
+/// integrating only from the left would diverge in the right forbidden region, due to the energy's limited precision
+fn matched_solution(discretization: &Discretization, energy: f64) -> Vec<f64> {
+    let g = discretization.g(energy);
+    let len = g.len();
+    let left = integrate(&g, discretization.step);
+
+    let reversed_g: Vec<f64> = g.iter().rev().cloned().collect();
+    let mut right = integrate(&reversed_g, discretization.step);
+    right.reverse();
+
+    let max_left = left.iter().fold(0.0_f64, |acc, v| acc.max(v.abs()));
+    let turning_point = (1..len - 1).rev().find(|i| g[*i] > 0.0).unwrap_or(len / 2);
+    // avoid joining at a node, where the scale can't be determined
+    let join = (1..=turning_point)
+        .rev()
+        .find(|i| left[*i].abs() > max_left * 1e-3 && right[*i] != 0.0)
+        .unwrap_or(turning_point);
+
+    let scale = if right[join] != 0.0 {
+        left[join] / right[join]
+    } else {
+        0.0
+    };
+    left.iter()
+        .zip(right.iter())
+        .enumerate()
+        .map(|(i, (l, r))| if i <= join { *l } else { r * scale })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::joule,
+        f64::{Energy, Mass},
+        length::meter,
+        mass::kilogram,
+    };
+
+    use crate::{
+        finite_difference_solver,
+        harmonic_oscillator_plot::HarmonicOscillator,
+        infinite_well_plot::InfiniteWell,
+        model::PotentialModel,
+        numerical_model::{test::double_well, SolverBackend},
+        plot::PlotSettings,
+        ui::EnergyLevel,
+    };
+
+    use super::{count_nodes, solve_level, Discretization, LazySpectrum};
+
+    #[test]
+    fn counts_nodes() {
+        assert_eq!(0, count_nodes(&[0.0, 1.0, 2.0, 1.0]));
+        assert_eq!(1, count_nodes(&[0.0, 1.0, -2.0, -1.0]));
+        assert_eq!(2, count_nodes(&[0.0, 1.0, 0.0, -2.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn harmonic_oscillator_energies_match_closed_form() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let discretization = Discretization::new(&settings, model.mass, |x| model.potential(x));
+        for level in 0..5 {
+            let state = solve_level(&discretization, level);
+            let expected = model.energy(&EnergyLevel(level)).get::<joule>();
+            assert_relative_eq!(expected, state.energy, max_relative = 1e-4);
+        }
+    }
+
+    #[test]
+    fn solves_high_infinite_well_levels() {
+        let model = InfiniteWell::default();
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: model.width.get::<meter>(),
+            sample_step: 0.001,
+            ..PlotSettings::default()
+        };
+        let discretization =
+            Discretization::new(&settings, model.mass, |_| Energy::new::<joule>(0.0));
+        for level in [0, 10, 60] {
+            let state = solve_level(&discretization, level);
+            // the infinite well's levels start at 1
+            let expected = model.energy(&EnergyLevel(level + 1)).get::<joule>();
+            assert_relative_eq!(expected, state.energy, max_relative = 1e-3);
+
+            let psi: Vec<f64> = state.points.iter().map(|p| p.y).collect();
+            assert_eq!(level as usize, count_nodes(&psi));
+        }
+    }
+
+    #[test]
+    fn produces_domain_points() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let discretization = Discretization::new(&settings, model.mass, |x| model.potential(x));
+        let state = solve_level(&discretization, 2);
+
+        let first = state.points.first().unwrap();
+        let last = state.points.last().unwrap();
+        assert_relative_eq!(settings.domain_range_start, first.x);
+        assert_relative_eq!(settings.domain_range_end, last.x);
+        assert_relative_eq!(0.0, first.y);
+        assert_relative_eq!(0.0, last.y);
+        for pair in state.points.windows(2) {
+            assert_relative_eq!(
+                settings.sample_step,
+                pair[1].x - pair[0].x,
+                max_relative = 1e-9
+            );
+        }
+
+        let norm: f64 = state
+            .points
+            .iter()
+            .map(|p| p.y.powi(2) * settings.sample_step)
+            .sum();
+        assert_relative_eq!(1.0, norm, max_relative = 1e-3);
+    }
+
+    #[test]
+    fn matches_finite_difference_solver() {
+        let model = double_well(SolverBackend::FiniteDifference);
+        let settings = model.plot_settings();
+        let mass = Mass::new::<kilogram>(9.11e-31);
+
+        let numerov = LazySpectrum::new(&settings, mass, |x| model.potential(x));
+        let finite_difference =
+            finite_difference_solver::solve(&settings, mass, |x| model.potential(x), 4);
+
+        for (level, b) in finite_difference.states.iter().enumerate() {
+            let a = numerov.state(level as u32);
+            assert_relative_eq!(b.energy, a.energy, max_relative = 1e-3);
+            let overlap: f64 = a.psi.iter().zip(&b.psi).map(|(a, b)| a * b).sum();
+            assert_relative_eq!(1.0, overlap * finite_difference.step, max_relative = 1e-3);
+        }
+    }
+
+    #[test]
+    fn lazy_spectrum_solves_only_requested_levels() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let spectrum = LazySpectrum::new(&settings, model.mass, |x| model.potential(x));
+
+        let energy = spectrum.state(8).energy;
+        let expected = model.energy(&EnergyLevel(8)).get::<joule>();
+        assert_relative_eq!(expected, energy, max_relative = 1e-3);
+        assert_eq!(1, spectrum.states.lock().unwrap().len());
+
+        // cached, not solved again
+        spectrum.psi(8, 0.0);
+        assert_eq!(1, spectrum.states.lock().unwrap().len());
+        spectrum.psi(3, 0.0);
+        assert_eq!(2, spectrum.states.lock().unwrap().len());
+    }
+}
@@ -0,0 +1,358 @@
+/// custom piecewise constant potential, a scattering model whose segments are edited on the UI
+/// it plots Ψ(x) and PDF(x) of a plane wave with energy E incident from the left, solved with transfer matrices,
+/// e.g. resonant tunneling through a double barrier, and lists the bound states of its wells (V < 0)
+use crate::{
+    model::{
+        add_model, is_model_selected, setup_parameter_sliders, ModelParameter, ModelPlotSettings,
+        ParameterRange, PotentialModel, H_BAR,
+    },
+    plot::PlotSettings,
+    scattering::{energy_parameter, scattering_plot_settings, Scattering},
+    text_input::{TextInput, TextInputTarget},
+    transfer_matrix::{PiecewiseConstant, Segment},
+    ui::{
+        add_segment_editor, add_segment_rows, parse_f64, segment_value_text, EnergyLevel,
+        ParameterSlidersMarker, PotentialModelInput, SegmentInput, SegmentInputEvent,
+        SegmentRowsMarker, UiInputsEvent,
+    },
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::{electronvolt, joule},
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+const MAX_SEGMENTS: usize = 8;
+/// accepted widths of a segment (m)
+const SEGMENT_WIDTH_RANGE: ParameterRange = ParameterRange {
+    min: 1e-11,
+    max: 1e-8,
+};
+/// bound on |V| of a segment (J), 100 eV
+const MAX_SEGMENT_POTENTIAL: f64 = 1.6e-17;
+
+/// segments of constant V from x = 0 on, with V = 0 left and right of them
+#[derive(Resource)]
+pub struct PiecewisePotential {
+    energy: Energy,
+    potential: PiecewiseConstant,
+    /// solved bound state energies (J), ascending
+    bound_states: Vec<f64>,
+}
+
+/// a double barrier, as in a resonant tunneling diode
+impl Default for PiecewisePotential {
+    fn default() -> Self {
+        let barrier = Segment {
+            width: 0.5e-9,
+            potential: Energy::new::<electronvolt>(1.0).get::<joule>(),
+        };
+        let well = Segment {
+            width: 2e-9,
+            potential: 0.0,
+        };
+        Self::new(
+            Energy::new::<electronvolt>(0.5),
+            Mass::new::<kilogram>(9.11e-31),
+            vec![barrier, well, barrier],
+        )
+    }
+}
+
+impl PiecewisePotential {
+    pub fn new(energy: Energy, mass: Mass, segments: Vec<Segment>) -> Self {
+        let potential = PiecewiseConstant {
+            segments,
+            mass: mass.get::<kilogram>(),
+        };
+        Self {
+            energy,
+            bound_states: potential.bound_state_energies(),
+            potential,
+        }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.potential.segments
+    }
+
+    /// the bound states are solved again
+    pub fn set_segments(&mut self, segments: Vec<Segment>) {
+        self.potential.segments = segments;
+        self.bound_states = self.potential.bound_state_energies();
+    }
+}
+
+impl PotentialModel for PiecewisePotential {
+    fn name(&self) -> &'static str {
+        "Custom piecewise"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            energy_parameter(self.energy),
+            ModelParameter {
+                name: "m",
+                value: self.potential.mass,
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    /// the bound states are solved again for a new mass
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.energy = Energy::new::<joule>(value),
+            1 => {
+                self.potential.mass = value;
+                self.bound_states = self.potential.bound_state_energies();
+            }
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        Mass::new::<kilogram>(self.potential.mass)
+    }
+
+    /// the same for all levels, it's the state of energy E
+    fn psi(&self, x: Length, _level: &EnergyLevel) -> Complex64 {
+        let energy = self.energy.get::<joule>();
+        match self.potential.scattering_state(energy) {
+            Some(state) => state.psi(&self.potential, energy, x.get::<meter>()),
+            None => Complex64::new(0.0, 0.0),
+        }
+    }
+
+    fn energy(&self, _level: &EnergyLevel) -> Energy {
+        self.energy
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        Energy::new::<joule>(self.potential.potential(x.get::<meter>()))
+    }
+
+    fn energy_parameter(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    fn scattering(&self, energy: Energy) -> Option<Scattering> {
+        self.potential.scattering(energy.get::<joule>())
+    }
+
+    fn info(&self) -> Option<String> {
+        if self.bound_states.is_empty() {
+            return None;
+        }
+        let energies: Vec<String> = self
+            .bound_states
+            .iter()
+            .enumerate()
+            .map(|(n, energy)| format!("E_{}: {:.2e} J", n, energy))
+            .collect();
+        Some(format!("bound states:\n{}", energies.join("\n")))
+    }
+
+    /// a few wave lengths at the largest |V| on each side of the segments, or their length if it's longer,
+    /// such that the domain doesn't change with E
+    fn plot_settings(&self) -> PlotSettings {
+        let max_potential = self
+            .segments()
+            .iter()
+            .map(|segment| segment.potential.abs())
+            .fold(0.0, f64::max);
+        // flat segments get the wave length at 1 eV
+        let reference = if max_potential > 0.0 {
+            max_potential
+        } else {
+            Energy::new::<electronvolt>(1.0).get::<joule>()
+        };
+        let wave_length = 2.0 * PI * H_BAR / (2.0 * self.potential.mass * reference).sqrt();
+        scattering_plot_settings(wave_length, 0.0, self.potential.length())
+    }
+
+    fn max_level(&self) -> u32 {
+        0
+    }
+}
+
+/// adds the model, with the systems of its segment editor
+pub fn add_piecewise_potential(app: &mut App) {
+    let id = add_model(app, PiecewisePotential::default());
+    app.add_systems(
+        Update,
+        (
+            setup_segment_editor.after(setup_parameter_sliders::<PiecewisePotential>),
+            listen_segment_ui_inputs,
+            update_segment_inputs,
+        )
+            .run_if(is_model_selected(id)),
+    );
+}
+
+/// (width, V) of the segments
+fn segment_values(model: &PiecewisePotential) -> Vec<(f64, f64)> {
+    model
+        .segments()
+        .iter()
+        .map(|segment| (segment.width, segment.potential))
+        .collect()
+}
+
+/// adds the segment editor below the parameter sliders when the model is selected,
+/// and replaces its rows when segments are added or removed
+fn setup_segment_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    selected: Res<PotentialModelInput>,
+    model: Res<PiecewisePotential>,
+    sliders_query: Query<Entity, With<ParameterSlidersMarker>>,
+    rows_query: Query<Entity, With<SegmentRowsMarker>>,
+    mut shown_segments: Local<usize>,
+) {
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    let rows_id = if selected.is_changed() {
+        // the sliders' container was just emptied, with the previous editor
+        let Ok(sliders_id) = sliders_query.get_single() else {
+            return;
+        };
+        add_segment_editor(&mut commands, sliders_id, &font)
+    } else if *shown_segments != model.segments().len() {
+        let Ok(rows_id) = rows_query.get_single() else {
+            return;
+        };
+        commands.entity(rows_id).despawn_descendants();
+        rows_id
+    } else {
+        return;
+    };
+    add_segment_rows(&mut commands, rows_id, &font, &segment_values(&model));
+    *shown_segments = model.segments().len();
+}
+
+/// adds and removes segments, and sets the widths and V committed in their text inputs
+/// the plot settings follow, so the segments fit on the screen
+fn listen_segment_ui_inputs(
+    mut segment_events: EventReader<SegmentInputEvent>,
+    mut text_events: EventReader<UiInputsEvent>,
+    mut model: ResMut<PiecewisePotential>,
+    mut settings: ResMut<ModelPlotSettings<PiecewisePotential>>,
+    mut input_query: Query<&mut TextInput>,
+) {
+    let mut segments = model.segments().to_vec();
+    for event in segment_events.read() {
+        match event.input {
+            SegmentInput::Add if segments.len() < MAX_SEGMENTS => {
+                // continues the last one, to be edited
+                let last = segments.last().copied();
+                segments.extend(last);
+            }
+            SegmentInput::Remove(index) if segments.len() > 1 && index < segments.len() => {
+                segments.remove(index);
+            }
+            _ => {}
+        }
+    }
+
+    for event in text_events.read() {
+        let (index, is_width) = match event.target {
+            TextInputTarget::SegmentWidth(index) => (index, true),
+            TextInputTarget::SegmentPotential(index) => (index, false),
+            _ => continue,
+        };
+        let Ok(mut input) = input_query.get_mut(event.input) else {
+            continue;
+        };
+        let Some(segment) = segments.get_mut(index) else {
+            continue;
+        };
+        let value = parse_f64(event.text.trim());
+        let result = if is_width {
+            let range = SEGMENT_WIDTH_RANGE;
+            value.and_then(|width| {
+                if width >= range.min && width <= range.max {
+                    segment.width = width;
+                    Ok(())
+                } else {
+                    Err(format!("not in {:.0e}..{:.0e}", range.min, range.max))
+                }
+            })
+        } else {
+            value.and_then(|potential| {
+                if potential.abs() <= MAX_SEGMENT_POTENTIAL {
+                    segment.potential = potential;
+                    Ok(())
+                } else {
+                    Err(format!("|V| over {:.1e}", MAX_SEGMENT_POTENTIAL))
+                }
+            })
+        };
+        input.set_error(result.err());
+    }
+
+    if segments != model.segments() {
+        model.set_segments(segments);
+        *settings = ModelPlotSettings::new(&*model);
+    }
+}
+
+/// updates the segments' text inputs with the model's values
+fn update_segment_inputs(model: Res<PiecewisePotential>, mut input_query: Query<&mut TextInput>) {
+    if !model.is_changed() {
+        return;
+    }
+    let segments = model.segments();
+    for mut input in input_query.iter_mut() {
+        let value = match input.target {
+            TextInputTarget::SegmentWidth(index) => segments.get(index).map(|s| s.width),
+            TextInputTarget::SegmentPotential(index) => segments.get(index).map(|s| s.potential),
+            _ => None,
+        };
+        if let Some(value) = value {
+            let text = segment_value_text(value);
+            if input.is_outdated(&text) {
+                input.set_value(text);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uom::si::{
+        energy::{electronvolt, joule},
+        f64::{Energy, Mass},
+        mass::kilogram,
+    };
+
+    use super::PiecewisePotential;
+    use crate::{model::PotentialModel, transfer_matrix::Segment};
+
+    #[test]
+    fn wells_have_bound_states() {
+        let mut model = PiecewisePotential::default();
+        assert!(model.info().is_none());
+
+        let well = Segment {
+            width: 1e-9,
+            potential: -Energy::new::<electronvolt>(2.0).get::<joule>(),
+        };
+        model.set_segments(vec![well]);
+        let info = model.info().unwrap();
+        assert!(info.contains("E_0"));
+
+        // heavier particles have more bound states
+        let count = model.bound_states.len();
+        let heavier = PiecewisePotential::new(
+            model.energy,
+            Mass::new::<kilogram>(10.0 * 9.11e-31),
+            vec![well],
+        );
+        assert!(heavier.bound_states.len() > count);
+    }
+}
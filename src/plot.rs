@@ -1,48 +1,73 @@
-use bevy::{color::palettes::css::GREEN, prelude::*};
+use bevy::{
+    color::palettes::css::{GREEN, WHITE},
+    math::DVec2,
+    prelude::*,
+    utils::HashSet,
+};
 
 use crate::{
+    axis::{setup_axis_ticks, PlotAxes},
     camera_controller::{CameraController, CameraControllerPlugin},
-    ui::{
-        despawn_all_entities_tu, harmonic_oscillator_button_handler,
-        infinite_well_model_button_handler, listen_energy_level_ui_inputs,
-        listen_potential_model_ui_inputs, listen_ui_inputs, minus_button_handler,
-        plus_button_handler, setup_ui, update_energy_level_label, PlusMinusInput,
-        PlusMinusInputEvent, PotentialModelInputEvent, UiInputsEvent,
-    },
+    inset::{InsetPlugin, InsetSeries},
 };
 
-// TODO refactor such that "plot" can be used for any (for now bezier curve) plot
-// i.e. move everything Ψ/PDF to a layer above
+/// the plotting machinery, independent of what's plotted:
+/// draws the `Series` entities, the axes with their ticks and titles, and lists the series in the legend
+/// the data's layers (e.g. Ψ, V) only spawn series with their screen points, and set `PlotAxes`
+/// series marked `InsetSeries` go to the inset plot instead, whose axes are `InsetAxes`
+pub struct PlotPlugin;
+
+impl Plugin for PlotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SeriesVisibility>()
+            .init_resource::<PlotAxes>()
+            .init_resource::<PixelSize>()
+            .add_plugins((CameraControllerPlugin, InsetPlugin))
+            .add_systems(Startup, (setup_camera, setup_legend))
+            .add_systems(
+                Update,
+                (
+                    update_pixel_size,
+                    setup_axes,
+                    setup_axis_ticks,
+                    draw_plot_series,
+                    update_legend,
+                ),
+            );
+    }
+}
+
+/// screen units per pixel, before zooming
+const INITIAL_CAMERA_SCALE: f32 = 0.01;
+
+/// screen px between the samples of a curve, before refining
+const SAMPLE_PX: f64 = 2.0;
+/// an interval is halved when the curve's midpoint is further than this (screen px) from the line between its ends
+const MAX_DEVIATION_PX: f64 = 0.5;
+/// times an interval between the initial samples is halved at most
+const MAX_REFINEMENTS: u32 = 6;
+/// bound on the initial samples, for deep zooms
+const MAX_INITIAL_SAMPLES: usize = 20_000;
+/// bezier positions drawn per segment, i.e. between two points
+const SMOOTH_SUBDIVISIONS: usize = 4;
 
 /// general plot settings
 #[derive(Resource, Clone)]
 pub struct PlotSettings {
     /// start of the axis domain's range
-    pub domain_range_start: f32,
+    pub domain_range_start: f64,
     /// end of the axis domain's range
-    pub domain_range_end: f32,
+    pub domain_range_end: f64,
+    /// spacing between sampled points (domain units), of the solvers' grid and V
+    /// Ψ's curves are sampled for the screen's pixels instead, see `AdaptiveSampling`
+    pub sample_step: f64,
 
     /// scale applied to domain coordinates to show on screen
     /// note final scale involves as well camera's transform
     /// ideally we should have direct screen settings instead (e.g. screen step) and derive scale internally
-    pub screen_scale_x: f32,
-    ///////////////////////////////
-    /// TODO move these to domain specific settings
-    /// PlotSettings should have only a screen_scale_y (analogous to screen_scale_x) field
-    ///
-    // scaled down y by ~max value so it fits in graph
-    pub screen_scale_y_psi: f32,
-    // scaled dowwn y by eye to plot together with psi
-    // exact height unimportant
-    pub screen_scale_y_pdf: f32,
-    ///////////////////////////////
-    pub ticks: TickSettings,
-}
-
-#[derive(Resource, Clone)]
-pub struct TickSettings {
-    /// spacing between ticks (domain units)
-    pub step: f32,
+    pub screen_scale_x: f64,
+    /// screen height the curves are fitted to, their y scale is derived from their values
+    pub screen_height: f64,
 }
 
 // consider removing this.. a domain default doesn't make much sense
@@ -51,71 +76,18 @@ impl Default for PlotSettings {
         Self {
             domain_range_start: -10.0,
             domain_range_end: 10.0,
+            sample_step: 0.02,
             screen_scale_x: 1.0,
-            screen_scale_y_psi: 1.0,
-            screen_scale_y_pdf: 1.0,
-            ticks: TickSettings { step: 1.0 },
+            screen_height: 2.0,
         }
     }
 }
 
-pub fn add_plot(app: &mut App) {
-    app.add_event::<UiInputsEvent>()
-        .add_event::<PlusMinusInputEvent>()
-        .add_event::<PotentialModelInputEvent>()
-        .add_plugins(CameraControllerPlugin)
-        .insert_resource(PlusMinusInput::Plus)
-        .add_plugins(DefaultPlugins)
-        .add_systems(Startup, (setup_camera, setup_light))
-        .add_systems(
-            Update,
-            (
-                setup_axes,
-                draw_curve,
-                listen_ui_inputs,
-                update_energy_level_label,
-                plus_button_handler,
-                minus_button_handler,
-                listen_energy_level_ui_inputs,
-                infinite_well_model_button_handler,
-                harmonic_oscillator_button_handler,
-                listen_potential_model_ui_inputs,
-            ),
-        )
-        .add_systems(Startup, setup_ui);
-}
-
-/// spawns bundle with bezier curve points, corresponding to data points
-/// note that the bezier curve points are still in domain space
-pub fn setup_curve<T>(
-    commands: &mut Commands,
-    color: impl Into<Color>,
-    id: u32,
-    curve_query: &Query<Entity, (With<Curve>, With<T>)>,
-    points: Vec<Vec2>,
-) where
-    T: Component,
-{
-    despawn_all_entities_tu(commands, curve_query);
-
-    let bezier_points = generate_path(&points, 0.3, 0.3);
-    let bezier = CubicBezier::new(bezier_points).to_curve();
-
-    commands.spawn((
-        CurveWave,
-        Curve {
-            id,
-            points: bezier,
-            color: color.into(),
-        },
-    ));
-}
-
 fn setup_camera(mut commands: Commands) {
     commands.spawn((
         Camera2dBundle {
             projection: OrthographicProjection {
-                scale: 0.01,
+                scale: INITIAL_CAMERA_SCALE,
                 ..default()
             },
             transform: Transform {
@@ -125,20 +97,21 @@ fn setup_camera(mut commands: Commands) {
             ..default()
         },
         CameraController::default(),
+        // not the inset's camera
+        IsDefaultUiCamera,
     ));
 }
 
-fn setup_light(mut commands: Commands) {
-    commands.insert_resource(AmbientLight {
-        color: Color::WHITE,
-        brightness: 1.0,
-    });
-}
-
 /// generates bezier curve points for data points
 /// basically inserts 2 control points between each consecutive point pair
 // https://github.com/ivnsch/SwiftCharts/blob/c354c1945bb35a1f01b665b22474f6db28cba4a2/SwiftCharts/Views/CubicLinePathGenerator
-fn generate_path(points: &[Vec2], tension1: f32, tension2: f32) -> Vec<[Vec2; 4]> {
+/// the curve isn't smoothed across the points at the corners' indices, it has a kink there
+fn generate_path(
+    points: &[Vec2],
+    corners: &[usize],
+    tension1: f32,
+    tension2: f32,
+) -> Vec<[Vec2; 4]> {
     let mut path = vec![];
 
     if points.is_empty() {
@@ -182,6 +155,13 @@ fn generate_path(points: &[Vec2], tension1: f32, tension2: f32) -> Vec<[Vec2; 4]
             tension_bezier2 = 0.0;
         }
 
+        if corners.contains(&i) {
+            tension_bezier1 = 0.0;
+        }
+        if corners.contains(&(i + 1)) {
+            tension_bezier2 = 0.0;
+        }
+
         let control_point1 = Vec2::new(
             p1.x + (p2.x - p1.x) / 3.0,
             p1.y - (p1.y - p2.y) / 3.0 - (p0.y - p1.y) * tension_bezier1,
@@ -205,47 +185,301 @@ fn generate_path(points: &[Vec2], tension1: f32, tension2: f32) -> Vec<[Vec2; 4]
     path
 }
 
-/// representation of domain curve
-#[derive(Component)]
-pub struct Curve {
-    /// some identifier for debugging
-    /// uniqueness needed only if used
-    #[allow(dead_code)]
-    id: u32,
-    /// data points (domain)
-    points: CubicCurve<Vec2>,
-    /// color with which the curve will be displayed
-    color: Color,
+/// identifies a series, for its legend entry and its visibility
+/// several entities can have the same id, e.g. a set of lines, they're shown and hidden together
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SeriesId(pub &'static str);
+
+/// how the points of a series are joined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineStyle {
+    /// bezier curve through the points
+    Smooth,
+    /// straight lines between the points, e.g. for steps
+    Straight,
+    /// straight dashed lines
+    Dashed,
+}
+
+#[derive(Debug, Clone)]
+pub enum SeriesColor {
+    Uniform(Color),
+    /// a color per point, blended along the lines
+    PerPoint(Vec<Color>),
+}
+
+/// a plotted data series, in screen coordinates
+/// the data's layer converts its (domain) values with its scales
+#[derive(Component, Debug)]
+pub struct Series {
+    pub id: SeriesId,
+    /// legend entry, None to not list it, e.g. for the other lines of a set
+    pub label: Option<String>,
+    pub color: SeriesColor,
+    pub style: LineStyle,
+    points: Vec<Vec2>,
+    /// for `LineStyle::Smooth`
+    bezier: Option<CubicCurve<Vec2>>,
+}
+
+/// the bezier curve of a `LineStyle::Smooth` series
+fn smooth_curve(style: LineStyle, points: &[Vec2], corners: &[usize]) -> Option<CubicCurve<Vec2>> {
+    (style == LineStyle::Smooth && points.len() > 1)
+        .then(|| CubicBezier::new(generate_path(points, corners, 0.3, 0.3)).to_curve())
+}
+
+impl Series {
+    pub fn new(
+        id: SeriesId,
+        label: &str,
+        color: impl Into<Color>,
+        style: LineStyle,
+        points: Vec<Vec2>,
+    ) -> Self {
+        let bezier = smooth_curve(style, &points, &[]);
+        Self {
+            id,
+            label: Some(label.to_string()),
+            color: SeriesColor::Uniform(color.into()),
+            style,
+            points,
+            bezier,
+        }
+    }
+
+    /// a smooth series has kinks at the points at these indices, e.g. the cusps of Ψ at δ potentials
+    pub fn with_corners(mut self, corners: &[usize]) -> Self {
+        self.bezier = smooth_curve(self.style, &self.points, corners);
+        self
+    }
+
+    /// a series with straight lines between points of their own color
+    pub fn with_point_colors(id: SeriesId, label: &str, points: Vec<(Vec2, Color)>) -> Self {
+        let (points, colors) = points.into_iter().unzip();
+        Self {
+            id,
+            label: Some(label.to_string()),
+            color: SeriesColor::PerPoint(colors),
+            style: LineStyle::Straight,
+            points,
+            bezier: None,
+        }
+    }
+
+    /// not listed in the legend
+    pub fn unlabeled(mut self) -> Self {
+        self.label = None;
+        self
+    }
+
+    /// color of the legend entry, white if it varies
+    pub fn legend_color(&self) -> Color {
+        match &self.color {
+            SeriesColor::Uniform(color) => *color,
+            SeriesColor::PerPoint(_) => WHITE.into(),
+        }
+    }
+}
+
+/// series hidden on the UI, by id
+#[derive(Resource, Debug, Default)]
+pub struct SeriesVisibility {
+    hidden: HashSet<SeriesId>,
+}
+
+impl SeriesVisibility {
+    pub fn hiding(ids: &[SeriesId]) -> Self {
+        Self {
+            hidden: ids.iter().cloned().collect(),
+        }
+    }
+
+    pub fn is_visible(&self, id: SeriesId) -> bool {
+        !self.hidden.contains(&id)
+    }
+
+    pub fn toggle(&mut self, id: SeriesId) {
+        if !self.hidden.remove(&id) {
+            self.hidden.insert(id);
+        }
+    }
+}
+
+/// the legend's container
+#[derive(Component, Default)]
+struct LegendMarker;
+
+/// generates a text label displayed on the bottom left corner of the window
+fn generate_legend(font: &Handle<Font>, label: &str, color: impl Into<Color>) -> impl Bundle {
+    TextBundle {
+        style: Style {
+            position_type: PositionType::Relative,
+            left: Val::Px(0.0),
+            width: Val::Auto,
+            height: Val::Auto,
+            ..default()
+        },
+        text: Text::from_section(
+            label.to_string(),
+            TextStyle {
+                font: font.clone(),
+                font_size: 14.0,
+                color: color.into(),
+            },
+        ),
+        ..default()
+    }
+}
+
+/// adds container element for the legends to bottom left corner of window
+fn setup_legend(mut commands: Commands) {
+    let row = NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            left: Val::Px(10.0),
+            bottom: Val::Px(0.0),
+            width: Val::Auto,
+            height: Val::Auto,
+            ..default()
+        },
+        ..default()
+    };
+
+    // the legends are added by update_legend, depending on the visible curves
+    commands.spawn((LegendMarker, row));
 }
 
-/// bevy bundle marker for Ψ curve
-#[derive(Component)]
-pub struct CurveWave;
+/// replaces the legends with the ones for the visible series, when they change
+/// listed by label, a series with several entities once
+fn update_legend(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    visibility: Res<SeriesVisibility>,
+    series_query: Query<&Series>,
+    legend_query: Query<Entity, With<LegendMarker>>,
+    mut shown_entries: Local<Vec<(String, Color)>>,
+) {
+    let mut entries: Vec<(String, Color)> = series_query
+        .iter()
+        .filter(|series| visibility.is_visible(series.id))
+        .filter_map(|series| {
+            let label = series.label.clone()?;
+            Some((label, series.legend_color()))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    entries.dedup_by(|a, b| a.0 == b.0);
+    if *shown_entries == entries {
+        return;
+    }
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    for legend_box in legend_query.iter() {
+        commands.entity(legend_box).despawn_descendants();
+        for (label, color) in &entries {
+            add_legend(&mut commands, legend_box, &font, label, *color);
+        }
+    }
+    *shown_entries = entries;
+}
 
-/// bevy bundle marker for PDF curve
-#[derive(Component)]
-pub struct CurvePDF;
+/// adds legend to container
+fn add_legend(
+    commands: &mut Commands,
+    container_id: Entity,
+    font: &Handle<Font>,
+    label: &str,
+    color: impl Into<Color>,
+) -> Entity {
+    let bundle = generate_legend(font, label, color);
+    let entity = commands.spawn(bundle).id();
+    commands.entity(container_id).push_children(&[entity]);
+    entity
+}
 
-/// draws the curve generated in setup_curve on the screen
-fn draw_curve(mut query: Query<&Curve>, mut gizmos: Gizmos) {
-    for cubic_curve in &mut query {
-        gizmos.linestrip_2d(cubic_curve.points.iter_positions(1000), cubic_curve.color);
+/// length of a dash and of the gap after it, in screen units
+const DASH: (f32, f32) = (0.06, 0.04);
+
+/// draws the visible series of the main plot
+fn draw_plot_series(
+    query: Query<&Series, Without<InsetSeries>>,
+    visibility: Res<SeriesVisibility>,
+    mut gizmos: Gizmos,
+) {
+    for series in query.iter().filter(|s| visibility.is_visible(s.id)) {
+        draw_series(&mut gizmos, series);
     }
 }
 
+/// draws a series with the gizmos of a plot
+pub fn draw_series<T: GizmoConfigGroup>(gizmos: &mut Gizmos<T>, series: &Series) {
+    match (&series.color, series.style) {
+        (SeriesColor::PerPoint(colors), _) => {
+            gizmos.linestrip_gradient_2d(series.points.iter().cloned().zip(colors.iter().cloned()));
+        }
+        (SeriesColor::Uniform(color), LineStyle::Smooth) => {
+            if let Some(bezier) = &series.bezier {
+                let subdivisions = bezier.segments().len() * SMOOTH_SUBDIVISIONS;
+                gizmos.linestrip_2d(bezier.iter_positions(subdivisions), *color);
+            }
+        }
+        (SeriesColor::Uniform(color), LineStyle::Straight) => {
+            gizmos.linestrip_2d(series.points.iter().cloned(), *color);
+        }
+        (SeriesColor::Uniform(color), LineStyle::Dashed) => {
+            for (start, end) in dashes(&series.points) {
+                gizmos.line_2d(start, end, *color);
+            }
+        }
+    }
+}
+
+/// the dashes along the lines between the points, the pattern continues through the points
+fn dashes(points: &[Vec2]) -> Vec<(Vec2, Vec2)> {
+    let (dash, gap) = DASH;
+    let period = dash + gap;
+    let mut dashes = vec![];
+    // position in the dash + gap period at the line's start
+    let mut phase = 0.0;
+    for line in points.windows(2) {
+        let (start, end) = (line[0], line[1]);
+        let length = start.distance(end);
+        if length <= 0.0 {
+            continue;
+        }
+        let direction = (end - start) / length;
+        // each dash from its index, as stepping through the line would accumulate rounding errors
+        let mut index = 0;
+        loop {
+            let dash_start = index as f32 * period - phase;
+            if dash_start >= length {
+                break;
+            }
+            let (from, to) = (dash_start.max(0.0), (dash_start + dash).min(length));
+            if to > from {
+                dashes.push((start + direction * from, start + direction * to));
+            }
+            index += 1;
+        }
+        phase = (phase + length) % period;
+    }
+    dashes
+}
+
 /// generates points (x, y) by evaluating function on an x
 /// within [range_start, range_end], with a given step size.
-pub fn generate_points<F>(range_start: f32, range_end: f32, step: f32, function: F) -> Vec<Vec2>
+/// in f64, as the domain values are far outside f32's comfortable range, to be converted only for the screen
+pub fn generate_points<F>(range_start: f64, range_end: f64, step: f64, function: F) -> Vec<DVec2>
 where
-    F: Fn(f32) -> f32,
+    F: Fn(f64) -> f64,
 {
     let mut points = vec![];
-    let mut value = range_start as f32;
-    while value <= range_end as f32 {
+    let mut value = range_start;
+    while value <= range_end {
         let x = value;
         let y = function(x);
 
-        points.push(Vec2::new(x, y));
+        points.push(DVec2::new(x, y));
 
         value += step;
     }
@@ -253,8 +487,127 @@ where
     points
 }
 
+/// screen units per pixel at the camera's zoom, rounded to a power of 2,
+/// such that the curves are resampled when zooming by a factor of 2, not with every scroll
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PixelSize(pub f64);
+
+impl PixelSize {
+    pub fn of_scale(scale: f64) -> Self {
+        Self(2_f64.powf(scale.log2().round()))
+    }
+}
+
+impl Default for PixelSize {
+    fn default() -> Self {
+        Self::of_scale(INITIAL_CAMERA_SCALE as f64)
+    }
+}
+
+fn update_pixel_size(
+    camera_query: Query<&OrthographicProjection, With<CameraController>>,
+    mut pixel_size: ResMut<PixelSize>,
+) {
+    for projection in camera_query.iter() {
+        pixel_size.set_if_neq(PixelSize::of_scale(projection.scale as f64));
+    }
+}
+
+/// samples a curve on the domain for the screen: starts with a sample every `SAMPLE_PX` px,
+/// and halves the intervals in which the curve bends, so oscillations get samples without oversampling flat parts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveSampling {
+    start: f64,
+    end: f64,
+    intervals: usize,
+    /// max deviation (screen units) of the curve from the line between samples
+    tolerance: f64,
+}
+
+impl AdaptiveSampling {
+    pub fn new(settings: &PlotSettings, pixel_size: PixelSize) -> Self {
+        let (start, end) = (settings.domain_range_start, settings.domain_range_end);
+        let width_px = (end - start) * settings.screen_scale_x / pixel_size.0;
+        let intervals = (width_px / SAMPLE_PX).ceil();
+        Self {
+            start,
+            end,
+            intervals: if intervals.is_finite() {
+                (intervals as usize).clamp(1, MAX_INITIAL_SAMPLES)
+            } else {
+                1
+            },
+            tolerance: MAX_DEVIATION_PX * pixel_size.0,
+        }
+    }
+
+    /// (x, function(x)) from start to end
+    /// deviation is the screen distance of the value at an interval's middle from the mean of the values at its ends
+    /// breaks are sampled too, e.g. the cusps of Ψ, which halving the intervals would only get close to
+    pub fn sample<T, F, D>(&self, function: F, deviation: D, breaks: &[f64]) -> Vec<(f64, T)>
+    where
+        T: Copy,
+        F: Fn(f64) -> T,
+        D: Fn(T, T, T) -> f64,
+    {
+        let step = (self.end - self.start) / self.intervals as f64;
+        let mut nodes: Vec<f64> = (1..self.intervals)
+            .map(|i| self.start + i as f64 * step)
+            .chain(
+                breaks
+                    .iter()
+                    .cloned()
+                    .filter(|x| *x > self.start && *x < self.end),
+            )
+            .collect();
+        nodes.sort_by(f64::total_cmp);
+        nodes.dedup();
+        nodes.push(self.end);
+
+        let mut samples = vec![(self.start, function(self.start))];
+        for x in nodes {
+            let start = samples[samples.len() - 1];
+            self.refine(
+                start,
+                (x, function(x)),
+                0,
+                &function,
+                &deviation,
+                &mut samples,
+            );
+        }
+        samples
+    }
+
+    /// adds the samples after start up to end
+    fn refine<T, F, D>(
+        &self,
+        start: (f64, T),
+        end: (f64, T),
+        depth: u32,
+        function: &F,
+        deviation: &D,
+        samples: &mut Vec<(f64, T)>,
+    ) where
+        T: Copy,
+        F: Fn(f64) -> T,
+        D: Fn(T, T, T) -> f64,
+    {
+        if depth < MAX_REFINEMENTS {
+            let x = (start.0 + end.0) / 2.0;
+            let middle = (x, function(x));
+            if deviation(start.1, middle.1, end.1) > self.tolerance {
+                self.refine(start, middle, depth + 1, function, deviation, samples);
+                self.refine(middle, end, depth + 1, function, deviation, samples);
+                return;
+            }
+        }
+        samples.push(end);
+    }
+}
+
 /// generates axis lines
-fn setup_axes(mut gizmos: Gizmos) {
+pub fn setup_axes(mut gizmos: Gizmos) {
     let size = 300.0;
     let zero = 0.0;
     // x
@@ -263,28 +616,119 @@ fn setup_axes(mut gizmos: Gizmos) {
     gizmos.line_2d(Vec2 { x: zero, y: -size }, Vec2 { x: zero, y: size }, GREEN);
 }
 
-/// generates axis ticks
-pub fn setup_plot_ticks(gizmos: &mut Gizmos, settings: PlotSettings) {
-    let domain_points = generate_points(
-        settings.domain_range_start,
-        settings.domain_range_end,
-        settings.ticks.step,
-        |x| x,
-    );
-    let line_height = 0.1;
-    let half_line_height = line_height / 2.0;
-    for point in domain_points {
-        let x = point.x * settings.screen_scale_x;
-        gizmos.line_2d(
-            Vec2 {
-                x,
-                y: -half_line_height,
-            },
-            Vec2 {
-                x,
-                y: half_line_height,
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use bevy::math::Vec2;
+
+    use super::{
+        dashes, generate_path, AdaptiveSampling, PixelSize, PlotSettings, DASH, SAMPLE_PX,
+    };
+
+    #[test]
+    fn dashes_continue_through_points() {
+        let (dash, gap) = DASH;
+        let period = dash + gap;
+        let points = [
+            Vec2::ZERO,
+            Vec2::new(period * 1.25, 0.0),
+            Vec2::new(period * 1.25, period),
+        ];
+        let dashes = dashes(&points);
+        assert_eq!(4, dashes.len());
+        assert_relative_eq!(dash, dashes[0].0.distance(dashes[0].1));
+        // cut by the corner, continued on the next line
+        assert_relative_eq!(period * 0.25, dashes[1].0.distance(dashes[1].1));
+        assert_relative_eq!(
+            dash - period * 0.25,
+            dashes[2].0.distance(dashes[2].1),
+            max_relative = 1e-5
+        );
+        assert!(points[1].distance(dashes[2].0) < 1e-6);
+    }
+
+    #[test]
+    fn dashes_of_long_lines_end() {
+        let (dash, gap) = DASH;
+        let length = 1000.0;
+        let dashes = dashes(&[Vec2::ZERO, Vec2::new(length, 0.0)]);
+        let expected = length / (dash + gap);
+        assert!((dashes.len() as f32 - expected).abs() <= 1.0);
+    }
+
+    #[test]
+    fn adaptive_sampling_refines_where_curves_bend() {
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: 1.0,
+            screen_scale_x: 1.0,
+            ..PlotSettings::default()
+        };
+        let pixel_size = PixelSize(0.01);
+        let sampling = AdaptiveSampling::new(&settings, pixel_size);
+        let deviation = |start: f64, middle: f64, end: f64| (middle - (start + end) / 2.0).abs();
+        let intervals = (1.0 / (pixel_size.0 * SAMPLE_PX)) as usize;
+
+        // a line isn't refined
+        let line = sampling.sample(|x| 2.0 * x, deviation, &[]);
+        assert_eq!(intervals + 1, line.len());
+        assert_eq!((1.0, 2.0), line[intervals]);
+
+        // flat on the left half, oscillating on the right one
+        let curve = sampling.sample(
+            |x| {
+                if x < 0.5 {
+                    0.0
+                } else {
+                    ((x - 0.5) * 300.0).sin()
+                }
             },
-            GREEN,
+            deviation,
+            &[],
         );
+        let left = curve.iter().filter(|(x, _)| *x < 0.5).count();
+        let right = curve.len() - left;
+        assert_eq!(intervals / 2, left);
+        assert!(right > 4 * left);
+        assert!(curve.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(1.0, curve[curve.len() - 1].0);
+    }
+
+    #[test]
+    fn adaptive_sampling_includes_breaks() {
+        let settings = PlotSettings {
+            domain_range_start: -1.0,
+            domain_range_end: 1.0,
+            screen_scale_x: 1.0,
+            ..PlotSettings::default()
+        };
+        let sampling = AdaptiveSampling::new(&settings, PixelSize(0.01));
+        let deviation = |start: f64, middle: f64, end: f64| (middle - (start + end) / 2.0).abs();
+        // a cusp between the initial samples
+        let cusp = 0.123_456;
+        let curve = sampling.sample(|x| -(x - cusp).abs(), deviation, &[cusp, 5.0]);
+        assert!(curve.contains(&(cusp, 0.0)));
+        assert!(curve.windows(2).all(|pair| pair[0].0 < pair[1].0));
+        assert_eq!(1.0, curve[curve.len() - 1].0);
+    }
+
+    #[test]
+    fn paths_keep_corners() {
+        let points = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(-0.5, 0.5),
+            Vec2::ZERO,
+            Vec2::new(0.5, 0.5),
+            Vec2::new(1.0, 1.0),
+        ];
+        // the control points next to the corner are on the lines to it, so the curve has the kink
+        let path = generate_path(&points, &[2], 0.3, 0.3);
+        assert_relative_eq!(points[1].y * 2.0 / 3.0, path[1][1].y);
+        assert_relative_eq!(points[1].y / 3.0, path[1][2].y);
+        assert_relative_eq!(points[3].y / 3.0, path[2][1].y);
+
+        // smoothed across it otherwise
+        let path = generate_path(&points, &[], 0.3, 0.3);
+        assert!(path[1][2].y < points[1].y / 3.0 - 0.1);
     }
 }
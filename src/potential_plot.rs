@@ -0,0 +1,319 @@
+/// plots the potential V(x) and horizontal lines at the energies E_n, over the wave function
+/// the energies share the y axis with V: V's minimum on the domain is at the x axis,
+/// and the scale is such that the drawn levels fit on the screen
+/// V is clipped at the top of the plot, so infinite walls are vertical lines, and δ potentials are vertical lines
+/// up to the top (barriers) or down below the x axis (wells), whose levels are below V's finite minimum
+/// V is only resampled when the model changes, and the series are only replaced when V or the lines change
+use crate::{
+    model::{ModelPlotSettings, PotentialModel},
+    plot::{generate_points, LineStyle, PlotSettings, Series, SeriesId},
+    superposition::Superposition,
+    ui::{despawn_all_entities, CurveOptions, EnergyLevel, EnergyLevelRange, PotentialModelInput},
+};
+use bevy::{
+    color::palettes::tailwind::{SKY_400, YELLOW_400},
+    math::DVec2,
+    prelude::*,
+};
+use uom::si::{energy::joule, f64::Length, length::meter};
+
+pub const POTENTIAL_COLOR: Srgba = YELLOW_400;
+pub const ENERGY_LEVEL_COLOR: Srgba = SKY_400;
+
+pub const POTENTIAL_SERIES: SeriesId = SeriesId("V");
+/// a series of a line per level
+pub const ENERGY_LEVELS_SERIES: SeriesId = SeriesId("E_n");
+
+/// number of the lowest levels drawn as lines, besides the superposition's
+const ENERGY_LINES: u32 = 10;
+/// screen height of the highest drawn level
+const ENERGY_SCREEN_HEIGHT: f64 = 3.0;
+/// V is clipped at this screen height
+const POTENTIAL_SCREEN_TOP: f64 = 4.0;
+/// δ wells reach down to this screen height
+const POTENTIAL_SCREEN_BOTTOM: f64 = -1.0;
+/// alpha of the lines of the levels not in the superposition
+const INACTIVE_LEVEL_ALPHA: f32 = 0.35;
+
+/// marker for the series of V and the E_n lines, which are replaced together
+#[derive(Component)]
+pub struct PotentialCurve;
+
+/// maps energies to the screen's y, shared by V, the E_n lines and Ψ offset to its energy
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct EnergyScale {
+    /// energy (J) on the x axis
+    pub baseline: f64,
+    /// screen units per J
+    pub scale: f64,
+}
+
+impl EnergyScale {
+    /// V's minimum (J) at the x axis, or the lowest energy if it's below it (δ wells),
+    /// the highest energy (J) at `ENERGY_SCREEN_HEIGHT`
+    /// values that aren't finite (walls, unbound levels) are ignored
+    pub fn new(potential: &[f64], energies: &[f64]) -> Self {
+        let finite_min = |values: &[f64]| {
+            values
+                .iter()
+                .cloned()
+                .filter(|v| v.is_finite())
+                .reduce(f64::min)
+        };
+        let finite_max = |values: &[f64]| {
+            values
+                .iter()
+                .cloned()
+                .filter(|v| v.is_finite())
+                .reduce(f64::max)
+        };
+
+        let baseline = match (finite_min(potential), finite_min(energies)) {
+            (Some(potential), Some(energy)) => potential.min(energy),
+            (potential, energy) => potential.or(energy).unwrap_or(0.0),
+        };
+        // a flat potential without levels still gets the screen's height
+        let top = finite_max(energies)
+            .filter(|top| *top > baseline)
+            .or(finite_max(potential));
+        let scale = match top {
+            Some(top) if top > baseline => ENERGY_SCREEN_HEIGHT / (top - baseline),
+            _ => 0.0,
+        };
+        Self { baseline, scale }
+    }
+
+    pub fn screen_y(&self, energy: f64) -> f64 {
+        (energy - self.baseline) * self.scale
+    }
+}
+
+/// levels drawn as lines: the lowest ones and the superposition's
+pub fn energy_line_levels(
+    range: &EnergyLevelRange,
+    superposition: &Superposition,
+) -> Vec<EnergyLevel> {
+    let lowest_max = range.max.min(range.min + ENERGY_LINES - 1);
+    let mut levels: Vec<EnergyLevel> = (range.min..=lowest_max).map(EnergyLevel).collect();
+    for weighted in &superposition.levels {
+        if !levels.contains(&weighted.level) {
+            levels.push(weighted.level);
+        }
+    }
+    levels
+}
+
+/// (x, V(x)) in (m, J) at the settings' sample points
+fn sample_potential<M: PotentialModel>(model: &M, settings: &PlotSettings) -> Vec<DVec2> {
+    generate_points(
+        settings.domain_range_start,
+        settings.domain_range_end,
+        settings.sample_step,
+        |x| model.potential(Length::new::<meter>(x)).get::<joule>(),
+    )
+}
+
+/// updates the energy scale for the model's potential and levels
+/// when the model or the drawn levels change, not with the superposition's time
+pub fn update_energy_scale<M: PotentialModel>(
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    selected: Res<PotentialModelInput>,
+    range: Res<EnergyLevelRange>,
+    superposition: Res<Superposition>,
+    mut energy_scale: ResMut<EnergyScale>,
+    mut fitted_levels: Local<Vec<EnergyLevel>>,
+) {
+    let levels = energy_line_levels(&range, &superposition);
+    if !(model.is_changed() || settings.is_changed() || selected.is_changed())
+        && *fitted_levels == levels
+    {
+        return;
+    }
+    let potential: Vec<f64> = sample_potential(&*model, &settings.settings)
+        .iter()
+        .map(|point| point.y)
+        .collect();
+    let energies: Vec<f64> = levels
+        .iter()
+        .map(|level| model.energy(level).get::<joule>())
+        .collect();
+    energy_scale.set_if_neq(EnergyScale::new(&potential, &energies));
+    *fitted_levels = levels;
+}
+
+/// V(x) sampled for the model, and the drawn E_n lines with whether they're in the superposition
+#[derive(Default)]
+pub struct DrawnPotential {
+    potential: Vec<DVec2>,
+    lines: Vec<(EnergyLevel, bool)>,
+}
+
+/// replaces the series of V(x) and the E_n lines, the superposition's levels highlighted
+/// they're removed in momentum space
+#[allow(clippy::too_many_arguments)]
+pub fn setup_potential_curves<M: PotentialModel>(
+    mut commands: Commands,
+    curve_query: Query<Entity, With<PotentialCurve>>,
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    selected: Res<PotentialModelInput>,
+    range: Res<EnergyLevelRange>,
+    superposition: Res<Superposition>,
+    energy_scale: Res<EnergyScale>,
+    options: Res<CurveOptions>,
+    mut drawn: Local<DrawnPotential>,
+) {
+    let lines: Vec<(EnergyLevel, bool)> = energy_line_levels(&range, &superposition)
+        .into_iter()
+        .map(|level| (level, superposition.weight(level).amplitude > 0.0))
+        .collect();
+    let model_changed = model.is_changed() || settings.is_changed();
+    if !(model_changed
+        || selected.is_changed()
+        || energy_scale.is_changed()
+        || options.is_changed())
+        && drawn.lines == lines
+    {
+        return;
+    }
+    if model_changed {
+        drawn.potential = sample_potential(&*model, &settings.settings);
+    }
+    drawn.lines = lines;
+
+    despawn_all_entities(&mut commands, &curve_query);
+    if options.momentum {
+        return;
+    }
+    let settings = &settings.settings;
+    let screen_x = |x: f64| (x * settings.screen_scale_x) as f32;
+
+    let points = drawn
+        .potential
+        .iter()
+        .map(|point| {
+            let y = if point.y.is_finite() {
+                energy_scale.screen_y(point.y).min(POTENTIAL_SCREEN_TOP)
+            } else {
+                POTENTIAL_SCREEN_TOP
+            };
+            Vec2::new(screen_x(point.x), y as f32)
+        })
+        .collect();
+    // not smoothed, to keep the steps at the walls
+    let series = Series::new(
+        POTENTIAL_SERIES,
+        "V(x)",
+        POTENTIAL_COLOR,
+        LineStyle::Straight,
+        points,
+    );
+    commands.spawn((PotentialCurve, series));
+
+    for delta in model.deltas() {
+        let x = delta.position.get::<meter>();
+        let from = energy_scale
+            .screen_y(model.potential(delta.position).get::<joule>())
+            .min(POTENTIAL_SCREEN_TOP);
+        let to = if delta.strength > 0.0 {
+            POTENTIAL_SCREEN_TOP
+        } else {
+            POTENTIAL_SCREEN_BOTTOM
+        };
+        let points = vec![
+            Vec2::new(screen_x(x), from as f32),
+            Vec2::new(screen_x(x), to as f32),
+        ];
+        let series = Series::new(
+            POTENTIAL_SERIES,
+            "V(x)",
+            POTENTIAL_COLOR,
+            LineStyle::Straight,
+            points,
+        )
+        .unlabeled();
+        commands.spawn((PotentialCurve, series));
+    }
+
+    let start = screen_x(settings.domain_range_start);
+    let end = screen_x(settings.domain_range_end);
+    for (level, active) in &drawn.lines {
+        let energy = model.energy(level).get::<joule>();
+        if !energy.is_finite() {
+            continue;
+        }
+        let y = energy_scale.screen_y(energy) as f32;
+        let points = vec![Vec2::new(start, y), Vec2::new(end, y)];
+        // the legend lists the series once, with the highlighted color
+        let series = if *active {
+            Series::new(
+                ENERGY_LEVELS_SERIES,
+                "E_n",
+                ENERGY_LEVEL_COLOR,
+                LineStyle::Straight,
+                points,
+            )
+        } else {
+            Series::new(
+                ENERGY_LEVELS_SERIES,
+                "E_n",
+                ENERGY_LEVEL_COLOR.with_alpha(INACTIVE_LEVEL_ALPHA),
+                LineStyle::Dashed,
+                points,
+            )
+            .unlabeled()
+        };
+        commands.spawn((PotentialCurve, series));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use super::{energy_line_levels, EnergyScale, ENERGY_LINES, ENERGY_SCREEN_HEIGHT};
+    use crate::{
+        superposition::Superposition,
+        ui::{EnergyLevel, EnergyLevelRange},
+    };
+
+    #[test]
+    fn energy_scale_fits_levels_above_potential_minimum() {
+        let potential = [f64::INFINITY, 2e-20, 1e-20, 3e-20, f64::INFINITY];
+        let energies = [2e-20, 5e-20, f64::NAN];
+        let scale = EnergyScale::new(&potential, &energies);
+        assert_relative_eq!(0.0, scale.screen_y(1e-20));
+        assert_relative_eq!(ENERGY_SCREEN_HEIGHT, scale.screen_y(5e-20));
+        assert_relative_eq!(ENERGY_SCREEN_HEIGHT / 4.0, scale.screen_y(2e-20));
+    }
+
+    #[test]
+    fn energy_scale_fits_levels_below_potential_of_delta_wells() {
+        // V is 0 besides the δs, which aren't sampled
+        let potential = [0.0, 0.0, 0.0];
+        let energies = [-4e-19, -1e-19];
+        let scale = EnergyScale::new(&potential, &energies);
+        assert_relative_eq!(0.0, scale.screen_y(-4e-19));
+        assert_relative_eq!(ENERGY_SCREEN_HEIGHT, scale.screen_y(-1e-19));
+
+        // a single level, V's maximum is the top
+        let scale = EnergyScale::new(&potential, &[-4e-19]);
+        assert_relative_eq!(ENERGY_SCREEN_HEIGHT, scale.screen_y(0.0));
+    }
+
+    #[test]
+    fn energy_lines_include_superposition_levels() {
+        let range = EnergyLevelRange { min: 0, max: 300 };
+        let superposition = Superposition::single(EnergyLevel(40));
+        let levels = energy_line_levels(&range, &superposition);
+        assert_eq!(ENERGY_LINES as usize + 1, levels.len());
+        assert_eq!(Some(&EnergyLevel(40)), levels.last());
+
+        // fewer levels than lines
+        let range = EnergyLevelRange { min: 1, max: 3 };
+        let superposition = Superposition::single(EnergyLevel(2));
+        let levels = energy_line_levels(&range, &superposition);
+        assert_eq!(vec![EnergyLevel(1), EnergyLevel(2), EnergyLevel(3)], levels);
+    }
+}
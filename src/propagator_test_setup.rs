@@ -0,0 +1,60 @@
+/// setup shared by the propagators' tests: the harmonic oscillator's V on a grid, a wave packet in it,
+/// and the norm of Ψ sampled on a grid
+use crate::{
+    finite_difference_solver::interior_grid, harmonic_oscillator_plot::HarmonicOscillator,
+    model::PotentialModel, plot::PlotSettings, time_evolution::gaussian_wave_packet,
+};
+use num_complex::Complex64;
+use uom::si::{energy::joule, f64::Length, length::meter, mass::kilogram};
+
+/// ∫|Ψ|^2 dx, with Ψ sampled every step
+pub fn norm(psi: &[Complex64], step: f64) -> f64 {
+    psi.iter().map(|p| p.norm_sqr()).sum::<f64>() * step
+}
+
+/// V (J) of model at the grid's points
+pub fn potential<M: PotentialModel>(model: &M, grid: &[f64]) -> Vec<f64> {
+    grid.iter()
+        .map(|x| model.potential(Length::new::<meter>(*x)).get::<joule>())
+        .collect()
+}
+
+/// the default harmonic oscillator, on a few times its ground state's width,
+/// the model's domain fits much higher levels
+pub struct HarmonicOscillatorSetup {
+    pub model: HarmonicOscillator,
+    pub grid: Vec<f64>,
+    pub step: f64,
+    /// V (J) on the grid
+    pub potential: Vec<f64>,
+    /// kg
+    pub mass: f64,
+}
+
+impl Default for HarmonicOscillatorSetup {
+    fn default() -> Self {
+        let model = HarmonicOscillator::default();
+        let (grid, step) = interior_grid(&PlotSettings {
+            domain_range_start: -2e-10,
+            domain_range_end: 2e-10,
+            sample_step: 1e-12,
+            ..PlotSettings::default()
+        });
+        let potential = potential(&model, &grid);
+        let mass = model.mass().get::<kilogram>();
+        Self {
+            model,
+            grid,
+            step,
+            potential,
+            mass,
+        }
+    }
+}
+
+impl HarmonicOscillatorSetup {
+    /// a packet left of the well's center, moving right
+    pub fn packet(&self) -> Vec<Complex64> {
+        gaussian_wave_packet(&self.grid, -5e-11, 2e-11, 5e10)
+    }
+}
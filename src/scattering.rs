@@ -0,0 +1,211 @@
+/// scattering of a plane wave e^(ikx) incident from the left on a potential that's flat far from it (step, barrier)
+/// the energy E is continuous, a parameter of the model instead of a level, and Ψ isn't normalizable,
+/// the incident wave has amplitude 1
+/// the transmission and reflection probabilities T(E), R(E) are plotted on the inset, with a marker at the current E
+use crate::{
+    axis::{AxisUnit, PlotAxes, PlotAxis},
+    inset::{InsetAxes, InsetSeries, INSET_HEIGHT, INSET_WIDTH},
+    model::{ModelParameter, ModelPlotSettings, ParameterRange, PotentialModel, H_BAR},
+    plot::{generate_points, LineStyle, PlotSettings, Series, SeriesId},
+    potential_plot::{ENERGY_LEVELS_SERIES, ENERGY_LEVEL_COLOR},
+    time_evolution::TimeEvolution,
+    ui::{despawn_all_entities, PotentialModelInput},
+};
+use bevy::{
+    color::palettes::tailwind::{ORANGE_400, TEAL_400},
+    prelude::*,
+};
+use num_complex::Complex64;
+use uom::si::{
+    energy::joule,
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+pub const TRANSMISSION_COLOR: Srgba = TEAL_400;
+pub const REFLECTION_COLOR: Srgba = ORANGE_400;
+
+pub const TRANSMISSION_SERIES: SeriesId = SeriesId("T(E)");
+pub const REFLECTION_SERIES: SeriesId = SeriesId("R(E)");
+
+/// number of energies T and R are sampled at
+const SCATTERING_SAMPLES: f64 = 400.0;
+/// the energies span this many times V's maximum, so what happens above it shows (e.g. resonances)
+const ENERGY_RANGE_FACTOR: f64 = 3.0;
+
+/// probabilities of the incident wave to be transmitted and reflected, T + R = 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scattering {
+    pub transmission: f64,
+    pub reflection: f64,
+}
+
+/// √(2m(E - V))/ħ, the wave number where E > V, and iκ, with κ > 0 the decay constant, where E < V
+pub fn wave_number(mass: Mass, energy: Energy, potential: Energy) -> Complex64 {
+    let kinetic = (energy - potential).get::<joule>();
+    Complex64::new(2.0 * mass.get::<kilogram>() * kinetic, 0.0).sqrt() / H_BAR
+}
+
+/// wave lengths on each side of the potential on the plot's domain,
+/// at the model's characteristic energy (e.g. V₀), so Ψ's oscillations and decay show
+pub const DOMAIN_WAVE_LENGTHS: f64 = 4.0;
+
+/// the incident wave's energy E, the scattering models' first parameter
+pub fn energy_parameter(energy: Energy) -> ModelParameter {
+    ModelParameter {
+        name: "E",
+        value: energy.get::<joule>(),
+        unit: "J",
+        // 1 meV to 100 eV
+        range: ParameterRange::new(1.6e-22, 1.6e-17),
+    }
+}
+
+/// plot settings for a potential over [start, start + length], flat outside of it:
+/// a few wave lengths on each side, or the potential's length if it's longer
+pub fn scattering_plot_settings(wave_length: f64, start: f64, length: f64) -> PlotSettings {
+    let side = (DOMAIN_WAVE_LENGTHS * wave_length).max(length);
+    let extent = length + 2.0 * side;
+    PlotSettings {
+        domain_range_start: start - side,
+        domain_range_end: start + length + side,
+        sample_step: extent / 2000.0,
+        screen_scale_x: 10.0 / extent,
+        ..PlotSettings::default()
+    }
+}
+
+/// marker for the T(E), R(E) curves and the E marker on the inset, replaced together
+#[derive(Component)]
+pub struct ScatteringCurve;
+
+/// the energies the T(E), R(E) plot spans: up to a multiple of V's maximum on the domain, or beyond E
+/// a δ barrier counts with the energy at which it transmits half, as it has no height
+fn energy_range_end<M: PotentialModel>(
+    model: &M,
+    settings: &ModelPlotSettings<M>,
+    energy: f64,
+) -> f64 {
+    let settings = &settings.settings;
+    let potential_max = generate_points(
+        settings.domain_range_start,
+        settings.domain_range_end,
+        settings.sample_step,
+        |x| model.potential(Length::new::<meter>(x)).get::<joule>(),
+    )
+    .iter()
+    .map(|point| point.y)
+    .filter(|v| v.is_finite())
+    .chain(
+        model
+            .deltas()
+            .iter()
+            .filter(|delta| delta.strength > 0.0)
+            .map(|delta| delta.energy(model.mass()).get::<joule>()),
+    )
+    .fold(0.0, f64::max);
+    (ENERGY_RANGE_FACTOR * potential_max).max(1.25 * energy)
+}
+
+/// replaces the T(E), R(E) curves and the E marker of scattering models, and sets the inset's axes for them
+/// other models hide the inset
+/// while the time evolution is active, the inset is left to the expectation values' history
+#[allow(clippy::too_many_arguments)]
+pub fn setup_scattering_curves<M: PotentialModel>(
+    mut commands: Commands,
+    curve_query: Query<Entity, With<ScatteringCurve>>,
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    selected: Res<PotentialModelInput>,
+    time_evolution: Res<TimeEvolution>,
+    mut inset_axes: ResMut<InsetAxes>,
+    mut evolving: Local<bool>,
+) {
+    if !(model.is_changed()
+        || settings.is_changed()
+        || selected.is_changed()
+        || *evolving != time_evolution.active)
+    {
+        return;
+    }
+    *evolving = time_evolution.active;
+    despawn_all_entities(&mut commands, &curve_query);
+    if time_evolution.active {
+        return;
+    }
+    let energy = model
+        .energy_parameter()
+        .and_then(|index| model.parameters().get(index).map(|p| p.value));
+    let Some(energy) = energy else {
+        inset_axes.set_if_neq(InsetAxes::default());
+        return;
+    };
+
+    let end = energy_range_end(&*model, &settings, energy);
+    let scale_x = INSET_WIDTH as f64 / end;
+    let scale_y = INSET_HEIGHT as f64;
+    let mut transmission = vec![];
+    let mut reflection = vec![];
+    for point in generate_points(0.0, end, end / SCATTERING_SAMPLES, |_| 0.0) {
+        let Some(scattering) = model.scattering(Energy::new::<joule>(point.x)) else {
+            continue;
+        };
+        let x = (point.x * scale_x) as f32;
+        transmission.push(Vec2::new(x, (scattering.transmission * scale_y) as f32));
+        reflection.push(Vec2::new(x, (scattering.reflection * scale_y) as f32));
+    }
+    commands.spawn((
+        ScatteringCurve,
+        InsetSeries,
+        Series::new(
+            TRANSMISSION_SERIES,
+            "T(E)",
+            TRANSMISSION_COLOR,
+            LineStyle::Straight,
+            transmission,
+        ),
+    ));
+    commands.spawn((
+        ScatteringCurve,
+        InsetSeries,
+        Series::new(
+            REFLECTION_SERIES,
+            "R(E)",
+            REFLECTION_COLOR,
+            LineStyle::Straight,
+            reflection,
+        ),
+    ));
+    // hidden with the E_n line of the main plot
+    let x = (energy * scale_x) as f32;
+    let marker = vec![Vec2::new(x, 0.0), Vec2::new(x, INSET_HEIGHT)];
+    commands.spawn((
+        ScatteringCurve,
+        InsetSeries,
+        Series::new(
+            ENERGY_LEVELS_SERIES,
+            "E",
+            ENERGY_LEVEL_COLOR,
+            LineStyle::Dashed,
+            marker,
+        )
+        .unlabeled(),
+    ));
+
+    inset_axes.set_if_neq(InsetAxes {
+        axes: Some(PlotAxes {
+            x: PlotAxis {
+                symbol: "E",
+                unit: AxisUnit::Other("J"),
+                screen_scale: scale_x,
+            },
+            y: PlotAxis {
+                symbol: "T, R",
+                unit: AxisUnit::Other(""),
+                screen_scale: scale_y,
+            },
+        }),
+        centered_y: false,
+    });
+}
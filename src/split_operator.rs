@@ -0,0 +1,226 @@
+/// split-operator (split-step Fourier) propagator for the time dependent Schrödinger equation
+/// Ψ(t + Δt) = e^(-iVΔt/2ħ) F⁻¹ e^(-iħk^2Δt/2m) F e^(-iVΔt/2ħ) Ψ(t), F being the FFT
+/// each factor is unitary, so the norm is conserved, and the kinetic part is exact for the grid's wave numbers
+/// the FFT makes the grid periodic: Ψ leaving at one domain end comes back at the other,
+/// unless the grid is padded with an absorbing layer
+/// grid points where V isn't finite are walls, Ψ is set to 0 there
+use crate::model::H_BAR;
+use num_complex::Complex64;
+use rustfft::{Fft, FftPlanner};
+use std::{f64::consts::PI, sync::Arc};
+
+/// what happens to Ψ at the domain ends
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// leaves at one end and comes back at the other
+    Periodic,
+    /// the grid is extended at both ends by a layer where Ψ is damped, such that it doesn't come back
+    Absorbing,
+}
+
+pub struct SplitOperator {
+    /// e^(-iVΔt/2ħ) at the (padded) grid points, 0 at walls
+    potential_phase: Vec<Complex64>,
+    /// e^(-iħk^2Δt/2m) at the FFT's wave numbers
+    kinetic_phase: Vec<Complex64>,
+    /// damping factor at the (padded) grid points, 1 in the domain
+    mask: Vec<f64>,
+    /// number of points added at each end
+    padding: usize,
+    /// Ψ on the padded grid, keeps what left the domain until it's absorbed
+    buffer: Vec<Complex64>,
+    forward: Arc<dyn Fft<f64>>,
+    inverse: Arc<dyn Fft<f64>>,
+}
+
+impl SplitOperator {
+    /// potential (J) at the grid points, grid spacing (m), mass (kg), time step (s)
+    pub fn new(potential: &[f64], step: f64, mass: f64, dt: f64, boundary: Boundary) -> Self {
+        let padding = match boundary {
+            Boundary::Periodic => 0,
+            Boundary::Absorbing => potential.len() / 2,
+        };
+
+        // the padding continues the potential at the domain ends
+        let first = potential.first().cloned().unwrap_or(0.0);
+        let last = potential.last().cloned().unwrap_or(0.0);
+        let padded_potential: Vec<f64> = std::iter::repeat_n(first, padding)
+            .chain(potential.iter().cloned())
+            .chain(std::iter::repeat_n(last, padding))
+            .collect();
+        let n = padded_potential.len();
+
+        let potential_phase = padded_potential
+            .iter()
+            .map(|v| {
+                if v.is_finite() {
+                    Complex64::from_polar(1.0, -v * dt / (2.0 * H_BAR))
+                } else {
+                    Complex64::new(0.0, 0.0)
+                }
+            })
+            .collect();
+
+        // FFT order: 0, 1, .., n/2 - 1, -n/2, .., -1 times 2π/(n dx)
+        let kinetic_phase = (0..n)
+            .map(|j| {
+                let index = if j < n.div_ceil(2) {
+                    j as f64
+                } else {
+                    j as f64 - n as f64
+                };
+                let k = 2.0 * PI * index / (n as f64 * step);
+                Complex64::from_polar(1.0, -H_BAR * k * k * dt / (2.0 * mass))
+            })
+            .collect();
+
+        // cos^(1/8) mask, smooth enough to not reflect
+        let mask = (0..n)
+            .map(|i| {
+                let depth = if i < padding {
+                    padding - i
+                } else if i >= n - padding {
+                    i + 1 - (n - padding)
+                } else {
+                    0
+                };
+                (PI / 2.0 * depth as f64 / (padding + 1) as f64)
+                    .cos()
+                    .powf(0.125)
+            })
+            .collect();
+
+        let mut planner = FftPlanner::new();
+        Self {
+            potential_phase,
+            kinetic_phase,
+            mask,
+            padding,
+            buffer: vec![Complex64::new(0.0, 0.0); n],
+            forward: planner.plan_fft_forward(n),
+            inverse: planner.plan_fft_inverse(n),
+        }
+    }
+
+    /// advances Ψ by one time step
+    pub fn step(&mut self, psi: &mut [Complex64]) {
+        let n = self.buffer.len();
+        self.buffer[self.padding..self.padding + psi.len()].copy_from_slice(psi);
+
+        for (value, phase) in self.buffer.iter_mut().zip(&self.potential_phase) {
+            *value *= phase;
+        }
+        self.forward.process(&mut self.buffer);
+        for (value, phase) in self.buffer.iter_mut().zip(&self.kinetic_phase) {
+            // rustfft doesn't normalize, done here for the round trip
+            *value *= phase / n as f64;
+        }
+        self.inverse.process(&mut self.buffer);
+        for ((value, phase), mask) in self
+            .buffer
+            .iter_mut()
+            .zip(&self.potential_phase)
+            .zip(&self.mask)
+        {
+            *value *= phase * mask;
+        }
+
+        psi.copy_from_slice(&self.buffer[self.padding..self.padding + psi.len()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+    use uom::si::{energy::joule, f64::Length, length::meter};
+
+    use crate::{
+        finite_difference_solver::interior_grid,
+        model::{PotentialModel, H_BAR},
+        plot::PlotSettings,
+        propagator_test_setup::{norm, HarmonicOscillatorSetup},
+        time_evolution::gaussian_wave_packet,
+        ui::EnergyLevel,
+    };
+
+    use super::{Boundary, SplitOperator};
+
+    #[test]
+    fn conserves_norm() {
+        let setup = HarmonicOscillatorSetup::default();
+        let mut psi = setup.packet();
+        let mut propagator = SplitOperator::new(
+            &setup.potential,
+            setup.step,
+            setup.mass,
+            1e-18,
+            Boundary::Periodic,
+        );
+        for _ in 0..500 {
+            propagator.step(&mut psi);
+        }
+        assert_relative_eq!(1.0, norm(&psi, setup.step), max_relative = 1e-9);
+    }
+
+    #[test]
+    fn harmonic_oscillator_ground_state_only_gains_phase() {
+        let HarmonicOscillatorSetup {
+            model,
+            grid,
+            step,
+            potential,
+            mass,
+        } = HarmonicOscillatorSetup::default();
+
+        let initial: Vec<Complex64> = grid
+            .iter()
+            .map(|x| model.psi(Length::new::<meter>(*x), &EnergyLevel(0)))
+            .collect();
+        let initial_norm = norm(&initial, step).sqrt();
+        let initial: Vec<Complex64> = initial.iter().map(|p| p / initial_norm).collect();
+
+        let dt = 1e-19;
+        let steps = 200;
+        let mut psi = initial.clone();
+        let mut propagator = SplitOperator::new(&potential, step, mass, dt, Boundary::Periodic);
+        for _ in 0..steps {
+            propagator.step(&mut psi);
+        }
+
+        // <Ψ(0)|Ψ(t)> = e^(-iE₀t/ħ)
+        let overlap: Complex64 = initial
+            .iter()
+            .zip(&psi)
+            .map(|(a, b)| a.conj() * b)
+            .sum::<Complex64>()
+            * step;
+        assert_relative_eq!(1.0, overlap.norm(), max_relative = 1e-4);
+
+        let energy = model.energy(&EnergyLevel(0)).get::<joule>();
+        let expected_phase = -energy * dt * steps as f64 / H_BAR;
+        let phase_difference = (overlap.arg() - expected_phase).rem_euclid(2.0 * PI);
+        assert!(phase_difference.min(2.0 * PI - phase_difference) < 1e-3);
+    }
+
+    #[test]
+    fn absorbing_boundary_removes_outgoing_packet() {
+        let (grid, step) = interior_grid(&PlotSettings {
+            domain_range_start: -1e-9,
+            domain_range_end: 1e-9,
+            sample_step: 2e-12,
+            ..Default::default()
+        });
+        let potential = vec![0.0; grid.len()];
+        let mass = 9.11e-31;
+
+        let mut psi = gaussian_wave_packet(&grid, -5e-10, 1e-10, 2e10);
+        let mut propagator = SplitOperator::new(&potential, step, mass, 1e-17, Boundary::Absorbing);
+        // long enough for the packet to leave the domain (v ≈ 2.3e6 m/s)
+        for _ in 0..500 {
+            propagator.step(&mut psi);
+        }
+        assert!(norm(&psi, step) < 1e-3);
+    }
+}
@@ -0,0 +1,202 @@
+/// potential step, a scattering model
+/// it plots Ψ(x) and PDF(x) of a plane wave with energy E incident from the left, selected via the UI
+/// left of the step Ψ is the incident and the reflected wave, right of it the transmitted one,
+/// which decays where E < V₀ (total reflection)
+/// see https://en.wikipedia.org/wiki/Potential_step
+use crate::{
+    model::{ModelParameter, ParameterRange, PotentialModel, H_BAR},
+    plot::PlotSettings,
+    scattering::{energy_parameter, wave_number, Scattering, DOMAIN_WAVE_LENGTHS},
+    ui::EnergyLevel,
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{
+    energy::{electronvolt, joule},
+    f64::{Energy, Length, Mass},
+    length::meter,
+    mass::kilogram,
+};
+
+/// step at x = 0, V(x) = 0 left of it and V₀ (height) right of it
+#[derive(Resource)]
+pub struct PotentialStep {
+    pub energy: Energy,
+    pub height: Energy,
+    pub mass: Mass,
+}
+
+impl Default for PotentialStep {
+    fn default() -> Self {
+        Self {
+            energy: Energy::new::<electronvolt>(1.5),
+            height: Energy::new::<electronvolt>(1.0),
+            mass: Mass::new::<kilogram>(9.11e-31),
+        }
+    }
+}
+
+impl PotentialStep {
+    /// wave numbers k left and q right of the step
+    fn wave_numbers(&self, energy: Energy) -> (Complex64, Complex64) {
+        let zero = Energy::new::<joule>(0.0);
+        (
+            wave_number(self.mass, energy, zero),
+            wave_number(self.mass, energy, self.height),
+        )
+    }
+
+    /// amplitudes of the reflected and transmitted waves, from the continuity of Ψ and Ψ' at the step:
+    /// r = (k - q)/(k + q), t = 2k/(k + q)
+    fn amplitudes(&self, energy: Energy) -> (Complex64, Complex64) {
+        let (k, q) = self.wave_numbers(energy);
+        ((k - q) / (k + q), 2.0 * k / (k + q))
+    }
+}
+
+impl PotentialModel for PotentialStep {
+    fn name(&self) -> &'static str {
+        "Potential step"
+    }
+
+    fn parameters(&self) -> Vec<ModelParameter> {
+        vec![
+            energy_parameter(self.energy),
+            ModelParameter {
+                name: "V₀",
+                value: self.height.get::<joule>(),
+                unit: "J",
+                range: ParameterRange::new(1.6e-22, 1.6e-17),
+            },
+            ModelParameter {
+                name: "m",
+                value: self.mass.get::<kilogram>(),
+                unit: "kg",
+                range: ParameterRange::new(1e-31, 1e-26),
+            },
+        ]
+    }
+
+    fn set_parameter(&mut self, index: usize, value: f64) {
+        match index {
+            0 => self.energy = Energy::new::<joule>(value),
+            1 => self.height = Energy::new::<joule>(value),
+            2 => self.mass = Mass::new::<kilogram>(value),
+            _ => {}
+        }
+    }
+
+    fn mass(&self) -> Mass {
+        self.mass
+    }
+
+    /// the same for all levels, it's the state of energy E
+    fn psi(&self, x: Length, _level: &EnergyLevel) -> Complex64 {
+        let x = x.get::<meter>();
+        let (k, q) = self.wave_numbers(self.energy);
+        let (r, t) = self.amplitudes(self.energy);
+        let i = Complex64::i();
+        if x < 0.0 {
+            (i * k * x).exp() + r * (-i * k * x).exp()
+        } else {
+            t * (i * q * x).exp()
+        }
+    }
+
+    fn energy(&self, _level: &EnergyLevel) -> Energy {
+        self.energy
+    }
+
+    fn potential(&self, x: Length) -> Energy {
+        if x.get::<meter>() < 0.0 {
+            Energy::new::<joule>(0.0)
+        } else {
+            self.height
+        }
+    }
+
+    fn energy_parameter(&self) -> Option<usize> {
+        Some(0)
+    }
+
+    /// T = Re(q)/k |t|^2, the ratio of the transmitted and incident probability currents, R = |r|^2
+    fn scattering(&self, energy: Energy) -> Option<Scattering> {
+        let (k, q) = self.wave_numbers(energy);
+        if k.re <= 0.0 {
+            return None;
+        }
+        let (r, t) = self.amplitudes(energy);
+        Some(Scattering {
+            transmission: q.re / k.re * t.norm_sqr(),
+            reflection: r.norm_sqr(),
+        })
+    }
+
+    /// a few wave lengths at V₀ on each side of the step, such that the domain doesn't change with E
+    fn plot_settings(&self) -> PlotSettings {
+        let wave_length = 2.0 * PI * H_BAR
+            / (2.0 * self.mass.get::<kilogram>() * self.height.get::<joule>()).sqrt();
+        let half_extent = DOMAIN_WAVE_LENGTHS * wave_length;
+        PlotSettings {
+            domain_range_start: -half_extent,
+            domain_range_end: half_extent,
+            sample_step: wave_length / 200.0,
+            screen_scale_x: 5.0 / half_extent,
+            ..PlotSettings::default()
+        }
+    }
+
+    fn max_level(&self) -> u32 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::electronvolt,
+        f64::{Energy, Length},
+        length::meter,
+    };
+
+    use super::PotentialStep;
+    use crate::{model::PotentialModel, ui::EnergyLevel};
+
+    #[test]
+    fn psi_is_continuous_at_the_step() {
+        for energy in [0.5, 1.5, 4.0] {
+            let step = PotentialStep {
+                energy: Energy::new::<electronvolt>(energy),
+                ..PotentialStep::default()
+            };
+            let psi = |x: f64| step.psi(Length::new::<meter>(x), &EnergyLevel(0));
+            let h = 1e-15;
+            let difference = (psi(-h) - psi(0.0)).norm();
+            assert!(difference < 1e-4, "Ψ jumps by {}", difference);
+            // Ψ' from each side
+            let left = (psi(-h) - psi(-2.0 * h)) / h;
+            let right = (psi(2.0 * h) - psi(h)) / h;
+            assert!((left - right).norm() < 1e-3 * left.norm().max(right.norm()));
+        }
+    }
+
+    #[test]
+    fn transmission_and_reflection_add_up_to_1() {
+        let step = PotentialStep::default();
+        for energy in [0.2, 0.99, 1.01, 1.5, 10.0] {
+            let energy = Energy::new::<electronvolt>(energy);
+            let scattering = step.scattering(energy).unwrap();
+            assert_relative_eq!(
+                1.0,
+                scattering.transmission + scattering.reflection,
+                epsilon = 1e-12
+            );
+            // T = 4kq/(k + q)^2 above the step, 0 below it
+            let (k, q) = step.wave_numbers(energy);
+            let expected = 4.0 * k.re * q.re / (k.re + q.re).powi(2);
+            assert_relative_eq!(expected, scattering.transmission, epsilon = 1e-12);
+        }
+    }
+}
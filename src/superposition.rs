@@ -0,0 +1,411 @@
+/// superposition of a model's eigenstates, Ψ(x, t) = Σ c_n Ψ_n(x) e^(-iE_n t/ħ)
+/// the amplitudes and phases of c_n are set per level on the UI, and normalized such that Σ|c_n|^2 = 1
+/// selecting a level with the energy level control resets it to that single level
+/// the time evolution is exact, so it can be animated at any speed, e.g. to show revivals
+use crate::{
+    expectation::ExpectationValues,
+    model::{cusps, PotentialModel, H_BAR},
+    plot::{AdaptiveSampling, PlotSettings},
+    ui::{
+        EnergyLevel, EnergyLevelRange, PlusMinusInput, SuperpositionInput, SuperpositionInputEvent,
+        SuperpositionLabelMarker,
+    },
+    wave_function_plot::{curve_deviation, sample_wave_function, CurveExtents},
+};
+use bevy::prelude::*;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+use uom::si::{energy::joule, f64::Length, length::meter};
+
+/// change of an amplitude per click
+pub const AMPLITUDE_STEP: f64 = 0.1;
+/// change of a phase per click
+pub const PHASE_STEP: f64 = std::f64::consts::FRAC_PI_4;
+/// frames per period of the superposition's fastest oscillation, at speed 1
+const FRAMES_PER_PERIOD: f64 = 100.0;
+
+/// a level with its (not normalized) coefficient
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedLevel {
+    pub level: EnergyLevel,
+    /// |c_n| before normalization, in [0, 1]
+    pub amplitude: f64,
+    /// arg c_n (rad)
+    pub phase: f64,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct Superposition {
+    /// levels with a non zero amplitude, sorted by level
+    pub levels: Vec<WeightedLevel>,
+    /// t (s)
+    pub time: f64,
+    pub animating: bool,
+    /// factor applied to the time advanced per frame
+    pub speed: f64,
+}
+
+impl Default for Superposition {
+    fn default() -> Self {
+        Self::single(EnergyLevel(1))
+    }
+}
+
+impl Superposition {
+    /// the eigenstate of a level
+    pub fn single(level: EnergyLevel) -> Self {
+        Self {
+            levels: vec![WeightedLevel {
+                level,
+                amplitude: 1.0,
+                phase: 0.0,
+            }],
+            time: 0.0,
+            animating: false,
+            speed: 1.0,
+        }
+    }
+
+    /// the weight of a level, amplitude 0 if not in the superposition
+    pub fn weight(&self, level: EnergyLevel) -> WeightedLevel {
+        self.levels
+            .iter()
+            .find(|l| l.level == level)
+            .cloned()
+            .unwrap_or(WeightedLevel {
+                level,
+                amplitude: 0.0,
+                phase: 0.0,
+            })
+    }
+
+    /// sets the weight of a level, removing it if the amplitude is 0
+    pub fn set_weight(&mut self, weight: WeightedLevel) {
+        self.levels.retain(|l| l.level != weight.level);
+        if weight.amplitude > 0.0 {
+            self.levels.push(weight);
+            self.levels.sort_by_key(|l| l.level.0);
+        }
+    }
+
+    /// |c_n|^2 of a level, after normalization
+    pub fn probability(&self, level: EnergyLevel) -> f64 {
+        let total: f64 = self.levels.iter().map(|l| l.amplitude.powi(2)).sum();
+        if total > 0.0 {
+            self.weight(level).amplitude.powi(2) / total
+        } else {
+            0.0
+        }
+    }
+
+    /// normalized c_n e^(-iE_n t/ħ) for each level
+    pub fn coefficients<M: PotentialModel>(&self, model: &M) -> Vec<(EnergyLevel, Complex64)> {
+        let total: f64 = self.levels.iter().map(|l| l.amplitude.powi(2)).sum();
+        if total <= 0.0 {
+            return vec![];
+        }
+        let norm = total.sqrt();
+        self.levels
+            .iter()
+            .map(|l| {
+                let energy = model.energy(&l.level).get::<joule>();
+                let phase = l.phase - energy * self.time / H_BAR;
+                let c = Complex64::from_polar(l.amplitude / norm, phase);
+                (l.level, c)
+            })
+            .collect()
+    }
+
+    /// <E> = Σ|c_n|^2 E_n (J)
+    pub fn mean_energy<M: PotentialModel>(&self, model: &M) -> f64 {
+        self.levels
+            .iter()
+            .map(|l| self.probability(l.level) * model.energy(&l.level).get::<joule>())
+            .sum()
+    }
+
+    /// (x, Ψ(x, t)) on the plot's domain, with the settings' step
+    pub fn sample<M: PotentialModel>(
+        &self,
+        model: &M,
+        settings: &PlotSettings,
+    ) -> Vec<(f64, Complex64)> {
+        sample_wave_function(settings, self.psi(model))
+    }
+
+    /// (x, Ψ(x, t)) for the screen, denser where the curves bend, with extents giving their height
+    /// the cusps at δ potentials are sampled
+    pub fn sample_curves<M: PotentialModel>(
+        &self,
+        model: &M,
+        settings: &PlotSettings,
+        sampling: &AdaptiveSampling,
+        extents: &CurveExtents,
+    ) -> Vec<(f64, Complex64)> {
+        sampling.sample(
+            self.psi(model),
+            curve_deviation(extents, settings),
+            &cusps(model),
+        )
+    }
+
+    /// x (m) -> Ψ(x, t)
+    fn psi<'a, M: PotentialModel>(&self, model: &'a M) -> impl Fn(f64) -> Complex64 + 'a {
+        let coefficients = self.coefficients(model);
+        move |x| {
+            let x = Length::new::<meter>(x);
+            coefficients
+                .iter()
+                .map(|(level, c)| c * model.psi(x, level))
+                .sum()
+        }
+    }
+
+    /// extents of Ψ(x, t) at any t, from its bound |Ψ(x, t)| <= Σ|c_n||Ψ_n(x)|
+    /// which is reached for a single level, so the curves don't change scale while animating
+    pub fn extents<M: PotentialModel>(&self, model: &M, settings: &PlotSettings) -> CurveExtents {
+        let coefficients = self.coefficients(model);
+        let bound = sample_wave_function(settings, |x| {
+            let x = Length::new::<meter>(x);
+            coefficients
+                .iter()
+                .map(|(level, c)| c.norm() * model.psi(x, level).norm())
+                .sum::<f64>()
+                .into()
+        });
+        CurveExtents::of_samples(&bound)
+    }
+
+    /// period (s) of the fastest oscillation: the largest beat 2πħ/ΔE between levels,
+    /// or the phase rotation 2πħ/E for a single level
+    fn shortest_period<M: PotentialModel>(&self, model: &M) -> Option<f64> {
+        let energies: Vec<f64> = self
+            .levels
+            .iter()
+            .map(|l| model.energy(&l.level).get::<joule>())
+            .collect();
+        let min = energies.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = energies.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let energy = if energies.len() > 1 { max - min } else { max };
+        if energy.is_finite() && energy > 0.0 {
+            Some(2.0 * PI * H_BAR / energy)
+        } else {
+            None
+        }
+    }
+}
+
+/// maps the superposition controls to state
+pub fn listen_superposition_ui_inputs(
+    mut events: EventReader<SuperpositionInputEvent>,
+    mut superposition: ResMut<Superposition>,
+) {
+    for event in events.read() {
+        match event.input {
+            SuperpositionInput::Amplitude(level, plus_minus) => {
+                let mut weight = superposition.weight(level);
+                weight.amplitude =
+                    (weight.amplitude + sign(plus_minus) * AMPLITUDE_STEP).clamp(0.0, 1.0);
+                // avoid e.g. 0.3 - 0.1 - 0.1 - 0.1 staying in the superposition
+                weight.amplitude = (weight.amplitude * 10.0).round() / 10.0;
+                superposition.set_weight(weight);
+            }
+            SuperpositionInput::Phase(level, plus_minus) => {
+                let mut weight = superposition.weight(level);
+                if weight.amplitude > 0.0 {
+                    weight.phase =
+                        (weight.phase + sign(plus_minus) * PHASE_STEP).rem_euclid(2.0 * PI);
+                    superposition.set_weight(weight);
+                }
+            }
+            SuperpositionInput::Animate => superposition.animating = !superposition.animating,
+            SuperpositionInput::Faster => superposition.speed *= 2.0,
+            SuperpositionInput::Slower => superposition.speed /= 2.0,
+            SuperpositionInput::Restart => superposition.time = 0.0,
+        }
+    }
+}
+
+fn sign(plus_minus: PlusMinusInput) -> f64 {
+    match plus_minus {
+        PlusMinusInput::Plus => 1.0,
+        PlusMinusInput::Minus => -1.0,
+    }
+}
+
+/// selecting a level shows its eigenstate
+/// and levels the model doesn't have are removed from the superposition, e.g. when switching models
+pub fn reset_superposition(
+    energy_level_query: Query<&EnergyLevel, Changed<EnergyLevel>>,
+    range: Res<EnergyLevelRange>,
+    mut superposition: ResMut<Superposition>,
+) {
+    for energy_level in energy_level_query.iter() {
+        let animating = superposition.animating;
+        let speed = superposition.speed;
+        *superposition = Superposition {
+            animating,
+            speed,
+            ..Superposition::single(*energy_level)
+        };
+    }
+    if range.is_changed() {
+        let outside = |l: &WeightedLevel| l.level.0 < range.min || l.level.0 > range.max;
+        if superposition.levels.iter().any(outside) {
+            superposition.levels.retain(|l| !outside(l));
+        }
+    }
+}
+
+/// advances the time of the superposition, by a fraction of its fastest period per frame
+pub fn animate_superposition<M: PotentialModel>(
+    model: Res<M>,
+    mut superposition: ResMut<Superposition>,
+) {
+    if !superposition.animating {
+        return;
+    }
+    if let Some(period) = superposition.shortest_period(&*model) {
+        superposition.time += period / FRAMES_PER_PERIOD * superposition.speed;
+    }
+}
+
+/// updates the UI label with the superposition's time and ⟨x⟩, the one of the expectation values
+pub fn update_superposition_label(
+    superposition: Res<Superposition>,
+    values: Res<ExpectationValues>,
+    mut label_query: Query<&mut Text, With<SuperpositionLabelMarker>>,
+) {
+    if !superposition.is_changed() && !values.is_changed() {
+        return;
+    }
+    let mean_x = match values.0 {
+        Some(expectations) => format!("{:.2e} m", expectations.position),
+        None => "-".to_string(),
+    };
+    let text = format!(
+        "t: {:.2e} s\nspeed: x{}\n⟨x⟩: {}",
+        superposition.time, superposition.speed, mean_x
+    );
+    for mut label in label_query.iter_mut() {
+        label.sections[0].value.clone_from(&text);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        f64::{Length, Mass},
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use crate::{
+        expectation::Expectations,
+        harmonic_oscillator_plot::HarmonicOscillator,
+        infinite_well_plot::InfiniteWell,
+        model::{PotentialModel, H_BAR},
+        plot::PlotSettings,
+        ui::EnergyLevel,
+    };
+
+    use super::{Superposition, WeightedLevel};
+
+    fn superposition(levels: &[(u32, f64, f64)]) -> Superposition {
+        let mut superposition = Superposition::single(EnergyLevel(0));
+        superposition.levels.clear();
+        for (level, amplitude, phase) in levels {
+            superposition.set_weight(WeightedLevel {
+                level: EnergyLevel(*level),
+                amplitude: *amplitude,
+                phase: *phase,
+            });
+        }
+        superposition
+    }
+
+    #[test]
+    fn normalizes_coefficients() {
+        let model = HarmonicOscillator::default();
+        let superposition = superposition(&[(0, 0.3, 0.0), (2, 0.4, 1.0), (5, 1.0, 2.0)]);
+        let total: f64 = superposition
+            .coefficients(&model)
+            .iter()
+            .map(|(_, c)| c.norm_sqr())
+            .sum();
+        assert_relative_eq!(1.0, total, max_relative = 1e-9);
+
+        let probabilities: f64 = [0, 2, 5]
+            .iter()
+            .map(|level| superposition.probability(EnergyLevel(*level)))
+            .sum();
+        assert_relative_eq!(1.0, probabilities, max_relative = 1e-9);
+    }
+
+    #[test]
+    fn harmonic_oscillator_mean_position_oscillates() {
+        let model = HarmonicOscillator::default();
+        let settings = model.plot_settings();
+        let mut superposition = superposition(&[(0, 1.0, 0.0), (1, 1.0, 0.0)]);
+        let mean_x = |superposition: &Superposition| {
+            Expectations::of_superposition(&model, superposition, &settings)
+                .unwrap()
+                .position
+        };
+
+        // (Ψ_0 + Ψ_1)/√2 has ⟨x⟩ = sqrt(ħ/2mω) cos(ωt)
+        let mass = model.mass.get::<kilogram>();
+        let ang_freq = model.ang_freq.value;
+        let amplitude = (H_BAR / (2.0 * mass * ang_freq)).sqrt();
+        assert_relative_eq!(amplitude, mean_x(&superposition), max_relative = 1e-2);
+
+        superposition.time = std::f64::consts::PI / ang_freq;
+        assert_relative_eq!(-amplitude, mean_x(&superposition), max_relative = 1e-2);
+
+        superposition.time = std::f64::consts::PI / 2.0 / ang_freq;
+        assert!(mean_x(&superposition).abs() < amplitude * 1e-2);
+    }
+
+    #[test]
+    fn infinite_well_revives() {
+        let model = InfiniteWell {
+            width: Length::new::<nanometer>(1.0),
+            mass: Mass::new::<kilogram>(9.11e-31),
+            ..InfiniteWell::default()
+        };
+        let width = model.width.get::<meter>();
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: width,
+            sample_step: width / 200.0,
+            ..PlotSettings::default()
+        };
+        let mut superposition = superposition(&[(1, 1.0, 0.0), (2, 0.6, 0.5), (3, 0.3, 0.0)]);
+        let pdf = |superposition: &Superposition| -> Vec<f64> {
+            superposition
+                .sample(&model, &settings)
+                .iter()
+                .map(|(_, psi)| psi.norm_sqr())
+                .collect()
+        };
+        let initial = pdf(&superposition);
+        let max = initial.iter().cloned().fold(0.0, f64::max);
+
+        // T = 4mL^2/πħ
+        let revival_time =
+            4.0 * model.mass.get::<kilogram>() * width.powi(2) / (std::f64::consts::PI * H_BAR);
+
+        superposition.time = revival_time / 3.0;
+        let changed = pdf(&superposition);
+        assert!(initial
+            .iter()
+            .zip(&changed)
+            .any(|(a, b)| (a - b).abs() > max * 0.1));
+
+        superposition.time = revival_time;
+        for (a, b) in initial.iter().zip(&pdf(&superposition)) {
+            assert!((a - b).abs() < max * 1e-3);
+        }
+    }
+}
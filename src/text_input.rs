@@ -0,0 +1,361 @@
+/// focusable single line text input, used for the numeric values on the UI
+/// clicking it focuses it, typing edits the text at the caret, enter commits it with a `UiInputsEvent`
+/// and escape or clicking elsewhere restores the current value
+/// the listeners of the event parse and validate the text, errors are shown in red below the input
+use crate::{camera_controller::CameraController, ui::UiInputsEvent};
+use bevy::{
+    color::palettes::css::{DARK_GRAY, GRAY, RED, WHITE},
+    input::{
+        keyboard::{Key, KeyboardInput},
+        ButtonState,
+    },
+    prelude::*,
+};
+
+/// what a text input sets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextInputTarget {
+    EnergyLevel,
+    /// a parameter of the selected model, by index in its parameters
+    Parameter(usize),
+    /// width of a segment of the custom piecewise potential, by index
+    SegmentWidth(usize),
+    /// V of a segment of the custom piecewise potential, by index
+    SegmentPotential(usize),
+}
+
+#[derive(Component, Debug)]
+pub struct TextInput {
+    pub target: TextInputTarget,
+    /// the shown (edited) text
+    text: String,
+    /// the value of the state the input sets, restored when cancelling
+    value: String,
+    /// position of the caret, in chars
+    caret: usize,
+    focused: bool,
+    /// from the last commit
+    error: Option<String>,
+}
+
+/// the red label below a text input, with the input it belongs to
+#[derive(Component, Debug)]
+pub struct TextInputErrorLabel(pub Entity);
+
+impl TextInput {
+    pub fn new(target: TextInputTarget, value: &str) -> Self {
+        Self {
+            target,
+            text: value.to_string(),
+            value: value.to_string(),
+            caret: value.chars().count(),
+            focused: false,
+            error: None,
+        }
+    }
+
+    /// whether the value of the state changed and can be shown, i.e. the input isn't being edited
+    /// to be checked before `set_value`, to not trigger change detection every frame
+    pub fn is_outdated(&self, value: &str) -> bool {
+        !self.focused && self.value != value
+    }
+
+    /// sets the value of the state, replacing the text unless the input is being edited
+    /// the text of an invalid commit stays until the value changes
+    pub fn set_value(&mut self, value: String) {
+        if !self.focused {
+            self.text.clone_from(&value);
+            self.value = value;
+        }
+    }
+
+    /// shows an error, or clears it with None
+    pub fn set_error(&mut self, error: Option<String>) {
+        if self.error != error {
+            self.error = error;
+        }
+    }
+
+    fn focus(&mut self) {
+        self.focused = true;
+        self.caret = self.text.chars().count();
+    }
+
+    /// restores the value and removes the focus
+    fn cancel(&mut self) {
+        self.focused = false;
+        self.text.clone_from(&self.value);
+        self.error = None;
+    }
+
+    /// removes the focus and returns the text to commit
+    fn commit(&mut self) -> String {
+        self.focused = false;
+        self.text.clone()
+    }
+
+    fn byte_index(&self, caret: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(caret)
+            .map(|(i, _)| i)
+            .unwrap_or(self.text.len())
+    }
+
+    fn insert(&mut self, characters: &str) {
+        let index = self.byte_index(self.caret);
+        self.text.insert_str(index, characters);
+        self.caret += characters.chars().count();
+    }
+
+    /// removes the char before the caret
+    fn backspace(&mut self) {
+        if self.caret > 0 {
+            self.caret -= 1;
+            let index = self.byte_index(self.caret);
+            self.text.remove(index);
+        }
+    }
+
+    /// removes the char after the caret
+    fn delete(&mut self) {
+        if self.caret < self.text.chars().count() {
+            let index = self.byte_index(self.caret);
+            self.text.remove(index);
+        }
+    }
+
+    fn move_caret(&mut self, offset: isize) {
+        let len = self.text.chars().count() as isize;
+        self.caret = (self.caret as isize + offset).clamp(0, len) as usize;
+    }
+
+    /// the text with a caret while focused
+    fn display_text(&self) -> String {
+        if self.focused {
+            let mut text = self.text.clone();
+            text.insert(self.byte_index(self.caret), '|');
+            text
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+/// adds a text input with its error label below to container, returns the input
+pub fn add_text_input(
+    commands: &mut Commands,
+    container_id: Entity,
+    font: &Handle<Font>,
+    target: TextInputTarget,
+    value: &str,
+) -> Entity {
+    let input = TextInput::new(target, value);
+    let text = input.display_text();
+    let input_id = commands
+        .spawn((
+            input,
+            ButtonBundle {
+                style: Style {
+                    width: Val::Percent(90.0),
+                    height: Val::Px(24.0),
+                    align_items: AlignItems::Center,
+                    padding: UiRect::horizontal(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: DARK_GRAY.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 14.0,
+                    color: WHITE.into(),
+                },
+            ));
+        })
+        .id();
+
+    let error_id = commands
+        .spawn((
+            TextInputErrorLabel(input_id),
+            TextBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    display: Display::None,
+                    ..default()
+                },
+                text: Text::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 12.0,
+                        color: RED.into(),
+                    },
+                ),
+                ..default()
+            },
+        ))
+        .id();
+
+    commands
+        .entity(container_id)
+        .push_children(&[input_id, error_id]);
+    input_id
+}
+
+/// clicking an input focuses it, clicking anywhere else cancels the editing
+/// the camera's keys are disabled while an input has the focus, to be able to type them
+pub fn focus_text_inputs(
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut input_query: Query<(&Interaction, &mut TextInput)>,
+    mut camera_query: Query<&mut CameraController>,
+) {
+    if mouse_button_input.just_pressed(MouseButton::Left) {
+        for (interaction, mut input) in input_query.iter_mut() {
+            let pressed = *interaction == Interaction::Pressed;
+            if pressed && !input.focused {
+                input.focus();
+            } else if !pressed && input.focused {
+                input.cancel();
+            }
+        }
+    }
+
+    let editing = input_query.iter().any(|(_, input)| input.focused);
+    for mut controller in camera_query.iter_mut() {
+        if controller.enabled == editing {
+            controller.enabled = !editing;
+        }
+    }
+}
+
+/// edits the focused input with the keyboard, enter sends its text in an event
+pub fn edit_text_inputs(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut input_query: Query<(Entity, &mut TextInput)>,
+    mut my_events: EventWriter<UiInputsEvent>,
+) {
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        for (entity, mut input) in input_query.iter_mut() {
+            if !input.focused {
+                continue;
+            }
+            match &event.logical_key {
+                Key::Character(characters) => input.insert(characters),
+                Key::Backspace => input.backspace(),
+                Key::Delete => input.delete(),
+                Key::ArrowLeft => input.move_caret(-1),
+                Key::ArrowRight => input.move_caret(1),
+                Key::Home => input.move_caret(isize::MIN / 2),
+                Key::End => input.move_caret(isize::MAX / 2),
+                Key::Escape => input.cancel(),
+                Key::Enter => {
+                    let text = input.commit();
+                    my_events.send(UiInputsEvent {
+                        input: entity,
+                        target: input.target,
+                        text,
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// shows the inputs' text, caret and error
+#[allow(clippy::type_complexity)]
+pub fn update_text_input_labels(
+    mut input_query: Query<(&TextInput, &Children, &mut BackgroundColor), Changed<TextInput>>,
+    mut text_query: Query<&mut Text, Without<TextInputErrorLabel>>,
+    mut error_query: Query<(&TextInputErrorLabel, &mut Text, &mut Style)>,
+) {
+    for (input, children, mut background) in input_query.iter_mut() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = input.display_text();
+            }
+        }
+        *background = if input.focused { GRAY } else { DARK_GRAY }.into();
+    }
+    for (label, mut text, mut style) in error_query.iter_mut() {
+        let Ok((input, _, _)) = input_query.get(label.0) else {
+            continue;
+        };
+        let error = input.error.clone().unwrap_or_default();
+        style.display = if input.error.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        text.sections[0].value = error;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TextInput, TextInputTarget};
+
+    #[test]
+    fn edits_at_caret() {
+        let mut input = TextInput::new(TextInputTarget::EnergyLevel, "12");
+        input.focus();
+        input.move_caret(-1);
+        input.insert("5");
+        assert_eq!("152", input.text);
+        assert_eq!("15|2", input.display_text());
+
+        input.backspace();
+        assert_eq!("12", input.text);
+        input.delete();
+        assert_eq!("1", input.text);
+        // nothing after the caret
+        input.delete();
+        assert_eq!("1", input.text);
+
+        input.move_caret(-10);
+        input.backspace();
+        input.insert("ω");
+        input.move_caret(10);
+        input.insert("é");
+        assert_eq!("ω1é|", input.display_text());
+    }
+
+    #[test]
+    fn cancel_restores_value() {
+        let mut input = TextInput::new(TextInputTarget::Parameter(0), "1e-9");
+        input.focus();
+        input.insert("9");
+        // the state isn't shown while editing
+        input.set_value("2e-9".to_string());
+        assert_eq!("1e-99", input.text);
+
+        input.cancel();
+        assert_eq!("1e-9", input.text);
+        input.set_value("2e-9".to_string());
+        assert_eq!("2e-9", input.text);
+    }
+
+    #[test]
+    fn commit_keeps_text_until_value_changes() {
+        let mut input = TextInput::new(TextInputTarget::EnergyLevel, "1");
+        input.focus();
+        input.backspace();
+        input.insert("x");
+        assert_eq!("x", input.commit());
+        input.set_error(Some("not a number".to_string()));
+        assert_eq!("x", input.display_text());
+
+        // a valid commit updates the value, which replaces the text
+        input.set_error(None);
+        input.set_value("3".to_string());
+        assert_eq!("3", input.display_text());
+    }
+}
@@ -0,0 +1,453 @@
+/// time evolution of a wave packet in the selected model's potential
+/// the packet starts as a gaussian in the potential's minimum, with the selected level's energy as kinetic energy
+/// while active, it replaces the stationary Ψ and PDF curves, and it's advanced every frame with the selected propagator
+use crate::{
+    crank_nicolson::CrankNicolson,
+    expectation::Expectations,
+    finite_difference_solver::interior_grid,
+    model::{cusps, ModelPlotSettings, PotentialModel, H_BAR},
+    momentum::{fourier_transform, momenta},
+    plot::PlotSettings,
+    split_operator::{Boundary, SplitOperator},
+    ui::{
+        DtLabelMarker, EnergyLevel, PotentialModelInput, TimeEvolutionInput,
+        TimeEvolutionInputEvent, TimeLabelMarker,
+    },
+    wave_function_plot::{
+        setup_wave_function_curves, CurveExtents, CurveScales, CurveStyle, WaveFunctionCurve,
+    },
+};
+use bevy::{
+    prelude::*,
+    utils::{Duration, Instant},
+};
+use num_complex::Complex64;
+use uom::si::{energy::joule, f64::Length, length::meter, mass::kilogram};
+
+/// state of the time evolution, mapped from the UI controls
+#[derive(Resource)]
+pub struct TimeEvolution {
+    /// whether the wave packet is shown instead of the stationary state
+    pub active: bool,
+    pub playing: bool,
+    /// steps requested with the step button, done on the next frame
+    pub pending_steps: u32,
+    /// factor applied to the packet's default time step, changed with the dt buttons
+    pub dt_factor: f64,
+    pub propagator: PropagatorKind,
+    /// created for the selected model when needed
+    pub packet: Option<WavePacket>,
+}
+
+impl Default for TimeEvolution {
+    fn default() -> Self {
+        Self {
+            active: false,
+            playing: false,
+            pending_steps: 0,
+            dt_factor: 1.0,
+            propagator: PropagatorKind::CrankNicolson,
+            packet: None,
+        }
+    }
+}
+
+/// the propagators that can be selected on the UI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PropagatorKind {
+    /// Ψ is 0 at the domain ends, so the packet is reflected there
+    CrankNicolson,
+    SplitOperator(Boundary),
+}
+
+impl PropagatorKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PropagatorKind::CrankNicolson => "Crank-Nicolson",
+            PropagatorKind::SplitOperator(Boundary::Periodic) => "FFT periodic",
+            PropagatorKind::SplitOperator(Boundary::Absorbing) => "FFT absorbing",
+        }
+    }
+}
+
+/// advances Ψ by a fixed time step
+enum Propagator {
+    CrankNicolson(CrankNicolson),
+    SplitOperator(SplitOperator),
+}
+
+impl Propagator {
+    fn new(kind: PropagatorKind, potential: &[f64], step: f64, mass: f64, dt: f64) -> Self {
+        match kind {
+            PropagatorKind::CrankNicolson => {
+                Propagator::CrankNicolson(CrankNicolson::new(potential, step, mass, dt))
+            }
+            PropagatorKind::SplitOperator(boundary) => {
+                Propagator::SplitOperator(SplitOperator::new(potential, step, mass, dt, boundary))
+            }
+        }
+    }
+
+    fn step(&mut self, psi: &mut [Complex64]) {
+        match self {
+            Propagator::CrankNicolson(propagator) => propagator.step(psi),
+            Propagator::SplitOperator(propagator) => propagator.step(psi),
+        }
+    }
+}
+
+/// Ψ(x, t) on the plot's domain grid
+pub struct WavePacket {
+    /// x (m), without the domain ends
+    pub grid: Vec<f64>,
+    /// grid spacing (m)
+    pub step: f64,
+    pub psi: Vec<Complex64>,
+    /// the selected level's energy (J) the packet was made with, about its mean energy
+    pub energy: f64,
+    /// of the initial Ψ, kept for the curves' scale, so the packet's spreading is visible
+    pub extents: CurveExtents,
+    /// elapsed time (s)
+    pub time: f64,
+    /// time step (s), without the user's factor
+    default_dt: f64,
+    /// V (J) at the grid points
+    potential: Vec<f64>,
+    /// mass (kg)
+    mass: f64,
+    kind: PropagatorKind,
+    propagator: Propagator,
+    /// computation time of the last step, to compare propagators
+    pub step_duration: Duration,
+}
+
+impl WavePacket {
+    /// gaussian packet for a model
+    fn new<M: PotentialModel>(
+        model: &M,
+        settings: &PlotSettings,
+        level: &EnergyLevel,
+        dt_factor: f64,
+        kind: PropagatorKind,
+    ) -> Self {
+        let (grid, step) = interior_grid(settings);
+        let mut potential: Vec<f64> = grid
+            .iter()
+            .map(|x| model.potential(Length::new::<meter>(*x)).get::<joule>())
+            .collect();
+        let start = grid.first().cloned().unwrap_or(0.0);
+        for delta in model.deltas() {
+            delta.add_to_grid(&mut potential, start, step);
+        }
+        let mass = model.mass().get::<kilogram>();
+
+        let (center, min_potential) = potential_minimum(&grid, &potential);
+        let energy = model.energy(level).get::<joule>();
+        let kinetic_energy = (energy - min_potential).max(0.0);
+        let wave_number = (2.0 * mass * kinetic_energy).sqrt() / H_BAR;
+
+        // a few wave lengths wide, but small compared to the domain
+        let domain_width = step * (grid.len() + 1) as f64;
+        let max_width = domain_width / 40.0;
+        let width = if wave_number > 0.0 {
+            (2.0 / wave_number).min(max_width)
+        } else {
+            max_width
+        };
+
+        // a small fraction of the packet's phase period ħ/E, E being its kinetic or localization energy
+        let localization_energy = H_BAR / (2.0 * mass * width) * H_BAR / width;
+        let default_dt = 0.05 * H_BAR / kinetic_energy.max(localization_energy);
+
+        let psi = gaussian_wave_packet(&grid, center, width, wave_number);
+        let samples: Vec<(f64, Complex64)> =
+            grid.iter().cloned().zip(psi.iter().cloned()).collect();
+        let extents = CurveExtents::of_samples(&samples);
+        let propagator = Propagator::new(kind, &potential, step, mass, default_dt * dt_factor);
+
+        Self {
+            grid,
+            step,
+            psi,
+            energy: min_potential + kinetic_energy,
+            extents,
+            time: 0.0,
+            default_dt,
+            potential,
+            mass,
+            kind,
+            propagator,
+            step_duration: Duration::ZERO,
+        }
+    }
+
+    /// current time step (s)
+    pub fn dt(&self, dt_factor: f64) -> f64 {
+        self.default_dt * dt_factor
+    }
+
+    fn set_dt_factor(&mut self, dt_factor: f64) {
+        let dt = self.dt(dt_factor);
+        self.propagator = Propagator::new(self.kind, &self.potential, self.step, self.mass, dt);
+    }
+
+    fn advance(&mut self, dt_factor: f64) {
+        let start = Instant::now();
+        self.propagator.step(&mut self.psi);
+        self.step_duration = start.elapsed();
+        self.time += self.dt(dt_factor);
+    }
+
+    /// expectation values of the packet at its current time
+    pub fn expectations(&self) -> Option<Expectations> {
+        let start = *self.grid.first()?;
+        Expectations::new(&self.psi, start, self.step, &self.potential, self.mass)
+    }
+}
+
+/// center and value of the potential's minimum
+/// for a flat minimum (e.g. a well's bottom), the center of the first flat region
+fn potential_minimum(grid: &[f64], potential: &[f64]) -> (f64, f64) {
+    let finite = potential.iter().filter(|v| v.is_finite());
+    let min = finite.clone().cloned().fold(f64::INFINITY, f64::min);
+    let max = finite.cloned().fold(f64::NEG_INFINITY, f64::max);
+    let tolerance = (max - min) * 1e-6;
+    let is_minimum = |v: &f64| *v <= min + tolerance;
+
+    let Some(start) = potential.iter().position(is_minimum) else {
+        return (0.0, 0.0);
+    };
+    let count = potential[start..]
+        .iter()
+        .take_while(|v| is_minimum(v))
+        .count();
+    ((grid[start] + grid[start + count - 1]) / 2.0, min)
+}
+
+/// Ψ(x) = e^(-(x - x₀)^2 / 4σ^2) e^(ik₀x), normalized on the grid
+pub fn gaussian_wave_packet(
+    grid: &[f64],
+    center: f64,
+    width: f64,
+    wave_number: f64,
+) -> Vec<Complex64> {
+    let mut psi: Vec<Complex64> = grid
+        .iter()
+        .map(|x| {
+            let envelope = (-(x - center).powi(2) / (4.0 * width.powi(2))).exp();
+            Complex64::from_polar(envelope, wave_number * x)
+        })
+        .collect();
+
+    let step = if grid.len() > 1 {
+        grid[1] - grid[0]
+    } else {
+        1.0
+    };
+    let norm = (psi.iter().map(|p| p.norm_sqr()).sum::<f64>() * step).sqrt();
+    if norm > 0.0 {
+        for p in psi.iter_mut() {
+            *p /= norm;
+        }
+    }
+    psi
+}
+
+/// condition to show the stationary state's curves
+pub fn is_stationary(time_evolution: Res<TimeEvolution>) -> bool {
+    !time_evolution.active
+}
+
+/// maps the time evolution controls to state
+pub fn listen_time_evolution_ui_inputs(
+    mut events: EventReader<TimeEvolutionInputEvent>,
+    mut time_evolution: ResMut<TimeEvolution>,
+) {
+    for event in events.read() {
+        match event.input {
+            TimeEvolutionInput::Play => {
+                time_evolution.active = true;
+                time_evolution.playing = true;
+            }
+            TimeEvolutionInput::Pause => time_evolution.playing = false,
+            TimeEvolutionInput::Step => {
+                time_evolution.active = true;
+                time_evolution.playing = false;
+                time_evolution.pending_steps += 1;
+            }
+            TimeEvolutionInput::Reset => {
+                // recreated on the next frame, with the current model and level
+                time_evolution.active = true;
+                time_evolution.playing = false;
+                time_evolution.packet = None;
+            }
+            TimeEvolutionInput::DecreaseDt | TimeEvolutionInput::IncreaseDt => {
+                let factor = if event.input == TimeEvolutionInput::IncreaseDt {
+                    2.0
+                } else {
+                    0.5
+                };
+                let dt_factor = time_evolution.dt_factor * factor;
+                time_evolution.dt_factor = dt_factor;
+                if let Some(packet) = time_evolution.packet.as_mut() {
+                    packet.set_dt_factor(dt_factor);
+                }
+            }
+            TimeEvolutionInput::Propagator(kind) => {
+                // restart, to compare propagators from the same initial state
+                time_evolution.propagator = kind;
+                time_evolution.playing = false;
+                time_evolution.packet = None;
+            }
+        }
+    }
+}
+
+/// a packet belongs to the model it was created for, so it's discarded when selecting another one
+pub fn reset_time_evolution_on_model_change(
+    model: Res<PotentialModelInput>,
+    mut time_evolution: ResMut<TimeEvolution>,
+) {
+    if model.is_changed() {
+        time_evolution.active = false;
+        time_evolution.playing = false;
+        time_evolution.packet = None;
+    }
+}
+
+/// advances the packet and adds its screen curves to bevy
+/// the curves are only replaced when the packet changed, or how they're drawn
+#[allow(clippy::too_many_arguments)]
+pub fn update_wave_packet<M: PotentialModel>(
+    mut commands: Commands,
+    model: Res<M>,
+    settings: Res<ModelPlotSettings<M>>,
+    energy_level_query: Query<&EnergyLevel>,
+    mut time_evolution: ResMut<TimeEvolution>,
+    curve_query: Query<Entity, With<WaveFunctionCurve>>,
+    style: CurveStyle,
+    mut curve_scales: ResMut<CurveScales>,
+) {
+    if !time_evolution.active {
+        return;
+    }
+    // the packet's grid and potential are from the previous parameters
+    if settings.is_changed() {
+        time_evolution.packet = None;
+    }
+    let momentum_settings = &settings.momentum;
+    let settings = &settings.settings;
+    let dt_factor = time_evolution.dt_factor;
+
+    let created = time_evolution.packet.is_none();
+    if created {
+        let Some(level) = energy_level_query.iter().next() else {
+            return;
+        };
+        let kind = time_evolution.propagator;
+        time_evolution.packet = Some(WavePacket::new(&*model, settings, level, dt_factor, kind));
+    }
+
+    let mut steps = time_evolution.pending_steps;
+    if time_evolution.playing {
+        steps += 1;
+    }
+    // only written when set, to not trigger change detection every frame while paused
+    if time_evolution.pending_steps > 0 {
+        time_evolution.pending_steps = 0;
+    }
+    if !(created || steps > 0 || style.is_changed()) {
+        return;
+    }
+
+    let Some(packet) = time_evolution.packet.as_mut() else {
+        return;
+    };
+    for _ in 0..steps {
+        packet.advance(dt_factor);
+    }
+
+    if style.options.momentum {
+        let momenta = momenta(momentum_settings);
+        let start = packet.grid.first().cloned().unwrap_or(0.0);
+        let phi = fourier_transform(&packet.psi, start, packet.step, &momenta);
+        let samples: Vec<(f64, Complex64)> = momenta.into_iter().zip(phi).collect();
+        // φ of a packet in a potential changes its spread, so it's fitted at every step
+        let extents = CurveExtents::of_samples(&samples);
+        let scales = CurveScales::fit(&extents, momentum_settings, style.options.shared_scale);
+        curve_scales.set_if_neq(scales);
+        setup_wave_function_curves(
+            &mut commands,
+            &curve_query,
+            &samples,
+            &[],
+            momentum_settings,
+            &scales,
+            &style.options,
+            &style.visibility,
+            None,
+        );
+        return;
+    }
+
+    let samples: Vec<(f64, Complex64)> = packet
+        .grid
+        .iter()
+        .cloned()
+        .zip(packet.psi.iter().cloned())
+        .collect();
+    let scales = CurveScales::fit(&packet.extents, settings, style.options.shared_scale);
+    curve_scales.set_if_neq(scales);
+    let energy_offset = style
+        .options
+        .energy_offset
+        .then(|| style.energy_scale.screen_y(packet.energy));
+    setup_wave_function_curves(
+        &mut commands,
+        &curve_query,
+        &samples,
+        &cusps(&*model),
+        settings,
+        &scales,
+        &style.options,
+        &style.visibility,
+        energy_offset,
+    );
+}
+
+/// updates the UI labels with the packet's time, norm, step duration and time step
+pub fn update_time_evolution_labels(
+    time_evolution: Res<TimeEvolution>,
+    mut time_label_query: Query<&mut Text, (With<TimeLabelMarker>, Without<DtLabelMarker>)>,
+    mut dt_label_query: Query<&mut Text, (With<DtLabelMarker>, Without<TimeLabelMarker>)>,
+) {
+    if !time_evolution.is_changed() {
+        return;
+    }
+    let name = time_evolution.propagator.name();
+    let (time, dt) = match &time_evolution.packet {
+        Some(packet) => {
+            let norm = packet.psi.iter().map(|p| p.norm_sqr()).sum::<f64>() * packet.step;
+            (
+                format!(
+                    "{}\nt: {:.2e} s\nnorm: {:.6}\nstep: {:.2} ms",
+                    name,
+                    packet.time,
+                    norm,
+                    packet.step_duration.as_secs_f64() * 1000.0
+                ),
+                format!("dt: {:.2e} s", packet.dt(time_evolution.dt_factor)),
+            )
+        }
+        None => (
+            format!("{}\nt: -", name),
+            format!("dt: x{}", time_evolution.dt_factor),
+        ),
+    };
+    for mut text in time_label_query.iter_mut() {
+        text.sections[0].value.clone_from(&time);
+    }
+    for mut text in dt_label_query.iter_mut() {
+        text.sections[0].value.clone_from(&dt);
+    }
+}
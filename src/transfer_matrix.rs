@@ -0,0 +1,347 @@
+/// transfer matrices of piecewise constant potentials: segments of constant V from x = 0 on, between leads where V = 0
+/// in a segment, (Ψ, Ψ') is carried over a distance d by [[cos(qd), sin(qd)/q], [-q sin(qd), cos(qd)]],
+/// with q = √(2m(E - V))/ħ, imaginary where E < V, and the matrices of the segments multiply
+/// the waves are propagated from the right lead to the left one, where they're matched to the incident and
+/// reflected waves for scattering (E > 0), or to a decaying tail for bound states (E < 0), the poles of t
+/// (Ψ, Ψ') is rescaled at every edge, with the exponent kept apart, so thick barriers don't overflow
+use crate::{model::H_BAR, numerics::bisect, scattering::Scattering};
+use num_complex::Complex64;
+
+/// energies scanned for sign changes of the bound state condition, between V's minimum and 0
+const BOUND_STATE_SCAN_SAMPLES: usize = 4000;
+
+/// a region of constant potential
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Segment {
+    /// m
+    pub width: f64,
+    /// J
+    pub potential: f64,
+}
+
+/// (Ψ, Ψ') at a point
+type WaveState = [Complex64; 2];
+
+/// (Ψ, Ψ') at the segments' edges, from x = 0 to the end of the last segment,
+/// each as a normalized state and the ln of its scale
+struct EdgeStates {
+    edges: Vec<(WaveState, f64)>,
+}
+
+/// a potential of segments, in SI units
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewiseConstant {
+    pub segments: Vec<Segment>,
+    /// kg
+    pub mass: f64,
+}
+
+/// the scattering state of a wave e^(ikx) incident from the left, r e^(-ikx) reflected and t e^(ik(x - L)) transmitted,
+/// with L the end of the last segment
+#[derive(Debug, Clone)]
+pub struct ScatteringState {
+    pub k: Complex64,
+    pub r: Complex64,
+    pub t: Complex64,
+    /// (Ψ, Ψ') at the segments' edges
+    edges: Vec<WaveState>,
+}
+
+impl PiecewiseConstant {
+    /// x of the end of the last segment
+    pub fn length(&self) -> f64 {
+        self.segments.iter().map(|segment| segment.width).sum()
+    }
+
+    /// V(x), 0 in the leads
+    pub fn potential(&self, x: f64) -> f64 {
+        let mut start = 0.0;
+        for segment in &self.segments {
+            if x >= start && x < start + segment.width {
+                return segment.potential;
+            }
+            start += segment.width;
+        }
+        0.0
+    }
+
+    /// √(2m(E - V))/ħ, iκ with κ > 0 where E < V
+    fn wave_number(&self, energy: f64, potential: f64) -> Complex64 {
+        Complex64::new(2.0 * self.mass * (energy - potential), 0.0).sqrt() / H_BAR
+    }
+
+    /// carries (Ψ, Ψ') over a distance (negative to the left) in a region of wave number q
+    fn propagate(q: Complex64, distance: f64, state: WaveState) -> WaveState {
+        let c = (q * distance).cos();
+        // sin(qd)/q → d for q → 0
+        let s = if q.norm() * distance.abs() < 1e-8 {
+            Complex64::new(distance, 0.0)
+        } else {
+            (q * distance).sin() / q
+        };
+        [
+            c * state[0] + s * state[1],
+            -q * q * s * state[0] + c * state[1],
+        ]
+    }
+
+    /// (Ψ, Ψ') at the edges, for a state at the end of the last segment propagated to the left
+    fn edge_states(&self, energy: f64, end: WaveState) -> EdgeStates {
+        let mut edges = vec![(end, 0.0)];
+        let (mut state, mut log_scale) = (end, 0.0);
+        for segment in self.segments.iter().rev() {
+            let q = self.wave_number(energy, segment.potential);
+            state = Self::propagate(q, -segment.width, state);
+            let scale = state[0].norm().max(state[1].norm());
+            if scale > 0.0 && scale.is_finite() {
+                state = [state[0] / scale, state[1] / scale];
+                log_scale += scale.ln();
+            }
+            edges.push((state, log_scale));
+        }
+        edges.reverse();
+        EdgeStates { edges }
+    }
+
+    /// the amplitudes and edge states of the wave incident with energy E > 0, None for E <= 0
+    /// from (Ψ, Ψ')(0) = t u, with u the right lead's (1, ik) propagated to 0: 1 + r = t u₀ and ik(1 - r) = t u₁
+    pub fn scattering_state(&self, energy: f64) -> Option<ScatteringState> {
+        if energy <= 0.0 {
+            return None;
+        }
+        let k = self.wave_number(energy, 0.0);
+        let i = Complex64::i();
+        let EdgeStates { edges } = self.edge_states(energy, [1.0.into(), i * k]);
+        let (u, log_scale) = edges[0];
+        // t = 2 / (u₀ + u₁/ik), with u's scale apart
+        let denominator = u[0] + u[1] / (i * k);
+        let r = 2.0 * u[0] / denominator - 1.0;
+        let edges = edges
+            .iter()
+            .map(|(state, edge_log_scale)| {
+                let factor = 2.0 * (edge_log_scale - log_scale).exp() / denominator;
+                [factor * state[0], factor * state[1]]
+            })
+            .collect();
+        Some(ScatteringState {
+            k,
+            r,
+            t: 2.0 * (-log_scale).exp() / denominator,
+            edges,
+        })
+    }
+
+    /// T = |t|^2 and R = |r|^2, the leads being the same, None for E <= 0
+    pub fn scattering(&self, energy: f64) -> Option<Scattering> {
+        self.scattering_state(energy).map(|state| Scattering {
+            transmission: state.t.norm_sqr(),
+            reflection: state.r.norm_sqr(),
+        })
+    }
+
+    /// the condition for a bound state at E < 0, the denominator of t continued to k = iκ:
+    /// the right lead's decaying tail (1, -κ) propagated to 0 has to continue to the left lead's e^(κx), i.e. u₁ = κu₀
+    /// real, as the propagation is for E < 0, with the sign of the unscaled value
+    fn bound_state_condition(&self, energy: f64) -> f64 {
+        let kappa = (-2.0 * self.mass * energy).sqrt() / H_BAR;
+        let EdgeStates { edges } = self.edge_states(energy, [1.0.into(), (-kappa).into()]);
+        let (u, _) = edges[0];
+        u[1].re - kappa * u[0].re
+    }
+
+    /// energies (J) of the bound states, the roots of the bound state condition between V's minimum and 0, ascending
+    pub fn bound_state_energies(&self) -> Vec<f64> {
+        let min = self
+            .segments
+            .iter()
+            .map(|segment| segment.potential)
+            .fold(0.0, f64::min);
+        if min >= 0.0 {
+            return vec![];
+        }
+        let condition = |energy: f64| self.bound_state_condition(energy);
+        let step = -min / BOUND_STATE_SCAN_SAMPLES as f64;
+        let mut energies = vec![];
+        // the ends excluded, where there's no decay (0) or no oscillation (min)
+        let mut low = min + step / 2.0;
+        let mut low_value = condition(low);
+        for i in 1..BOUND_STATE_SCAN_SAMPLES {
+            let high = min + (i as f64 + 0.5) * step;
+            let high_value = condition(high);
+            if low_value == 0.0 || low_value.signum() != high_value.signum() {
+                energies.push(bisect(condition, low, high));
+            }
+            (low, low_value) = (high, high_value);
+        }
+        energies
+    }
+}
+
+impl ScatteringState {
+    /// Ψ(x) of the state, carried from the right edge of the region x is in
+    pub fn psi(&self, potential: &PiecewiseConstant, energy: f64, x: f64) -> Complex64 {
+        let i = Complex64::i();
+        if x < 0.0 {
+            return (i * self.k * x).exp() + self.r * (-i * self.k * x).exp();
+        }
+        let mut end = 0.0;
+        for (segment, edge) in potential.segments.iter().zip(&self.edges[1..]) {
+            end += segment.width;
+            if x < end {
+                let q = potential.wave_number(energy, segment.potential);
+                let psi = PiecewiseConstant::propagate(q, x - end, *edge)[0];
+                // beyond f64's range in a barrier too thick to tunnel through
+                return if psi.is_finite() { psi } else { 0.0.into() };
+            }
+        }
+        self.t * (i * self.k * (x - end)).exp()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use uom::si::{
+        energy::{electronvolt, joule},
+        f64::{Energy, Length, Mass},
+        length::{meter, nanometer},
+        mass::kilogram,
+    };
+
+    use super::{PiecewiseConstant, Segment};
+    use crate::{
+        barrier_plot::Barrier, finite_well_plot::FiniteWell, model::PotentialModel, ui::EnergyLevel,
+    };
+
+    const ELECTRON_VOLT: f64 = 1.602_176_634e-19;
+
+    #[test]
+    fn single_segment_matches_barrier() {
+        let barrier = Barrier::default();
+        let potential = PiecewiseConstant {
+            segments: vec![Segment {
+                width: barrier.width.get::<meter>(),
+                potential: barrier.height.get::<joule>(),
+            }],
+            mass: barrier.mass.get::<kilogram>(),
+        };
+        for energy in [0.1, 0.5, 1.0, 1.7, 4.0] {
+            let energy = Energy::new::<electronvolt>(energy);
+            let expected = barrier.scattering(energy).unwrap();
+            let scattering = potential.scattering(energy.get::<joule>()).unwrap();
+            assert_relative_eq!(
+                expected.transmission,
+                scattering.transmission,
+                max_relative = 1e-9
+            );
+            assert_relative_eq!(
+                1.0,
+                scattering.transmission + scattering.reflection,
+                epsilon = 1e-12
+            );
+
+            let barrier = Barrier { energy, ..barrier };
+            let state = potential.scattering_state(energy.get::<joule>()).unwrap();
+            for x in [-0.3e-9, 0.0, 0.2e-9, 0.45e-9, 0.8e-9] {
+                let psi = state.psi(&potential, energy.get::<joule>(), x);
+                let expected = barrier.psi(Length::new::<meter>(x), &EnergyLevel(0));
+                assert!((psi - expected).norm() < 1e-9 * expected.norm().max(1.0));
+            }
+        }
+    }
+
+    #[test]
+    fn split_segments_dont_change_scattering() {
+        let whole = PiecewiseConstant {
+            segments: vec![Segment {
+                width: 1e-9,
+                potential: 0.3 * ELECTRON_VOLT,
+            }],
+            mass: 9.11e-31,
+        };
+        let halves = PiecewiseConstant {
+            segments: vec![
+                Segment {
+                    width: 0.5e-9,
+                    ..whole.segments[0]
+                };
+                2
+            ],
+            ..whole.clone()
+        };
+        for energy in [0.1, 0.3, 0.6] {
+            let energy = energy * ELECTRON_VOLT;
+            assert_relative_eq!(
+                whole.scattering(energy).unwrap().transmission,
+                halves.scattering(energy).unwrap().transmission,
+                max_relative = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn double_barrier_transmits_fully_at_resonance() {
+        let barrier = Segment {
+            width: 0.5e-9,
+            potential: ELECTRON_VOLT,
+        };
+        let well = Segment {
+            width: 2e-9,
+            potential: 0.0,
+        };
+        let potential = PiecewiseConstant {
+            segments: vec![barrier, well, barrier],
+            mass: 9.11e-31,
+        };
+        // the lowest resonance, below the infinite well's ground state of 0.094 eV
+        let (energy, transmission) = (1..1000)
+            .map(|i| {
+                let energy = i as f64 * 1e-4 * ELECTRON_VOLT;
+                (energy, potential.scattering(energy).unwrap().transmission)
+            })
+            .fold(
+                (0.0, 0.0),
+                |max, value| if value.1 > max.1 { value } else { max },
+            );
+        assert!(energy < 0.094 * ELECTRON_VOLT);
+        assert!(transmission > 0.99, "T = {} at the resonance", transmission);
+        // a single barrier of the same thickness is opaque there
+        let single = PiecewiseConstant {
+            segments: vec![barrier],
+            ..potential
+        };
+        assert!(single.scattering(energy).unwrap().transmission < 0.1);
+    }
+
+    #[test]
+    fn bound_states_match_finite_well() {
+        let width = Length::new::<nanometer>(1.0);
+        let depth = Energy::new::<electronvolt>(10.0);
+        let mass = Mass::new::<kilogram>(9.11e-31);
+        let well = FiniteWell::new(width, depth, mass);
+        let potential = PiecewiseConstant {
+            segments: vec![Segment {
+                width: width.get::<meter>(),
+                potential: -depth.get::<joule>(),
+            }],
+            mass: mass.get::<kilogram>(),
+        };
+        let energies = potential.bound_state_energies();
+        assert_eq!(well.bound_state_count() as usize, energies.len());
+        for (n, energy) in energies.iter().enumerate() {
+            // the finite well's energies are from its bottom
+            let expected =
+                well.energy(&EnergyLevel(n as u32)).get::<joule>() - depth.get::<joule>();
+            assert_relative_eq!(expected, energy, max_relative = 1e-6);
+        }
+        // no wells, no bound states
+        let barrier = PiecewiseConstant {
+            segments: vec![Segment {
+                width: 1e-9,
+                potential: ELECTRON_VOLT,
+            }],
+            ..potential
+        };
+        assert!(barrier.bound_state_energies().is_empty());
+    }
+}
@@ -1,17 +1,32 @@
-use std::cmp;
-
 use bevy::{
-    color::palettes::{
-        css::{BLACK, GREEN, WHITE},
-        tailwind::GRAY_500,
-    },
-    ecs::query::QueryData,
+    color::palettes::css::{BLACK, DARK_GRAY, GREEN, WHITE},
+    input::mouse::{MouseScrollUnit, MouseWheel},
     prelude::*,
+    ui::RelativeCursorPosition,
+    window::PrimaryWindow,
 };
 
-#[derive(Event, Default, Debug)]
+use crate::{
+    axis::UI_COLUMN_WIDTH,
+    expectation::{MOMENTUM_SERIES, POSITION_SERIES},
+    infinite_well_plot::WellPlacement,
+    model::{ModelId, ModelParameter, ParameterRange, PotentialModelRegistry},
+    plot::{SeriesId, SeriesVisibility},
+    potential_plot::{ENERGY_LEVELS_SERIES, POTENTIAL_SERIES},
+    split_operator::Boundary,
+    superposition::Superposition,
+    text_input::{add_text_input, TextInput, TextInputTarget},
+    time_evolution::PropagatorKind,
+    wave_function_plot::{IMAGINARY_SERIES, PDF_SERIES, REAL_SERIES},
+};
+
+/// event triggered when committing a text input on UI
+#[derive(Event, Debug)]
 pub struct UiInputsEvent {
-    pub energy_level: String,
+    /// the text input, to show errors on
+    pub input: Entity,
+    pub target: TextInputTarget,
+    pub text: String,
 }
 
 #[derive(Resource)]
@@ -19,80 +34,587 @@ pub struct UiInputEntities {
     pub energy_level: Entity,
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
 pub struct EnergyLevel(pub u32);
 
-#[derive(Component, Default, QueryData)]
-pub struct EnergyLabelMarker;
+/// energy levels that can be selected, set from the selected model's bounds
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct EnergyLevelRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl Default for EnergyLevelRange {
+    fn default() -> Self {
+        Self { min: 0, max: 10 }
+    }
+}
+
 #[derive(Component, Default)]
 pub struct EnergyLevelPlusMarker;
 #[derive(Component, Default)]
 pub struct EnergyLevelMinusMarker;
+#[derive(Component, Default)]
+pub struct ModelInfoLabelMarker;
+#[derive(Component, Default)]
+pub struct DtLabelMarker;
+#[derive(Component, Default)]
+pub struct TimeLabelMarker;
+#[derive(Component, Default)]
+pub struct ExpectationLabelMarker;
+#[derive(Component, Default)]
+pub struct SuperpositionLabelMarker;
+#[derive(Component, Default)]
+pub struct SuperpositionRowsMarker;
+/// label with the weight of a level in the superposition
+#[derive(Component)]
+pub struct SuperpositionLevelLabelMarker(pub EnergyLevel);
+#[derive(Component, Default)]
+pub struct ParameterSlidersMarker;
+/// rows of the segment editor of the custom piecewise potential
+#[derive(Component, Default)]
+pub struct SegmentRowsMarker;
+/// the level's input and buttons, hidden for models with a continuous energy
+#[derive(Component, Default)]
+pub struct EnergyLevelControlsMarker;
+/// container of the energy's slider, for models with a continuous energy
+#[derive(Component, Default)]
+pub struct EnergyControlMarker;
+/// filled part of a parameter's slider, by index in the model's parameters
+#[derive(Component)]
+pub struct ParameterSliderFillMarker(pub usize);
+
+/// max number of levels listed in the superposition panel, to fit on screen
+const MAX_SUPERPOSITION_ROWS: u32 = 10;
+
+/// how the wave function's curves are shown, which of them is in `SeriesVisibility`
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct CurveOptions {
+    /// color |Ψ|^2 by arg Ψ
+    pub phase_coloring: bool,
+    /// Ψ drawn on the line of its energy
+    pub energy_offset: bool,
+    /// Ψ and |Ψ|^2 with one scale, instead of each fitting the plot's height
+    pub shared_scale: bool,
+    /// straight lines between the samples, instead of a bezier curve through them
+    pub polyline: bool,
+    /// φ(p) instead of Ψ(x), without V and the E_n lines
+    pub momentum: bool,
+}
+
+/// px scrolled per line of the mouse wheel
+const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
+/// the window's right column
+#[derive(Component, Default)]
+pub struct UiColumnMarker;
+
+/// the right column's content, scrolled with the mouse wheel when it's taller than the window
+#[derive(Component, Default)]
+pub struct ScrollingColumn {
+    /// px the content is moved up
+    pub offset: f32,
+}
 
 /// adds right column with ui elements to scene
-pub fn setup_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn setup_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    registry: Res<PotentialModelRegistry>,
+) {
     let font = asset_server.load("fonts/FiraMono-Medium.ttf");
 
-    let root = commands.spawn(NodeBundle {
-        style: Style {
-            position_type: PositionType::Absolute,
-            flex_direction: FlexDirection::Column,
-            top: Val::Px(0.0),
-            right: Val::Px(0.0),
-            width: Val::Px(130.0),
-            height: Val::Percent(100.0),
-            ..default()
-        },
-        background_color: BackgroundColor(Color::BLACK),
-        ..default()
-    });
-
-    let root_id = root.id();
+    let column_id = commands
+        .spawn((
+            UiColumnMarker,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    top: Val::Px(0.0),
+                    right: Val::Px(0.0),
+                    width: Val::Px(UI_COLUMN_WIDTH),
+                    height: Val::Percent(100.0),
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                background_color: BackgroundColor(Color::BLACK),
+                ..default()
+            },
+        ))
+        .id();
+    let root_id = commands
+        .spawn((
+            ScrollingColumn::default(),
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    // its own height, beyond the window's
+                    flex_shrink: 0.0,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(column_id).push_children(&[root_id]);
 
     add_header(&mut commands, root_id, &font, "Potential model:");
-    add_button(
-        &mut commands,
-        root_id,
-        &font,
-        "Infinite well",
-        InfiniteWellModelMarker,
-    );
-    add_button(
-        &mut commands,
-        root_id,
-        &font,
-        "Harmonic oscillator",
-        HarmonicOscillatorModelMarker,
-    );
+    for model in &registry.models {
+        add_button(
+            &mut commands,
+            root_id,
+            &font,
+            model.name,
+            PotentialModelMarker(model.id),
+        );
+    }
 
     add_spacer(&mut commands, root_id);
 
-    add_header(&mut commands, root_id, &font, "Energy level:");
+    add_header(&mut commands, root_id, &font, "Parameters:");
+    // the sliders are added by the selected model's systems
+    add_column(&mut commands, root_id, ParameterSlidersMarker);
+    add_header_with_marker(&mut commands, root_id, &font, "", ModelInfoLabelMarker);
+
+    add_spacer(&mut commands, root_id);
 
+    // either of them is shown, depending on the selected model
+    let level_controls_id = add_column(&mut commands, root_id, EnergyLevelControlsMarker);
+    add_header(&mut commands, level_controls_id, &font, "Energy level:");
     let init_energy_level = EnergyLevel(1);
     let energy_value_label =
-        add_energy_level_value_row(&mut commands, &font, root_id, init_energy_level);
+        add_energy_level_value_row(&mut commands, &font, level_controls_id, init_energy_level);
     commands.spawn(init_energy_level);
+    // the slider is added by the selected model's systems
+    add_column(&mut commands, root_id, EnergyControlMarker);
 
     commands.insert_resource(UiInputEntities {
         energy_level: energy_value_label,
     });
 
-    commands.insert_resource(PotentialModelInput::InfiniteWell);
+    add_spacer(&mut commands, root_id);
+
+    add_curve_toggles(&mut commands, &font, root_id);
+
+    add_spacer(&mut commands, root_id);
+
+    add_time_evolution_controls(&mut commands, &font, root_id);
+
+    add_spacer(&mut commands, root_id);
+
+    add_header(&mut commands, root_id, &font, "Expectation values:");
+    add_header_with_marker(&mut commands, root_id, &font, "-", ExpectationLabelMarker);
 
-    add_legend_box(&mut commands, &font);
+    commands.insert_resource(PotentialModelInput::default());
+
+    add_superposition_panel(&mut commands, &font);
 
     add_control_info_labels(commands, &font);
 }
 
-/// adds component to set energy level
-/// returns the label (entity) with the numeric value
+/// scrolls the right column's content with the mouse wheel, while the cursor is over it
+/// the offset is kept within the content, also when it gets shorter, e.g. with another model's sliders
+pub fn scroll_ui_column(
+    mut wheel_events: EventReader<MouseWheel>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut scrolling_query: Query<(&mut ScrollingColumn, &mut Style, &Node, &Parent)>,
+    node_query: Query<&Node>,
+) {
+    let over_column = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| {
+            let cursor = window.cursor_position()?;
+            Some(cursor.x >= window.width() - UI_COLUMN_WIDTH)
+        })
+        .unwrap_or(false);
+    let scrolled: f32 = wheel_events
+        .read()
+        .filter(|_| over_column)
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * SCROLL_LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    for (mut scrolling, mut style, node, parent) in scrolling_query.iter_mut() {
+        let visible_height = node_query
+            .get(parent.get())
+            .map(|parent| parent.size().y)
+            .unwrap_or(0.0);
+        let max_offset = (node.size().y - visible_height).max(0.0);
+        // wheel up (y > 0) scrolls to the top
+        let offset = (scrolling.offset - scrolled).clamp(0.0, max_offset);
+        if scrolling.offset != offset {
+            scrolling.offset = offset;
+            style.top = Val::Px(-offset);
+        }
+    }
+}
+
+/// adds components to set energy level
+/// returns the text input with the numeric value
 pub fn add_energy_level_value_row(
     commands: &mut Commands,
     font: &Handle<Font>,
     root_id: Entity,
     init_energy_level: EnergyLevel,
 ) -> Entity {
+    let energy_level_input = add_text_input(
+        commands,
+        root_id,
+        font,
+        TextInputTarget::EnergyLevel,
+        &init_energy_level.0.to_string(),
+    );
+
+    let row_id = add_row(commands, root_id);
+    add_square_button(commands, row_id, font, "-", EnergyLevelMinusMarker);
+    add_square_button(commands, row_id, font, "+", EnergyLevelPlusMarker);
+
+    energy_level_input
+}
+
+/// adds a label, a text input and a slider for a model's parameter to container
+/// index is the parameter's in the model's parameters
+pub fn add_parameter_slider(
+    commands: &mut Commands,
+    container_id: Entity,
+    font: &Handle<Font>,
+    index: usize,
+    parameter: &ModelParameter,
+) {
+    let label = format!("{} ({}):", parameter.name, parameter.unit);
+    add_header(commands, container_id, font, &label);
+    add_text_input(
+        commands,
+        container_id,
+        font,
+        TextInputTarget::Parameter(index),
+        &parameter_value_text(parameter),
+    );
+
+    let slider_id = commands
+        .spawn((
+            ParameterSliderMarker {
+                index,
+                range: parameter.range,
+            },
+            RelativeCursorPosition::default(),
+            ButtonBundle {
+                style: Style {
+                    width: Val::Percent(90.0),
+                    height: Val::Px(10.0),
+                    margin: UiRect {
+                        top: Val::Px(4.0),
+                        bottom: Val::Px(10.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                background_color: DARK_GRAY.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ParameterSliderFillMarker(index),
+                NodeBundle {
+                    style: Style {
+                        width: slider_fill_width(parameter),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    background_color: GREEN.into(),
+                    ..default()
+                },
+            ));
+        })
+        .id();
+    commands.entity(container_id).push_children(&[slider_id]);
+}
+
+/// adds the editor of a piecewise potential's segments to container, below the parameters
+/// returns the container of the segments' rows, see `add_segment_rows`
+pub fn add_segment_editor(
+    commands: &mut Commands,
+    container_id: Entity,
+    font: &Handle<Font>,
+) -> Entity {
+    add_header(commands, container_id, font, "Segments, w (m) and V (J):");
+    let rows_id = add_column(commands, container_id, SegmentRowsMarker);
+    add_button(
+        commands,
+        container_id,
+        font,
+        "add segment",
+        SegmentMarker(SegmentInput::Add),
+    );
+    rows_id
+}
+
+/// adds a row per segment, (width, V) in (m, J), with their text inputs and a button to remove it
+pub fn add_segment_rows(
+    commands: &mut Commands,
+    rows_id: Entity,
+    font: &Handle<Font>,
+    segments: &[(f64, f64)],
+) {
+    for (index, (width, potential)) in segments.iter().enumerate() {
+        let row_id = add_row(commands, rows_id);
+        add_header(commands, row_id, font, &format!("#{}", index + 1));
+        add_square_button(
+            commands,
+            row_id,
+            font,
+            "x",
+            SegmentMarker(SegmentInput::Remove(index)),
+        );
+        add_text_input(
+            commands,
+            rows_id,
+            font,
+            TextInputTarget::SegmentWidth(index),
+            &segment_value_text(*width),
+        );
+        add_text_input(
+            commands,
+            rows_id,
+            font,
+            TextInputTarget::SegmentPotential(index),
+            &segment_value_text(*potential),
+        );
+    }
+}
+
+/// adds the buttons to place an infinite well's walls to container, below the parameters
+pub fn add_placement_buttons(commands: &mut Commands, container_id: Entity, font: &Handle<Font>) {
+    add_header(commands, container_id, font, "Walls:");
+    add_button(
+        commands,
+        container_id,
+        font,
+        "at 0 and L",
+        WellPlacementMarker(WellPlacement::ZeroBased),
+    );
+    add_button(
+        commands,
+        container_id,
+        font,
+        "at -L/2 and L/2",
+        WellPlacementMarker(WellPlacement::Centered),
+    );
+}
+
+/// value of a segment's width or V as shown in its text input
+pub fn segment_value_text(value: f64) -> String {
+    format!("{:.3e}", value)
+}
+
+/// value of a parameter as shown in its text input
+pub fn parameter_value_text(parameter: &ModelParameter) -> String {
+    format!("{:.3e}", parameter.value)
+}
+
+/// width of the filled part of a parameter's slider, up to its value
+pub fn slider_fill_width(parameter: &ModelParameter) -> Val {
+    Val::Percent((parameter.range.fraction(parameter.value) * 100.0) as f32)
+}
+
+/// adds buttons to show or hide the wave function's curves
+fn add_curve_toggles(commands: &mut Commands, font: &Handle<Font>, root_id: Entity) {
+    add_header(commands, root_id, font, "Curves:");
+    for (label, curve) in [
+        ("Re Ψ", CurveToggle::Series(REAL_SERIES)),
+        ("Im Ψ", CurveToggle::Series(IMAGINARY_SERIES)),
+        ("|Ψ|^2", CurveToggle::Series(PDF_SERIES)),
+        ("V", CurveToggle::Series(POTENTIAL_SERIES)),
+        ("E_n", CurveToggle::Series(ENERGY_LEVELS_SERIES)),
+        ("⟨x⟩", CurveToggle::Series(POSITION_SERIES)),
+        ("⟨p⟩", CurveToggle::Series(MOMENTUM_SERIES)),
+        ("phase color", CurveToggle::PhaseColoring),
+        ("Ψ at E", CurveToggle::EnergyOffset),
+        ("shared scale", CurveToggle::SharedScale),
+        ("polyline", CurveToggle::Polyline),
+        ("φ(p)", CurveToggle::Momentum),
+    ] {
+        add_button(commands, root_id, font, label, CurveToggleMarker(curve));
+    }
+}
+
+/// adds container with the superposition controls to the left of the window, below the control info labels
+/// the level rows are added by update_superposition_rows, depending on the model's levels
+fn add_superposition_panel(commands: &mut Commands, font: &Handle<Font>) {
+    let panel_id = commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                left: Val::Px(10.0),
+                top: Val::Px(140.0),
+                width: Val::Px(280.0),
+                height: Val::Auto,
+                ..default()
+            },
+            ..default()
+        })
+        .id();
+
+    add_header(commands, panel_id, font, "Superposition:");
+    let row_id = add_row(commands, panel_id);
+    for (label, input) in [
+        ("animate", SuperpositionInput::Animate),
+        ("restart", SuperpositionInput::Restart),
+        ("slower", SuperpositionInput::Slower),
+        ("faster", SuperpositionInput::Faster),
+    ] {
+        add_button(commands, row_id, font, label, SuperpositionMarker(input));
+    }
+    add_header_with_marker(commands, panel_id, font, "", SuperpositionLabelMarker);
+    add_header(commands, panel_id, font, "n: amplitude phase |c|^2");
+
+    let rows_id = commands
+        .spawn((
+            SuperpositionRowsMarker,
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    width: Val::Percent(100.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .id();
+    commands.entity(panel_id).push_children(&[rows_id]);
+}
+
+/// replaces the superposition panel's rows with one per selectable level
+pub fn update_superposition_rows(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    range: Res<EnergyLevelRange>,
+    rows_query: Query<Entity, With<SuperpositionRowsMarker>>,
+) {
+    if !range.is_changed() {
+        return;
+    }
+    let font = asset_server.load("fonts/FiraMono-Medium.ttf");
+    let max = range.max.min(range.min + MAX_SUPERPOSITION_ROWS - 1);
+    for rows_id in rows_query.iter() {
+        commands.entity(rows_id).despawn_descendants();
+        for level in (range.min..=max).map(EnergyLevel) {
+            let row_id = add_row(&mut commands, rows_id);
+
+            let mut label = generate_button_label(&font, "");
+            label.style.width = Val::Px(140.0);
+            let label_id = commands
+                .spawn((SuperpositionLevelLabelMarker(level), label))
+                .id();
+            commands.entity(row_id).push_children(&[label_id]);
+
+            for (text, input) in [
+                (
+                    "a-",
+                    SuperpositionInput::Amplitude(level, PlusMinusInput::Minus),
+                ),
+                (
+                    "a+",
+                    SuperpositionInput::Amplitude(level, PlusMinusInput::Plus),
+                ),
+                (
+                    "φ-",
+                    SuperpositionInput::Phase(level, PlusMinusInput::Minus),
+                ),
+                ("φ+", SuperpositionInput::Phase(level, PlusMinusInput::Plus)),
+            ] {
+                add_square_button(
+                    &mut commands,
+                    row_id,
+                    &font,
+                    text,
+                    SuperpositionMarker(input),
+                );
+            }
+        }
+    }
+}
+
+/// updates the superposition panel's rows with the levels' weights
+pub fn update_superposition_level_labels(
+    superposition: Res<Superposition>,
+    mut label_query: Query<(&SuperpositionLevelLabelMarker, &mut Text)>,
+) {
+    for (marker, mut text) in label_query.iter_mut() {
+        let weight = superposition.weight(marker.0);
+        let label = format!(
+            "{:>2}: {:.1} {:.2}π {:>3.0}%",
+            marker.0 .0,
+            weight.amplitude,
+            weight.phase / std::f64::consts::PI,
+            superposition.probability(marker.0) * 100.0
+        );
+        if text.sections[0].value != label {
+            text.sections[0].value = label;
+        }
+    }
+}
+
+/// adds buttons to run the time evolution, set its time step, and labels with its state
+fn add_time_evolution_controls(commands: &mut Commands, font: &Handle<Font>, root_id: Entity) {
+    add_header(commands, root_id, font, "Time evolution:");
+    for (label, input) in [
+        ("play", TimeEvolutionInput::Play),
+        ("pause", TimeEvolutionInput::Pause),
+        ("step", TimeEvolutionInput::Step),
+        ("reset", TimeEvolutionInput::Reset),
+    ] {
+        add_button(commands, root_id, font, label, TimeEvolutionMarker(input));
+    }
+
+    add_header(commands, root_id, font, "Propagator:");
+    for kind in [
+        PropagatorKind::CrankNicolson,
+        PropagatorKind::SplitOperator(Boundary::Periodic),
+        PropagatorKind::SplitOperator(Boundary::Absorbing),
+    ] {
+        add_button(
+            commands,
+            root_id,
+            font,
+            kind.name(),
+            TimeEvolutionMarker(TimeEvolutionInput::Propagator(kind)),
+        );
+    }
+
+    add_header_with_marker(commands, root_id, font, "dt: x1", DtLabelMarker);
+    let row_id = add_row(commands, root_id);
+    add_square_button(
+        commands,
+        row_id,
+        font,
+        "-",
+        TimeEvolutionMarker(TimeEvolutionInput::DecreaseDt),
+    );
+    add_square_button(
+        commands,
+        row_id,
+        font,
+        "+",
+        TimeEvolutionMarker(TimeEvolutionInput::IncreaseDt),
+    );
+    add_header_with_marker(
+        commands,
+        root_id,
+        font,
+        "Crank-Nicolson\nt: -",
+        TimeLabelMarker,
+    );
+}
+
+/// adds a horizontal container to root, returns its entity
+fn add_row(commands: &mut Commands, root_id: Entity) -> Entity {
     let row = NodeBundle {
         style: Style {
             position_type: PositionType::Relative,
@@ -107,19 +629,25 @@ pub fn add_energy_level_value_row(
 
     let row_id = commands.spawn(row).id();
     commands.entity(root_id).push_children(&[row_id]);
+    row_id
+}
 
-    let energy_level_value_entity = add_button_label_with_marker(
-        commands,
-        row_id,
-        font,
-        &init_energy_level.0.to_string(),
-        EnergyLabelMarker,
-    );
-
-    add_square_button(commands, row_id, font, "-", EnergyLevelMinusMarker);
-    add_square_button(commands, row_id, font, "+", EnergyLevelPlusMarker);
-
-    energy_level_value_entity
+/// adds a vertical container with a marker to root, returns its entity
+fn add_column<T>(commands: &mut Commands, root_id: Entity, marker: T) -> Entity
+where
+    T: Component,
+{
+    let column = NodeBundle {
+        style: Style {
+            flex_direction: FlexDirection::Column,
+            width: Val::Percent(100.0),
+            ..default()
+        },
+        ..default()
+    };
+    let column_id = commands.spawn((marker, column)).id();
+    commands.entity(root_id).push_children(&[column_id]);
+    column_id
 }
 
 /// adds a generic vertical spacer element with fixed height
@@ -167,25 +695,6 @@ pub fn generate_header(font: &Handle<Font>, label: &str) -> TextBundle {
     }
 }
 
-/// adds a label with a given marker
-/// used for when we want to change the label dynamically
-// is this specific to buttons? needs more generic name I think
-pub fn add_button_label_with_marker<T>(
-    commands: &mut Commands,
-    row_id: Entity,
-    font: &Handle<Font>,
-    label: &str,
-    marker: T,
-) -> Entity
-where
-    T: Component,
-{
-    let label = generate_button_label(font, label);
-    let spawned_label = commands.spawn((marker, label)).id();
-    commands.entity(row_id).push_children(&[spawned_label]);
-    spawned_label
-}
-
 /// generates a text label
 /// meant to be added to a button (button-related dimensions)
 // this obviously needs improvement, we should have a button component etc..
@@ -213,65 +722,6 @@ pub fn generate_button_label(font: &Handle<Font>, label: &str) -> TextBundle {
     }
 }
 
-/// generates a text label displayed on the bottom left corner of the window
-pub fn generate_legend(font: &Handle<Font>, label: &str, color: impl Into<Color>) -> impl Bundle {
-    TextBundle {
-        style: Style {
-            position_type: PositionType::Relative,
-            left: Val::Px(0.0),
-            width: Val::Auto,
-            height: Val::Auto,
-            ..default()
-        },
-        text: Text::from_section(
-            label.to_string(),
-            TextStyle {
-                font: font.clone(),
-                font_size: 14.0,
-                color: color.into(),
-            },
-        ),
-        ..default()
-    }
-}
-
-/// adds container element and legends to bottom left corner of window
-pub fn add_legend_box(commands: &mut Commands, font: &Handle<Font>) -> Entity {
-    let row = NodeBundle {
-        style: Style {
-            position_type: PositionType::Absolute,
-            flex_direction: FlexDirection::Column,
-            left: Val::Px(10.0),
-            bottom: Val::Px(0.0),
-            width: Val::Auto,
-            height: Val::Auto,
-            ..default()
-        },
-        ..default()
-    };
-
-    let row_id = commands.spawn(row).id();
-
-    add_legend(commands, row_id, &font, "Ψ(x)", WHITE);
-    add_legend(commands, row_id, &font, "|Ψ(x)|^2", GRAY_500);
-
-    row_id
-}
-
-/// adds legend to container
-pub fn add_legend(
-    commands: &mut Commands,
-    container_id: Entity,
-    font: &Handle<Font>,
-    label: &str,
-    color: impl Into<Color>,
-) -> Entity {
-    let bundle = generate_legend(font, label, color);
-    let entity = commands.spawn(bundle).id();
-    commands.entity(container_id).push_children(&[entity]);
-    entity
-}
-
 /// adds button to container
 pub fn add_button<T>(
     commands: &mut Commands,
@@ -331,6 +781,26 @@ pub fn add_header(
     spawned_label
 }
 
+/// adds header with a given marker to container
+/// used for when we want to change the text dynamically
+pub fn add_header_with_marker<T>(
+    commands: &mut Commands,
+    container_id: Entity,
+    font: &Handle<Font>,
+    label: &str,
+    marker: T,
+) -> Entity
+where
+    T: Component,
+{
+    let label = generate_header(font, label);
+    let spawned_label = commands.spawn((marker, label)).id();
+    commands
+        .entity(container_id)
+        .push_children(&[spawned_label]);
+    spawned_label
+}
+
 /// adds a square button to container
 pub fn add_square_button<T>(
     commands: &mut Commands,
@@ -373,24 +843,34 @@ pub fn add_square_button<T>(
     commands.entity(container_id).push_children(&[button]);
 }
 
-/// processes the ui events
-/// basically, maps events to state
-// TODO error handling (show on ui)
-#[allow(clippy::too_many_arguments)]
+/// processes the energy level text input events
+/// basically, maps events to state, errors are shown on the input
 pub fn listen_ui_inputs(
     mut events: EventReader<UiInputsEvent>,
     mut commands: Commands,
     energy_level_query: Query<Entity, With<EnergyLevel>>,
+    range: Res<EnergyLevelRange>,
+    mut input_query: Query<&mut TextInput>,
 ) {
     for input in events.read() {
-        match parse_i32(&input.energy_level) {
-            Ok(i) => {
-                // ensure only 1 energy level active at a time
-                despawn_all_entities(&mut commands, &energy_level_query);
-                // spawn new level
-                commands.spawn(EnergyLevel(i));
+        if input.target != TextInputTarget::EnergyLevel {
+            continue;
+        }
+        let result = parse_i32(input.text.trim()).and_then(|i| {
+            if (range.min..=range.max).contains(&i) {
+                Ok(i)
+            } else {
+                Err(format!("not in {}..{}", range.min, range.max))
             }
-            Err(err) => println!("error: {}", err),
+        });
+        if let Ok(i) = result {
+            // ensure only 1 energy level active at a time
+            despawn_all_entities(&mut commands, &energy_level_query);
+            // spawn new level
+            commands.spawn(EnergyLevel(i));
+        }
+        if let Ok(mut text_input) = input_query.get_mut(input.input) {
+            text_input.set_error(result.err());
         }
     }
 }
@@ -403,25 +883,18 @@ pub fn parse_i32(str: &str) -> Result<u32, String> {
     }
 }
 
-/// removes all entities matching a query (1 filter)
-pub fn despawn_all_entities<T>(commands: &mut Commands, query: &Query<Entity, With<T>>)
-where
-    T: Component,
-{
-    for e in query.iter() {
-        let entity = commands.entity(e);
-        entity.despawn_recursive();
+pub fn parse_f64(str: &str) -> Result<f64, String> {
+    match str.parse::<f64>() {
+        Ok(f) if f.is_finite() => Ok(f),
+        Ok(_) => Err("Not a finite number".to_string()),
+        Err(e) => Err(format!("Failed to parse f64: {}", e)),
     }
 }
 
-/// removes all entities matching a query (2 filters)
-/// TODO refactor with despawn_all_entities? shouldn't have to add more functions for each type parameter..
-pub fn despawn_all_entities_tu<T, U>(
-    commands: &mut Commands,
-    query: &Query<Entity, (With<T>, With<U>)>,
-) where
+/// removes all entities matching a query (1 filter)
+pub fn despawn_all_entities<T>(commands: &mut Commands, query: &Query<Entity, With<T>>)
+where
     T: Component,
-    U: Component,
 {
     for e in query.iter() {
         let entity = commands.entity(e);
@@ -500,6 +973,7 @@ pub fn listen_energy_level_ui_inputs(
     mut commands: Commands,
     mut energy_level_query: Query<&EnergyLevel>,
     energy_level_entity_query: Query<Entity, With<EnergyLevel>>,
+    range: Res<EnergyLevelRange>,
 ) {
     for input in events.read() {
         for e in energy_level_query.iter_mut() {
@@ -511,10 +985,8 @@ pub fn listen_energy_level_ui_inputs(
                 PlusMinusInput::Minus => -1,
             };
             let new_i = current as i32 + increment;
-            // pressing "-" at 0 stays at 0
-            let mut new = cmp::max(0, new_i) as u32;
-            // currently no hermitian polynomials for n > 10, and this seems not needed for now anyway
-            new = cmp::min(10, new);
+            // pressing "-" at the lowest level stays there, same for "+" at the highest
+            let new = new_i.clamp(range.min as i32, range.max as i32) as u32;
 
             // ensure only one energy level at a time
             despawn_all_entities(&mut commands, &energy_level_entity_query);
@@ -527,19 +999,16 @@ pub fn listen_energy_level_ui_inputs(
 
 /// updates the UI energy level to reflect the current system entity
 pub fn update_energy_level_label(
-    mut commands: Commands,
     energy_level_query: Query<&EnergyLevel>,
     input_entities: Res<UiInputEntities>,
-    mut label_query: Query<(Entity, &mut Text), With<EnergyLabelMarker>>,
+    mut input_query: Query<&mut TextInput>,
 ) {
     // current energy level
     for energy_level in energy_level_query.iter() {
-        // find the UI label
-        let entity_id = commands.entity(input_entities.energy_level).id();
-        for (entity, mut text) in label_query.iter_mut() {
-            if entity == entity_id {
-                // update value
-                text.sections[0].value = energy_level.0.to_string();
+        if let Ok(mut input) = input_query.get_mut(input_entities.energy_level) {
+            let value = energy_level.0.to_string();
+            if input.is_outdated(&value) {
+                input.set_value(value);
             }
         }
     }
@@ -562,94 +1031,369 @@ pub struct PlusMinusInputEvent {
 
 /// state for selected model
 #[derive(Debug, Default, Clone, Copy, Resource, PartialEq)]
-pub enum PotentialModelInput {
-    #[default]
-    InfiniteWell,
-    HarmonicOscillator,
-}
+pub struct PotentialModelInput(pub ModelId);
 
 /// event triggered when selecting a model on UI
 #[derive(Event, Default, Debug)]
 pub struct PotentialModelInputEvent {
-    pub model: PotentialModelInput,
+    pub model: ModelId,
 }
 
-/// bevy marker for infinite well model button
-#[derive(Component, Default)]
-pub struct InfiniteWellModelMarker;
+/// bevy marker for a model button, with the model it selects
+#[derive(Component, Debug)]
+pub struct PotentialModelMarker(pub ModelId);
 
-/// bevy marker for harmonic oscillator model button
-#[derive(Component, Default)]
-pub struct HarmonicOscillatorModelMarker;
-
-/// handles interactions with model button
+/// handles interactions with model buttons
 /// styles button accordingly and when clicked, triggers an event with the selected input
 #[allow(clippy::type_complexity)]
-pub fn infinite_well_model_button_handler(
+pub fn potential_model_button_handler(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<InfiniteWellModelMarker>),
+        (
+            &Interaction,
+            &PotentialModelMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
     >,
     mut my_events: EventWriter<PotentialModelInputEvent>,
 ) {
-    for (interaction, mut color, mut border_color) in &mut interaction_query {
-        potential_model_button_handler(
-            (interaction, &mut color, &mut border_color),
-            &mut my_events,
-            PotentialModelInput::InfiniteWell,
-        );
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(PotentialModelInputEvent { model: marker.0 });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
+        }
     }
 }
 
-/// handles interactions with model button
-/// styles button accordingly and when clicked, triggers an event with the selected input
+/// basically maps the model selection event to state
+#[allow(clippy::too_many_arguments)]
+pub fn listen_potential_model_ui_inputs(
+    mut events: EventReader<PotentialModelInputEvent>,
+    mut model: ResMut<PotentialModelInput>,
+) {
+    for input in events.read() {
+        model.0 = input.model;
+    }
+}
+
+/// carried in the superposition event
+#[derive(Debug, Clone, Copy)]
+pub enum SuperpositionInput {
+    /// changes the amplitude of a level
+    Amplitude(EnergyLevel, PlusMinusInput),
+    /// changes the phase of a level
+    Phase(EnergyLevel, PlusMinusInput),
+    Animate,
+    Restart,
+    Slower,
+    Faster,
+}
+
+/// event triggered when clicking a superposition button on UI
+#[derive(Event, Debug)]
+pub struct SuperpositionInputEvent {
+    pub input: SuperpositionInput,
+}
+
+/// bevy marker for a superposition button, with the input it triggers
+#[derive(Component, Debug)]
+pub struct SuperpositionMarker(pub SuperpositionInput);
+
+/// handles interactions with superposition buttons
+/// styles button accordingly and when clicked, triggers an event with the input
 #[allow(clippy::type_complexity)]
-pub fn harmonic_oscillator_button_handler(
+pub fn superposition_button_handler(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &mut BorderColor),
-        (Changed<Interaction>, With<HarmonicOscillatorModelMarker>),
+        (
+            &Interaction,
+            &SuperpositionMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
     >,
-    mut my_events: EventWriter<PotentialModelInputEvent>,
+    mut my_events: EventWriter<SuperpositionInputEvent>,
 ) {
-    for (interaction, mut color, mut border_color) in &mut interaction_query {
-        potential_model_button_handler(
-            (interaction, &mut color, &mut border_color),
-            &mut my_events,
-            PotentialModelInput::HarmonicOscillator,
-        );
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(SuperpositionInputEvent { input: marker.0 });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
+        }
     }
 }
 
-/// handles interactions with model button
-/// styles button accordingly and when clicked, triggers an event with the selected input
-fn potential_model_button_handler(
-    interaction: (&Interaction, &mut BackgroundColor, &mut BorderColor),
-    my_events: &mut EventWriter<PotentialModelInputEvent>,
-    polarity: PotentialModelInput,
+/// carried in the curve toggle event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveToggle {
+    /// shows or hides a series
+    Series(SeriesId),
+    PhaseColoring,
+    EnergyOffset,
+    SharedScale,
+    Polyline,
+    Momentum,
+}
+
+/// event triggered when clicking a curve toggle button on UI
+#[derive(Event, Debug)]
+pub struct CurveToggleEvent {
+    pub curve: CurveToggle,
+}
+
+/// bevy marker for a curve toggle button, with the curve it toggles
+#[derive(Component, Debug)]
+pub struct CurveToggleMarker(pub CurveToggle);
+
+/// handles interactions with curve toggle buttons
+/// styles button accordingly and when clicked, triggers an event with the curve
+#[allow(clippy::type_complexity)]
+pub fn curve_toggle_button_handler(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &CurveToggleMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut my_events: EventWriter<CurveToggleEvent>,
 ) {
-    let (interaction, color, border_color) = interaction;
-    match *interaction {
-        Interaction::Pressed => {
-            *color = GREEN.into();
-            border_color.0 = GREEN.into();
-            my_events.send(PotentialModelInputEvent { model: polarity });
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(CurveToggleEvent { curve: marker.0 });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
         }
-        Interaction::Hovered => {}
-        Interaction::None => {
-            *color = BLACK.into();
-            border_color.0 = BLACK.into();
+    }
+}
+
+/// basically maps the curve toggle events to state
+pub fn listen_curve_toggle_ui_inputs(
+    mut events: EventReader<CurveToggleEvent>,
+    mut visibility: ResMut<SeriesVisibility>,
+    mut options: ResMut<CurveOptions>,
+) {
+    for event in events.read() {
+        match event.curve {
+            CurveToggle::Series(id) => visibility.toggle(id),
+            CurveToggle::PhaseColoring => options.phase_coloring = !options.phase_coloring,
+            CurveToggle::EnergyOffset => options.energy_offset = !options.energy_offset,
+            CurveToggle::SharedScale => options.shared_scale = !options.shared_scale,
+            CurveToggle::Polyline => options.polyline = !options.polyline,
+            CurveToggle::Momentum => options.momentum = !options.momentum,
         }
     }
 }
 
-/// basically maps the model selection event to state
-#[allow(clippy::too_many_arguments)]
-pub fn listen_potential_model_ui_inputs(
-    mut events: EventReader<PotentialModelInputEvent>,
-    mut model: ResMut<PotentialModelInput>,
+/// carried in the segment event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentInput {
+    Add,
+    /// by index
+    Remove(usize),
+}
+
+/// event triggered when clicking a segment editor button on UI
+#[derive(Event, Debug)]
+pub struct SegmentInputEvent {
+    pub input: SegmentInput,
+}
+
+/// bevy marker for a segment editor button, with the input it triggers
+#[derive(Component, Debug)]
+pub struct SegmentMarker(pub SegmentInput);
+
+/// handles interactions with segment editor buttons
+/// styles button accordingly and when clicked, triggers an event with the input
+#[allow(clippy::type_complexity)]
+pub fn segment_button_handler(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &SegmentMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut my_events: EventWriter<SegmentInputEvent>,
 ) {
-    for input in events.read() {
-        *model = input.model;
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(SegmentInputEvent { input: marker.0 });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
+        }
+    }
+}
+
+/// event triggered when clicking a well placement button on UI
+#[derive(Event, Debug)]
+pub struct WellPlacementInputEvent {
+    pub placement: WellPlacement,
+}
+
+/// bevy marker for a well placement button, with the placement it selects
+#[derive(Component, Debug)]
+pub struct WellPlacementMarker(pub WellPlacement);
+
+/// handles interactions with well placement buttons
+/// styles button accordingly and when clicked, triggers an event with the placement
+#[allow(clippy::type_complexity)]
+pub fn well_placement_button_handler(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &WellPlacementMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut my_events: EventWriter<WellPlacementInputEvent>,
+) {
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(WellPlacementInputEvent {
+                    placement: marker.0,
+                });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
+        }
+    }
+}
+
+/// carried in the time evolution event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeEvolutionInput {
+    Play,
+    Pause,
+    Step,
+    Reset,
+    IncreaseDt,
+    DecreaseDt,
+    Propagator(PropagatorKind),
+}
+
+/// event triggered when clicking a time evolution button on UI
+#[derive(Event, Debug)]
+pub struct TimeEvolutionInputEvent {
+    pub input: TimeEvolutionInput,
+}
+
+/// bevy marker for a time evolution button, with the input it triggers
+#[derive(Component, Debug)]
+pub struct TimeEvolutionMarker(pub TimeEvolutionInput);
+
+/// handles interactions with time evolution buttons
+/// styles button accordingly and when clicked, triggers an event with the input
+#[allow(clippy::type_complexity)]
+pub fn time_evolution_button_handler(
+    mut interaction_query: Query<
+        (
+            &Interaction,
+            &TimeEvolutionMarker,
+            &mut BackgroundColor,
+            &mut BorderColor,
+        ),
+        Changed<Interaction>,
+    >,
+    mut my_events: EventWriter<TimeEvolutionInputEvent>,
+) {
+    for (interaction, marker, mut color, mut border_color) in &mut interaction_query {
+        match *interaction {
+            Interaction::Pressed => {
+                *color = GREEN.into();
+                border_color.0 = GREEN.into();
+                my_events.send(TimeEvolutionInputEvent { input: marker.0 });
+            }
+            Interaction::Hovered => {}
+            Interaction::None => {
+                *color = BLACK.into();
+                border_color.0 = BLACK.into();
+            }
+        }
+    }
+}
+
+/// event triggered when dragging a parameter slider on UI
+#[derive(Event, Debug)]
+pub struct ParameterInputEvent {
+    /// the model the slider belongs to
+    pub model: ModelId,
+    /// index in the model's parameters
+    pub index: usize,
+    pub value: f64,
+}
+
+/// bevy marker for a parameter slider, with the parameter it sets
+#[derive(Component, Debug)]
+pub struct ParameterSliderMarker {
+    pub index: usize,
+    pub range: ParameterRange,
+}
+
+/// handles dragging parameter sliders
+/// while pressed, triggers an event with the value at the cursor, also when it's moved outside of the slider
+pub fn parameter_slider_handler(
+    slider_query: Query<(
+        &Interaction,
+        &RelativeCursorPosition,
+        &ParameterSliderMarker,
+    )>,
+    selected: Res<PotentialModelInput>,
+    mut my_events: EventWriter<ParameterInputEvent>,
+) {
+    for (interaction, cursor, slider) in slider_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if let Some(position) = cursor.normalized {
+            my_events.send(ParameterInputEvent {
+                model: selected.0,
+                index: slider.index,
+                value: slider.range.value(position.x as f64),
+            });
+        }
     }
 }
 
@@ -0,0 +1,222 @@
+/// numerical checks of a model's eigenstates, sampled on its plot domain:
+/// ∫|Ψ_n|^2 dx = 1, ⟨Ψ_m|Ψ_n⟩ = δ_mn, Ψ_n = 0 behind walls (where V isn't finite),
+/// and HΨ_n = E_nΨ_n, with the laplacian as finite differences
+/// the grid is the numerical solvers' one, so their states are checked at the points they were solved for
+use crate::{
+    model::{PotentialModel, H_BAR},
+    plot::PlotSettings,
+    ui::EnergyLevel,
+};
+use num_complex::Complex64;
+use uom::si::{energy::joule, f64::Length, length::meter, mass::kilogram};
+
+/// subintervals per step where V is sampled for the residual
+const POTENTIAL_SUBSAMPLES: usize = 8;
+
+/// points of the settings' domain, its ends included, with the step adjusted to divide it evenly
+pub struct Grid {
+    pub points: Vec<f64>,
+    pub step: f64,
+}
+
+impl Grid {
+    pub fn new(settings: &PlotSettings) -> Self {
+        let start = settings.domain_range_start;
+        let end = settings.domain_range_end;
+        let intervals = ((end - start) / settings.sample_step).round().max(1.0) as usize;
+        let step = (end - start) / intervals as f64;
+        let points = (0..=intervals).map(|i| start + i as f64 * step).collect();
+        Self { points, step }
+    }
+
+    fn psi<M: PotentialModel>(&self, model: &M, level: &EnergyLevel) -> Vec<Complex64> {
+        self.points
+            .iter()
+            .map(|x| model.psi(Length::new::<meter>(*x), level))
+            .collect()
+    }
+
+    fn potential<M: PotentialModel>(&self, model: &M) -> Vec<f64> {
+        self.points
+            .iter()
+            .map(|x| model.potential(Length::new::<meter>(*x)).get::<joule>())
+            .collect()
+    }
+
+    /// ⟨Ψ_m|Ψ_n⟩ = ∫ Ψ_m* Ψ_n dx
+    pub fn overlap<M: PotentialModel>(
+        &self,
+        model: &M,
+        m: &EnergyLevel,
+        n: &EnergyLevel,
+    ) -> Complex64 {
+        let psi_m = self.psi(model, m);
+        let psi_n = self.psi(model, n);
+        psi_m
+            .iter()
+            .zip(&psi_n)
+            .map(|(a, b)| a.conj() * b)
+            .sum::<Complex64>()
+            * self.step
+    }
+
+    /// ∫|Ψ_n|^2 dx
+    pub fn norm<M: PotentialModel>(&self, model: &M, level: &EnergyLevel) -> f64 {
+        self.overlap(model, level, level).re
+    }
+
+    /// ∫|Ψ_n|^2 dx where V isn't finite, 0 for a state that vanishes at the walls
+    pub fn wall_probability<M: PotentialModel>(&self, model: &M, level: &EnergyLevel) -> f64 {
+        self.psi(model, level)
+            .iter()
+            .zip(self.potential(model))
+            .filter(|(_, v)| !v.is_finite())
+            .map(|(psi, _)| psi.norm_sqr())
+            .sum::<f64>()
+            * self.step
+    }
+
+    /// V at x_j averaged with the weights 1 - |x - x_j| / step on [x_{j-1}, x_{j+1}], the ones the second difference
+    /// of Ψ averages Ψ'' with, so it matches the laplacian where V has a step (finite well) or a δ
+    fn averaged_potential<M: PotentialModel>(&self, model: &M, j: usize) -> f64 {
+        let potential = |x: f64| model.potential(Length::new::<meter>(x)).get::<joule>();
+        let samples = 2 * POTENTIAL_SUBSAMPLES;
+        let averaged = (0..samples)
+            .map(|i| {
+                // midpoints of the subintervals of [-1, 1]
+                let s = -1.0 + (i as f64 + 0.5) / POTENTIAL_SUBSAMPLES as f64;
+                (1.0 - s.abs()) * potential(self.points[j] + s * self.step)
+            })
+            .sum::<f64>()
+            / POTENTIAL_SUBSAMPLES as f64;
+        let deltas = model
+            .deltas()
+            .iter()
+            .map(|delta| {
+                let s = (delta.position.get::<meter>() - self.points[j]).abs() / self.step;
+                (1.0 - s).max(0.0) * delta.strength / self.step
+            })
+            .sum::<f64>();
+        averaged + deltas
+    }
+
+    /// |HΨ_n - E_nΨ_n| / |E_nΨ_n|, the norms over the grid's interior points away from walls
+    /// HΨ = -ħ^2/2m Ψ'' + VΨ, with Ψ'' = (Ψ_{j+1} - 2Ψ_j + Ψ_{j-1}) / step^2 and V averaged like it
+    pub fn residual<M: PotentialModel>(&self, model: &M, level: &EnergyLevel) -> f64 {
+        let psi = self.psi(model, level);
+        let energy = model.energy(level).get::<joule>();
+        let kinetic_factor = -H_BAR.powi(2) / (2.0 * model.mass().get::<kilogram>());
+
+        let mut residual = 0.0;
+        let mut reference = 0.0;
+        for j in 1..psi.len().saturating_sub(1) {
+            let v = self.averaged_potential(model, j);
+            if !v.is_finite() {
+                continue;
+            }
+            let laplacian = (psi[j + 1] - 2.0 * psi[j] + psi[j - 1]) / self.step.powi(2);
+            let h_psi = kinetic_factor * laplacian + v * psi[j];
+            residual += (h_psi - energy * psi[j]).norm_sqr();
+            reference += (energy * psi[j]).norm_sqr();
+        }
+        (residual / reference).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ops::RangeInclusive;
+
+    use super::Grid;
+    use crate::{
+        delta_well_plot::DeltaWell,
+        double_delta_well_plot::DoubleDeltaWell,
+        finite_well_plot::FiniteWell,
+        harmonic_oscillator_plot::HarmonicOscillator,
+        infinite_well_plot::{InfiniteWell, WellPlacement},
+        model::PotentialModel,
+        numerical_model::{test::double_well, SolverBackend},
+        ui::EnergyLevel,
+    };
+
+    /// checks the levels' states on the model's plot domain
+    /// the residual's tolerance depends on how fine the grid is for the levels' wave lengths
+    fn validate<M: PotentialModel>(model: &M, levels: RangeInclusive<u32>, max_residual: f64) {
+        let grid = Grid::new(&model.plot_settings());
+        let levels: Vec<EnergyLevel> = levels.map(EnergyLevel).collect();
+        for m in &levels {
+            for n in &levels {
+                let overlap = grid.overlap(model, m, n);
+                let expected = if m == n { 1.0 } else { 0.0 };
+                assert!(
+                    (overlap.re - expected).abs() < 1e-3 && overlap.im.abs() < 1e-3,
+                    "{}: ⟨Ψ_{}|Ψ_{}⟩ = {}",
+                    model.name(),
+                    m.0,
+                    n.0,
+                    overlap
+                );
+            }
+            let wall_probability = grid.wall_probability(model, m);
+            assert!(
+                wall_probability < 1e-9,
+                "{}: Ψ_{} is in the walls with probability {}",
+                model.name(),
+                m.0,
+                wall_probability
+            );
+            let residual = grid.residual(model, m);
+            assert!(
+                residual < max_residual,
+                "{}: HΨ_{} - E_{}Ψ_{} relative to E_{}Ψ_{} is {}",
+                model.name(),
+                m.0,
+                m.0,
+                m.0,
+                m.0,
+                m.0,
+                residual
+            );
+        }
+    }
+
+    #[test]
+    fn harmonic_oscillator_states_are_valid() {
+        let model = HarmonicOscillator::default();
+        validate(&model, 0..=20, 1e-3);
+        assert!(
+            (Grid::new(&model.plot_settings()).norm(&model, &EnergyLevel(300)) - 1.0).abs() < 1e-3
+        );
+    }
+
+    #[test]
+    fn infinite_well_states_are_valid() {
+        validate(&InfiniteWell::default(), 1..=10, 1e-2);
+        let centered = InfiniteWell {
+            placement: WellPlacement::Centered,
+            ..InfiniteWell::default()
+        };
+        validate(&centered, 1..=10, 1e-2);
+    }
+
+    #[test]
+    fn finite_well_states_are_valid() {
+        let model = FiniteWell::default();
+        validate(&model, 0..=model.bound_state_count() - 1, 2e-2);
+    }
+
+    #[test]
+    fn delta_wells_states_are_valid() {
+        validate(&DeltaWell::default(), 0..=0, 1e-2);
+        let model = DoubleDeltaWell::default();
+        validate(&model, 0..=model.max_level(), 1e-2);
+    }
+
+    #[test]
+    fn numerical_models_states_are_valid() {
+        for backend in [SolverBackend::FiniteDifference, SolverBackend::Numerov] {
+            let model = double_well(backend);
+            validate(&model, 0..=model.max_level(), 1e-3);
+        }
+    }
+}
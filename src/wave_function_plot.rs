@@ -0,0 +1,320 @@
+/// plots a complex wave function Ψ(x), from a model's eigenstate or the time evolution
+/// Re Ψ, Im Ψ and |Ψ|^2 are separate series that can be toggled on the UI,
+/// |Ψ|^2 can be colored by arg Ψ, with the hue going around the color wheel with the phase
+/// the curves' y scale is derived from their largest values, such that they fit the plot's screen height
+/// the series are only replaced when Ψ or how it's drawn changes, not every frame
+use crate::{
+    plot::{LineStyle, PlotSettings, Series, SeriesId, SeriesVisibility},
+    potential_plot::EnergyScale,
+    ui::{despawn_all_entities, CurveOptions, PotentialModelInput},
+};
+use bevy::{
+    color::palettes::{
+        css::{ORANGE, WHITE},
+        tailwind::GRAY_500,
+    },
+    ecs::system::SystemParam,
+    prelude::*,
+};
+use num_complex::Complex64;
+
+pub const REAL_COLOR: Srgba = WHITE;
+pub const IMAGINARY_COLOR: Srgba = ORANGE;
+pub const PDF_COLOR: Srgba = GRAY_500;
+
+pub const REAL_SERIES: SeriesId = SeriesId("Re Ψ");
+pub const IMAGINARY_SERIES: SeriesId = SeriesId("Im Ψ");
+pub const PDF_SERIES: SeriesId = SeriesId("|Ψ|^2");
+
+/// height of the curves drawn on the line of their energy, relative to the plot's scale,
+/// such that they stay around the line, as the levels are closer than the curves are high
+const ENERGY_OFFSET_CURVE_SCALE: f64 = 0.3;
+
+/// marker for the series of the shown wave function, which are replaced together
+#[derive(Component)]
+pub struct WaveFunctionCurve;
+
+/// state of how Ψ is drawn, besides its samples, a change of which replaces the series
+#[derive(SystemParam)]
+pub struct CurveStyle<'w> {
+    pub options: Res<'w, CurveOptions>,
+    pub visibility: Res<'w, SeriesVisibility>,
+    pub energy_scale: Res<'w, EnergyScale>,
+    /// the series of a model are replaced by the ones of another when selecting it
+    pub selected: Res<'w, PotentialModelInput>,
+}
+
+impl CurveStyle<'_> {
+    pub fn is_changed(&self) -> bool {
+        self.options.is_changed()
+            || self.visibility.is_changed()
+            || self.energy_scale.is_changed()
+            || self.selected.is_changed()
+    }
+}
+
+/// largest values of the curves, for their y scale
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CurveExtents {
+    /// max |Ψ|, bounds Re Ψ and Im Ψ
+    pub psi: f64,
+    /// max |Ψ|^2
+    pub pdf: f64,
+}
+
+impl CurveExtents {
+    pub fn of_samples(samples: &[(f64, Complex64)]) -> Self {
+        let psi = samples
+            .iter()
+            .map(|(_, psi)| psi.norm())
+            .fold(0.0, f64::max);
+        Self {
+            psi,
+            pdf: psi * psi,
+        }
+    }
+}
+
+/// screen y per unit of Ψ (m^-1/2) and of |Ψ|^2 (m^-1)
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct CurveScales {
+    pub psi: f64,
+    pub pdf: f64,
+}
+
+impl Default for CurveScales {
+    fn default() -> Self {
+        Self { psi: 1.0, pdf: 1.0 }
+    }
+}
+
+impl CurveScales {
+    /// fits the curves to the plot's screen height, each on its own,
+    /// or with a shared scale in units of the domain's width w: Ψ√w and |Ψ|^2 w have no unit,
+    /// such that the heights of Ψ and |Ψ|^2 can be compared
+    pub fn fit(extents: &CurveExtents, settings: &PlotSettings, shared: bool) -> Self {
+        let height = settings.screen_height;
+        let scale = |extent: f64| {
+            if extent > 0.0 && extent.is_finite() {
+                height / extent
+            } else {
+                1.0
+            }
+        };
+        if shared {
+            let width = settings.domain_range_end - settings.domain_range_start;
+            let shared_scale = scale((extents.psi * width.sqrt()).max(extents.pdf * width));
+            Self {
+                psi: shared_scale * width.sqrt(),
+                pdf: shared_scale * width,
+            }
+        } else {
+            Self {
+                psi: scale(extents.psi),
+                pdf: scale(extents.pdf),
+            }
+        }
+    }
+}
+
+/// samples (x, Ψ(x)) on the domain, with the settings' step
+pub fn sample_wave_function<F>(settings: &PlotSettings, function: F) -> Vec<(f64, Complex64)>
+where
+    F: Fn(f64) -> Complex64,
+{
+    let mut samples = vec![];
+    let mut x = settings.domain_range_start;
+    while x <= settings.domain_range_end {
+        samples.push((x, function(x)));
+        x += settings.sample_step;
+    }
+    samples
+}
+
+/// screen distance of Ψ's curves at an interval's middle from the lines between its ends, for `AdaptiveSampling`
+/// with the curves fitted to the plot's height for extents
+pub fn curve_deviation(
+    extents: &CurveExtents,
+    settings: &PlotSettings,
+) -> impl Fn(Complex64, Complex64, Complex64) -> f64 {
+    let scales = CurveScales::fit(extents, settings, false);
+    move |start, middle, end| {
+        let psi = middle - (start + end) / 2.0;
+        let pdf = middle.norm_sqr() - (start.norm_sqr() + end.norm_sqr()) / 2.0;
+        (psi.re.abs().max(psi.im.abs()) * scales.psi).max(pdf.abs() * scales.pdf)
+    }
+}
+
+/// replaces the wave function series with the visible ones for the samples, of Ψ(x) or φ(p)
+/// the curves have kinks at the samples nearest to the cusps (x of δ potentials)
+/// energy_offset is the screen y of Ψ's energy, to draw the curves on its line, None for on the x axis
+#[allow(clippy::too_many_arguments)]
+pub fn setup_wave_function_curves(
+    commands: &mut Commands,
+    curve_query: &Query<Entity, With<WaveFunctionCurve>>,
+    samples: &[(f64, Complex64)],
+    cusps: &[f64],
+    settings: &PlotSettings,
+    scales: &CurveScales,
+    options: &CurveOptions,
+    visibility: &SeriesVisibility,
+    energy_offset: Option<f64>,
+) {
+    despawn_all_entities(commands, curve_query);
+
+    let (offset_y, curve_scale) = match energy_offset {
+        Some(offset) => (offset, ENERGY_OFFSET_CURVE_SCALE),
+        None => (0.0, 1.0),
+    };
+    // not smoothed on request, as the bezier curve can overshoot near the nodes
+    let line_style = if options.polyline {
+        LineStyle::Straight
+    } else {
+        LineStyle::Smooth
+    };
+    let corners = nearest_samples(samples, cusps);
+    // the function in the labels
+    let name = if options.momentum { "φ(p)" } else { "Ψ(x)" };
+    let screen_points = |function: &dyn Fn(Complex64) -> f64, scale_y: f64| -> Vec<Vec2> {
+        samples
+            .iter()
+            .map(|(x, psi)| {
+                Vec2::new(
+                    (x * settings.screen_scale_x) as f32,
+                    (offset_y + function(*psi) * scale_y * curve_scale) as f32,
+                )
+            })
+            .collect()
+    };
+
+    if visibility.is_visible(REAL_SERIES) {
+        let points = screen_points(&|psi| psi.re, scales.psi);
+        let series = Series::new(
+            REAL_SERIES,
+            &format!("Re {}", name),
+            REAL_COLOR,
+            line_style,
+            points,
+        )
+        .with_corners(&corners);
+        commands.spawn((WaveFunctionCurve, series));
+    }
+    if visibility.is_visible(IMAGINARY_SERIES) {
+        let points = screen_points(&|psi| psi.im, scales.psi);
+        let series = Series::new(
+            IMAGINARY_SERIES,
+            &format!("Im {}", name),
+            IMAGINARY_COLOR,
+            line_style,
+            points,
+        )
+        .with_corners(&corners);
+        commands.spawn((WaveFunctionCurve, series));
+    }
+    if visibility.is_visible(PDF_SERIES) {
+        let points = screen_points(&|psi| psi.norm_sqr(), scales.pdf);
+        let series = if options.phase_coloring {
+            // not smoothed, as the color is per point
+            let colors = samples.iter().map(|(_, psi)| phase_color(*psi));
+            Series::with_point_colors(
+                PDF_SERIES,
+                &format!("|{}|^2, hue: arg {}", name, name),
+                points.into_iter().zip(colors).collect(),
+            )
+        } else {
+            Series::new(
+                PDF_SERIES,
+                &format!("|{}|^2", name),
+                PDF_COLOR,
+                line_style,
+                points,
+            )
+            .with_corners(&corners)
+        };
+        commands.spawn((WaveFunctionCurve, series));
+    }
+}
+
+/// indices of the samples nearest to the xs within the samples' range, the samples sorted by x
+fn nearest_samples(samples: &[(f64, Complex64)], xs: &[f64]) -> Vec<usize> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return vec![];
+    };
+    xs.iter()
+        .filter(|x| **x >= first.0 && **x <= last.0)
+        .map(|x| {
+            let index = samples.partition_point(|(sample_x, _)| sample_x < x);
+            if index > 0
+                && (index == samples.len() || x - samples[index - 1].0 < samples[index].0 - x)
+            {
+                index - 1
+            } else {
+                index
+            }
+        })
+        .collect()
+}
+
+/// hue from arg Ψ: red for 0, going through yellow, green, cyan (π), blue and magenta
+pub fn phase_color(psi: Complex64) -> Color {
+    let hue = psi.arg().to_degrees().rem_euclid(360.0);
+    Color::hsl(hue as f32, 1.0, 0.5)
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+    use bevy::color::{Color, Hsla};
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+
+    use super::{phase_color, CurveExtents, CurveScales};
+    use crate::plot::PlotSettings;
+
+    fn hue(color: Color) -> f32 {
+        Hsla::from(color).hue
+    }
+
+    #[test]
+    fn curves_fit_screen_height() {
+        let samples = [
+            (0.0, Complex64::new(3e4, 0.0)),
+            (1e-9, Complex64::new(-3e4, 4e4)),
+            (2e-9, Complex64::new(0.0, 0.0)),
+        ];
+        let extents = CurveExtents::of_samples(&samples);
+        assert_relative_eq!(5e4, extents.psi);
+        assert_relative_eq!(2.5e9, extents.pdf);
+
+        let settings = PlotSettings {
+            domain_range_start: 0.0,
+            domain_range_end: 4e-9,
+            ..PlotSettings::default()
+        };
+        let height = settings.screen_height;
+        let scales = CurveScales::fit(&extents, &settings, false);
+        assert_relative_eq!(height, extents.psi * scales.psi);
+        assert_relative_eq!(height, extents.pdf * scales.pdf);
+
+        // |Ψ|^2 w = 10 > Ψ√w ≈ 3.2, so |Ψ|^2 fits and Ψ is lower
+        let scales = CurveScales::fit(&extents, &settings, true);
+        assert_relative_eq!(height, extents.pdf * scales.pdf, max_relative = 1e-12);
+        assert_relative_eq!(
+            height * (4e-9_f64).sqrt() * 5e4 / 10.0,
+            extents.psi * scales.psi,
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn phase_color_goes_around_the_hue_wheel() {
+        assert_relative_eq!(0.0, hue(phase_color(Complex64::new(1.0, 0.0))));
+        assert_relative_eq!(90.0, hue(phase_color(Complex64::from_polar(2.0, PI / 2.0))));
+        assert_relative_eq!(180.0, hue(phase_color(Complex64::new(-1.0, 0.0))));
+        assert_relative_eq!(
+            270.0,
+            hue(phase_color(Complex64::from_polar(1.0, -PI / 2.0))),
+            max_relative = 1e-5
+        );
+    }
+}